@@ -2,6 +2,11 @@ fn main() {
     // Always linking openblas
     // Compiling blas just for testing is tedious -- install it on your system
     // and run this.
+    //
+    // With the `fortran` feature this crate provides the BLAS symbols itself,
+    // so openblas is not linked to avoid clashing definitions.
     println!("cargo:rerun-if-changed=build.rs");
-    println!("cargo:rustc-link-lib={}=openblas", "dylib");
+    if std::env::var_os("CARGO_FEATURE_FORTRAN").is_none() {
+        println!("cargo:rustc-link-lib={}=openblas", "dylib");
+    }
 }
@@ -88,7 +88,7 @@ fn main() {
     unsafe {
         gemm::sgemm(
             &gemm::executor::DefaultExecutor,
-            false,
+            gemm::Layout::ColMajor,
             false,
             false,
 
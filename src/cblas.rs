@@ -0,0 +1,328 @@
+use crate::executor::DefaultExecutor;
+use crate::gemm::Layout;
+use std::os::raw::c_int;
+
+pub const CBLAS_ROW_MAJOR: c_int = 101;
+pub const CBLAS_COL_MAJOR: c_int = 102;
+
+pub const CBLAS_NO_TRANS: c_int = 111;
+pub const CBLAS_TRANS: c_int = 112;
+pub const CBLAS_CONJ_TRANS: c_int = 113;
+
+fn trans(t: c_int) -> Option<bool> {
+    match t {
+        CBLAS_NO_TRANS => Some(false),
+        CBLAS_TRANS | CBLAS_CONJ_TRANS => Some(true),
+        _ => None,
+    }
+}
+
+// Reports an invalid argument like the reference CBLAS, by its position
+// counted from 1; the call that reported it then returns without effect
+pub(crate) fn cblas_xerbla(info: c_int, routine: &str) {
+    eprintln!("Parameter {} to routine {} was incorrect", info, routine);
+}
+
+// Position of the first invalid gemm argument, 0 if there is none. The
+// leading dimensions are checked against the rows (columns for RowMajor)
+// of A, B and C as stored, as the reference CBLAS does
+pub(crate) fn gemm_info(
+    order: c_int,
+    transa: c_int,
+    transb: c_int,
+    m: c_int,
+    n: c_int,
+    k: c_int,
+    lda: c_int,
+    ldb: c_int,
+    ldc: c_int,
+) -> c_int {
+    if order != CBLAS_COL_MAJOR && order != CBLAS_ROW_MAJOR {
+        return 1;
+    }
+
+    let (ta, tb) = match (trans(transa), trans(transb)) {
+        (Some(ta), Some(tb)) => (ta, tb),
+        (None, _) => return 2,
+        (_, None) => return 3,
+    };
+
+    // the extent of a line of A, B and C along their leading dimension
+    let (a, b, c) = if order == CBLAS_COL_MAJOR {
+        (if ta { k } else { m }, if tb { n } else { k }, m)
+    } else {
+        (if ta { m } else { k }, if tb { k } else { n }, n)
+    };
+
+    if m < 0 {
+        4
+    } else if n < 0 {
+        5
+    } else if k < 0 {
+        6
+    } else if lda < a.max(1) {
+        9
+    } else if ldb < b.max(1) {
+        11
+    } else if ldc < c.max(1) {
+        14
+    } else {
+        0
+    }
+}
+
+macro_rules! cblas_gemm {
+    ($name: ident, $gemm: path, $t: ty) => {
+        #[no_mangle]
+        pub unsafe extern "C" fn $name(
+            order: c_int,
+            transa: c_int,
+            transb: c_int,
+            m: c_int,
+            n: c_int,
+            k: c_int,
+            alpha: $t,
+            a: *const $t,
+            lda: c_int,
+            b: *const $t,
+            ldb: c_int,
+            beta: $t,
+            c: *mut $t,
+            ldc: c_int,
+        ) {
+            let info = gemm_info(order, transa, transb, m, n, k, lda, ldb, ldc);
+            if info != 0 {
+                cblas_xerbla(info, stringify!($name));
+                return;
+            }
+
+            let layout = if order == CBLAS_COL_MAJOR {
+                Layout::ColMajor
+            } else {
+                Layout::RowMajor
+            };
+            let (transa, transb) = (transa != CBLAS_NO_TRANS, transb != CBLAS_NO_TRANS);
+            let (m, n, k) = (m as usize, n as usize, k as usize);
+            let (lda, ldb, ldc) = (lda as usize, ldb as usize, ldc as usize);
+
+            $gemm(
+                &DefaultExecutor,
+                layout,
+                transa,
+                transb,
+                m,
+                n,
+                k,
+                alpha,
+                a,
+                lda,
+                b,
+                ldb,
+                beta,
+                c,
+                ldc,
+            );
+        }
+    };
+}
+
+cblas_gemm!(cblas_sgemm, crate::sgemm, f32);
+cblas_gemm!(cblas_dgemm, crate::dgemm, f64);
+
+// BLAS walks a vector with a negative increment from its far end;
+// returns the pointer to the first visited element and the step
+unsafe fn start<T>(x: *const T, n: usize, inc: c_int) -> (*const T, isize) {
+    if inc < 0 {
+        (x.offset((n as isize - 1) * -(inc as isize)), inc as isize)
+    } else {
+        (x, inc as isize)
+    }
+}
+
+macro_rules! cblas_l1 {
+    ($t: ty,
+     $rot: ident => $rot_impl: path,
+     $swap: ident => $swap_impl: path,
+     $scal: ident => $scal_impl: path,
+     $copy: ident => $copy_impl: path,
+     $axpy: ident => $axpy_impl: path,
+     $dot: ident => $dot_impl: path,
+     $nrm2: ident => $nrm2_impl: path,
+     $asum: ident => $asum_impl: path) => {
+        #[no_mangle]
+        pub unsafe extern "C" fn $rot(
+            n: c_int,
+            x: *mut $t,
+            incx: c_int,
+            y: *mut $t,
+            incy: c_int,
+            c: $t,
+            s: $t,
+        ) {
+            if n <= 0 {
+                return;
+            }
+            let n = n as usize;
+
+            if incx >= 0 && incy >= 0 {
+                $rot_impl(n, x, incx as usize, y, incy as usize, c, s);
+            } else {
+                let (x, incx) = start(x, n, incx);
+                let (y, incy) = start(y, n, incy);
+                let (x, y) = (x as *mut $t, y as *mut $t);
+
+                for i in 0..n as isize {
+                    let (xi, yi) = (x.offset(i * incx), y.offset(i * incy));
+                    let (x0, y0) = (*xi, *yi);
+
+                    *xi = c * x0 + s * y0;
+                    *yi = c * y0 - s * x0;
+                }
+            }
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $swap(n: c_int, x: *mut $t, incx: c_int, y: *mut $t, incy: c_int) {
+            if n <= 0 {
+                return;
+            }
+            let n = n as usize;
+
+            if incx >= 0 && incy >= 0 {
+                $swap_impl(n, x, incx as usize, y, incy as usize);
+            } else {
+                let (x, incx) = start(x, n, incx);
+                let (y, incy) = start(y, n, incy);
+
+                for i in 0..n as isize {
+                    std::ptr::swap(x.offset(i * incx) as *mut $t, y.offset(i * incy) as *mut $t);
+                }
+            }
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $scal(n: c_int, alpha: $t, x: *mut $t, incx: c_int) {
+            if n <= 0 || incx <= 0 {
+                return;
+            }
+
+            $scal_impl(n as usize, alpha, x, incx as usize);
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $copy(
+            n: c_int,
+            x: *const $t,
+            incx: c_int,
+            y: *mut $t,
+            incy: c_int,
+        ) {
+            if n <= 0 {
+                return;
+            }
+            let n = n as usize;
+
+            if incx >= 0 && incy >= 0 {
+                $copy_impl(n, x, incx as usize, y, incy as usize);
+            } else {
+                let (x, incx) = start(x, n, incx);
+                let (y, incy) = start(y, n, incy);
+
+                for i in 0..n as isize {
+                    *(y.offset(i * incy) as *mut $t) = *x.offset(i * incx);
+                }
+            }
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $axpy(
+            n: c_int,
+            alpha: $t,
+            x: *const $t,
+            incx: c_int,
+            y: *mut $t,
+            incy: c_int,
+        ) {
+            if n <= 0 {
+                return;
+            }
+            let n = n as usize;
+
+            if incx >= 0 && incy >= 0 {
+                $axpy_impl(n, alpha, x, incx as usize, y, incy as usize);
+            } else {
+                let (x, incx) = start(x, n, incx);
+                let (y, incy) = start(y, n, incy);
+
+                for i in 0..n as isize {
+                    *(y.offset(i * incy) as *mut $t) += alpha * *x.offset(i * incx);
+                }
+            }
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $dot(
+            n: c_int,
+            x: *const $t,
+            incx: c_int,
+            y: *const $t,
+            incy: c_int,
+        ) -> $t {
+            if n <= 0 {
+                return 0.0;
+            }
+            let n = n as usize;
+
+            if incx >= 0 && incy >= 0 {
+                $dot_impl(n, x, incx as usize, y, incy as usize)
+            } else {
+                let (x, incx) = start(x, n, incx);
+                let (y, incy) = start(y, n, incy);
+
+                let mut acc = 0.0;
+                for i in 0..n as isize {
+                    acc += *x.offset(i * incx) * *y.offset(i * incy);
+                }
+                acc
+            }
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $nrm2(n: c_int, x: *const $t, incx: c_int) -> $t {
+            if n <= 0 || incx <= 0 {
+                return 0.0;
+            }
+
+            $nrm2_impl(n as usize, x, incx as usize)
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $asum(n: c_int, x: *const $t, incx: c_int) -> $t {
+            if n <= 0 || incx <= 0 {
+                return 0.0;
+            }
+
+            $asum_impl(n as usize, x, incx as usize)
+        }
+    };
+}
+
+cblas_l1!(f32,
+    cblas_srot => crate::srot,
+    cblas_sswap => crate::sswap,
+    cblas_sscal => crate::sscal,
+    cblas_scopy => crate::scopy,
+    cblas_saxpy => crate::saxpy,
+    cblas_sdot => crate::sdot,
+    cblas_snrm2 => crate::snrm2,
+    cblas_sasum => crate::sasum);
+
+cblas_l1!(f64,
+    cblas_drot => crate::drot,
+    cblas_dswap => crate::dswap,
+    cblas_dscal => crate::dscal,
+    cblas_dcopy => crate::dcopy,
+    cblas_daxpy => crate::daxpy,
+    cblas_ddot => crate::ddot,
+    cblas_dnrm2 => crate::dnrm2,
+    cblas_dasum => crate::dasum);
@@ -0,0 +1,57 @@
+use crate::complex::Complex;
+use crate::dim::*;
+use crate::error::GemmError;
+use crate::executor::Executor;
+use crate::gemm::{Layout, Transpose};
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+use crate::kernel::avx::AvxKernel;
+use crate::kernel::generic::GenericKernel;
+use crate::kernel::isa::Isa;
+use crate::kernel::{params, Blocking};
+
+pub unsafe fn cgemm<E: Executor>(
+    e: &E,
+    layout: Layout,
+    transa: Transpose,
+    transb: Transpose,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: Complex<f32>,
+    a: *const Complex<f32>,
+    lda: usize,
+    b: *const Complex<f32>,
+    ldb: usize,
+    beta: Complex<f32>,
+    c: *mut Complex<f32>,
+    ldc: usize,
+) {
+    match params::complex_single::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => {
+            crate::gemm::gemm_conj::<E, Complex<f32>, AvxKernel<Complex<f32>, GenericKernel>, A8, A4>(
+                e, layout, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc,
+            )
+        }
+
+        _ => crate::gemm::gemm_conj::<E, Complex<f32>, GenericKernel, A8, A4>(
+            e, layout, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc,
+        ),
+    }
+}
+
+pub fn cgemm_blocking() -> Blocking {
+    params::complex_single::blocking()
+}
+
+pub fn cgemm_set_blocking(blocking: Blocking) -> Result<(), GemmError> {
+    params::complex_single::set_blocking(blocking)
+}
+
+pub fn cgemm_isa() -> Isa {
+    params::complex_single::isa()
+}
+
+pub fn cgemm_set_isa(isa: Isa) -> Result<(), GemmError> {
+    params::complex_single::set_isa(isa)
+}
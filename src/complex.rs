@@ -0,0 +1,91 @@
+use crate::matrix::Number;
+use core::fmt;
+use core::ops::{Add, Mul, Neg, Sub};
+
+/// Complex number laid out as `[re, im]`, the layout of C and Fortran
+/// complex types.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Complex<F> {
+    pub re: F,
+    pub im: F,
+}
+
+impl<F> Complex<F> {
+    pub const fn new(re: F, im: F) -> Self {
+        Complex { re, im }
+    }
+}
+
+macro_rules! complex {
+    ($t: ty) => {
+        impl Complex<$t> {
+            #[inline]
+            pub fn norm_sqr(self) -> $t {
+                self.re * self.re + self.im * self.im
+            }
+        }
+
+        impl Add for Complex<$t> {
+            type Output = Self;
+
+            #[inline]
+            fn add(self, rhs: Self) -> Self {
+                Complex::new(self.re + rhs.re, self.im + rhs.im)
+            }
+        }
+
+        impl Sub for Complex<$t> {
+            type Output = Self;
+
+            #[inline]
+            fn sub(self, rhs: Self) -> Self {
+                Complex::new(self.re - rhs.re, self.im - rhs.im)
+            }
+        }
+
+        impl Mul for Complex<$t> {
+            type Output = Self;
+
+            #[inline]
+            fn mul(self, rhs: Self) -> Self {
+                Complex::new(
+                    self.re * rhs.re - self.im * rhs.im,
+                    self.re * rhs.im + self.im * rhs.re,
+                )
+            }
+        }
+
+        impl Neg for Complex<$t> {
+            type Output = Self;
+
+            #[inline]
+            fn neg(self) -> Self {
+                Complex::new(-self.re, -self.im)
+            }
+        }
+
+        impl fmt::Display for Complex<$t> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                if self.im < 0.0 {
+                    write!(f, "{}-{}i", self.re, -self.im)
+                } else {
+                    write!(f, "{}+{}i", self.re, self.im)
+                }
+            }
+        }
+
+        impl Number for Complex<$t> {
+            const ZERO: Self = Complex::new(0.0, 0.0);
+            const ONE: Self = Complex::new(1.0, 0.0);
+
+            #[inline]
+            fn conj(self) -> Self {
+                Complex::new(self.re, -self.im)
+            }
+        }
+    };
+}
+
+complex!(f32);
+complex!(f64);
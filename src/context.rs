@@ -0,0 +1,160 @@
+use crate::aligned_alloc;
+use crate::dim::Dim;
+use crate::error::GemmError;
+use crate::executor::Executor;
+use crate::gemm::{gemm_problem, packing_lens, GemmProblem, Layout};
+use crate::kernel::{Blocking, GemmKernel};
+use crate::matrix::Number;
+
+enum Workspace<'a, F> {
+    Owned(aligned_alloc::Alloc),
+    Borrowed(&'a mut [F]),
+}
+
+/// Packing workspace for gemm calls. Multiplies through a context take their
+/// packing buffers from it and fail with `GemmError::WorkspaceTooSmall` when it
+/// is too small. With the `DefaultExecutor` they never allocate, other
+/// executors may allocate to schedule their work.
+pub struct GemmContext<'a, F: Number> {
+    workspace: Workspace<'a, F>,
+    len: usize,
+}
+
+impl<F: Number> GemmContext<'static, F> {
+    /// Allocates a workspace of `len` elements, see [`workspace_size`].
+    pub fn new(len: usize) -> Self {
+        let size = std::cmp::max(len, 1) * std::mem::size_of::<F>();
+
+        GemmContext {
+            workspace: Workspace::Owned(aligned_alloc::Alloc::new(size)),
+            len,
+        }
+    }
+}
+
+impl<'a, F: Number> GemmContext<'a, F> {
+    /// Uses a caller-provided buffer as the workspace.
+    pub fn from_buffer(buf: &'a mut [F]) -> Self {
+        GemmContext {
+            len: buf.len(),
+            workspace: Workspace::Borrowed(buf),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // First element of the workspace on a 32-byte boundary
+    fn aligned_ptr(&mut self) -> *mut F {
+        match self.workspace {
+            Workspace::Owned(ref alloc) => alloc.ptr(),
+            Workspace::Borrowed(ref mut buf) => {
+                let offset = std::cmp::min(buf.as_mut_ptr().align_offset(32), buf.len());
+
+                buf[offset..].as_mut_ptr()
+            }
+        }
+    }
+}
+
+/// Number of elements of workspace a gemm of the given shape needs. It covers
+/// aligning a caller-provided buffer, so it holds for any buffer start.
+pub fn workspace_size<F, K, MR, NR>(layout: Layout, m: usize, n: usize, k: usize) -> usize
+where
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+{
+    required_size::<F>(K::blocking(), layout, m, n, k)
+}
+
+fn required_size<F: Number>(
+    blocking: Blocking,
+    layout: Layout,
+    m: usize,
+    n: usize,
+    k: usize,
+) -> usize {
+    if m == 0 || n == 0 || k == 0 {
+        return 0;
+    }
+
+    let (pa_len, pb_len) = match layout {
+        Layout::ColMajor => packing_lens::<F>(blocking, m, n, k),
+        Layout::RowMajor => packing_lens::<F>(blocking, n, m, k),
+    };
+
+    pa_len + pb_len + 32 / std::mem::size_of::<F>() - 1
+}
+
+pub unsafe fn gemm_with_context<E, F, K, MR, NR>(
+    ctx: &mut GemmContext<F>,
+    e: &E,
+    layout: Layout,
+    transa: bool,
+    transb: bool,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: F,
+    a: *const F,
+    lda: usize,
+    b: *const F,
+    ldb: usize,
+    beta: F,
+    c: *mut F,
+    ldc: usize,
+) -> Result<(), GemmError>
+where
+    E: Executor,
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+{
+    let blocking = K::blocking();
+    let required = required_size::<F>(blocking, layout, m, n, k);
+    if ctx.len < required {
+        return Err(GemmError::WorkspaceTooSmall {
+            len: ctx.len,
+            required,
+        });
+    }
+
+    let problem = GemmProblem {
+        layout,
+        transa,
+        transb,
+        m,
+        n,
+        k,
+        alpha,
+        a,
+        lda,
+        b,
+        ldb,
+        beta,
+        c,
+        ldc,
+    };
+
+    // empty products and k == 0 never touch the workspace
+    let (pa, pb) = if required == 0 {
+        (std::ptr::null_mut(), std::ptr::null_mut())
+    } else {
+        let pa = ctx.aligned_ptr();
+        let (pa_len, _) = problem.packing_lens(blocking);
+
+        (pa, pa.add(pa_len))
+    };
+
+    gemm_problem::<E, F, K, MR, NR>(e, &problem, blocking, pa, pb);
+
+    Ok(())
+}
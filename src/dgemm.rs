@@ -0,0 +1,350 @@
+use crate::context::GemmContext;
+use crate::dim::*;
+use crate::epilogue::Epilogue;
+use crate::error::GemmError;
+use crate::executor::Executor;
+use crate::gemm::{Diag, GemmProblem, Layout, Side, Uplo};
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+use crate::kernel::avx::AvxKernel;
+use crate::kernel::generic::GenericKernel;
+use crate::kernel::isa::Isa;
+use crate::kernel::{params, Blocking};
+use crate::packed::{PackedA, PackedB};
+
+pub unsafe fn dgemm<E: Executor>(
+    e: &E,
+    layout: Layout,
+    transa: bool,
+    transb: bool,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: f64,
+    a: *const f64,
+    lda: usize,
+    b: *const f64,
+    ldb: usize,
+    beta: f64,
+    c: *mut f64,
+    ldc: usize,
+) {
+    match params::double::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => crate::gemm::gemm::<E, f64, AvxKernel<f64, GenericKernel>, A8, A4>(
+            e, layout, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc,
+        ),
+
+        _ => crate::gemm::gemm::<E, f64, GenericKernel, A8, A4>(
+            e, layout, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc,
+        ),
+    }
+}
+
+/// dgemm with `epilogue` applied to every element of C, see
+/// [`Fused`](crate::epilogue::Fused)
+pub unsafe fn dgemm_epilogue<E: Executor, P: Epilogue<f64>>(
+    e: &E,
+    layout: Layout,
+    transa: bool,
+    transb: bool,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: f64,
+    a: *const f64,
+    lda: usize,
+    b: *const f64,
+    ldb: usize,
+    beta: f64,
+    c: *mut f64,
+    ldc: usize,
+    epilogue: P,
+) {
+    match params::double::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => crate::gemm::gemm_epilogue::<E, f64, AvxKernel<f64, GenericKernel>, A8, A4, P>(
+            e, layout, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc, epilogue,
+        ),
+
+        _ => crate::gemm::gemm_epilogue::<E, f64, GenericKernel, A8, A4, P>(
+            e, layout, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc, epilogue,
+        ),
+    }
+}
+
+/// Solves op(A) * X = alpha * B or X * op(A) = alpha * B of a triangular A,
+/// X overwrites B
+pub unsafe fn dtrsm<E: Executor>(
+    e: &E,
+    layout: Layout,
+    side: Side,
+    uplo: Uplo,
+    trans: bool,
+    diag: Diag,
+    m: usize,
+    n: usize,
+    alpha: f64,
+    a: *const f64,
+    lda: usize,
+    b: *mut f64,
+    ldb: usize,
+) {
+    match params::double::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => crate::trsm::trsm::<E, f64, AvxKernel<f64, GenericKernel>, A8, A4>(
+            e, layout, side, uplo, trans, diag, m, n, alpha, a, lda, b, ldb,
+        ),
+
+        _ => crate::trsm::trsm::<E, f64, GenericKernel, A8, A4>(
+            e, layout, side, uplo, trans, diag, m, n, alpha, a, lda, b, ldb,
+        ),
+    }
+}
+
+/// B = alpha * op(A) * B or B = alpha * B * op(A) of a triangular A
+pub unsafe fn dtrmm<E: Executor>(
+    e: &E,
+    layout: Layout,
+    side: Side,
+    uplo: Uplo,
+    trans: bool,
+    diag: Diag,
+    m: usize,
+    n: usize,
+    alpha: f64,
+    a: *const f64,
+    lda: usize,
+    b: *mut f64,
+    ldb: usize,
+) {
+    match params::double::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => crate::trmm::trmm::<E, f64, AvxKernel<f64, GenericKernel>, A8, A4>(
+            e, layout, side, uplo, trans, diag, m, n, alpha, a, lda, b, ldb,
+        ),
+
+        _ => crate::trmm::trmm::<E, f64, GenericKernel, A8, A4>(
+            e, layout, side, uplo, trans, diag, m, n, alpha, a, lda, b, ldb,
+        ),
+    }
+}
+
+pub unsafe fn dgemm_strided<E: Executor>(
+    e: &E,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: f64,
+    a: *const f64,
+    rsa: usize,
+    csa: usize,
+    b: *const f64,
+    rsb: usize,
+    csb: usize,
+    beta: f64,
+    c: *mut f64,
+    rsc: usize,
+    csc: usize,
+) {
+    match params::double::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => crate::gemm::gemm_strided::<E, f64, AvxKernel<f64, GenericKernel>, A8, A4>(
+            e, m, n, k, alpha, a, rsa, csa, b, rsb, csb, beta, c, rsc, csc,
+        ),
+
+        _ => crate::gemm::gemm_strided::<E, f64, GenericKernel, A8, A4>(
+            e, m, n, k, alpha, a, rsa, csa, b, rsb, csb, beta, c, rsc, csc,
+        ),
+    }
+}
+
+pub unsafe fn dgemm_batch_strided<E: Executor>(
+    e: &E,
+    layout: Layout,
+    transa: bool,
+    transb: bool,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: f64,
+    a: *const f64,
+    lda: usize,
+    stride_a: usize,
+    b: *const f64,
+    ldb: usize,
+    stride_b: usize,
+    beta: f64,
+    c: *mut f64,
+    ldc: usize,
+    stride_c: usize,
+    batch: usize,
+) {
+    match params::double::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => {
+            crate::gemm::gemm_batch_strided::<E, f64, AvxKernel<f64, GenericKernel>, A8, A4>(
+                e, layout, transa, transb, m, n, k, alpha, a, lda, stride_a, b, ldb, stride_b,
+                beta, c, ldc, stride_c, batch,
+            )
+        }
+
+        _ => crate::gemm::gemm_batch_strided::<E, f64, GenericKernel, A8, A4>(
+            e, layout, transa, transb, m, n, k, alpha, a, lda, stride_a, b, ldb, stride_b, beta, c,
+            ldc, stride_c, batch,
+        ),
+    }
+}
+
+pub unsafe fn dgemm_grouped<E: Executor>(e: &E, problems: &[GemmProblem<f64>]) {
+    match params::double::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => {
+            crate::gemm::gemm_grouped::<E, f64, AvxKernel<f64, GenericKernel>, A8, A4>(e, problems)
+        }
+
+        _ => crate::gemm::gemm_grouped::<E, f64, GenericKernel, A8, A4>(e, problems),
+    }
+}
+
+pub unsafe fn dgemm_pack_a<E: Executor>(
+    e: &E,
+    layout: Layout,
+    transa: bool,
+    m: usize,
+    k: usize,
+    a: *const f64,
+    lda: usize,
+) -> PackedA<f64> {
+    match params::double::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => crate::packed::pack_a::<E, f64, AvxKernel<f64, GenericKernel>, A8, A4>(
+            e, layout, transa, m, k, a, lda,
+        ),
+
+        _ => {
+            crate::packed::pack_a::<E, f64, GenericKernel, A8, A4>(e, layout, transa, m, k, a, lda)
+        }
+    }
+}
+
+pub unsafe fn dgemm_pack_b<E: Executor>(
+    e: &E,
+    layout: Layout,
+    transb: bool,
+    k: usize,
+    n: usize,
+    b: *const f64,
+    ldb: usize,
+) -> PackedB<f64> {
+    match params::double::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => crate::packed::pack_b::<E, f64, AvxKernel<f64, GenericKernel>, A8, A4>(
+            e, layout, transb, k, n, b, ldb,
+        ),
+
+        _ => {
+            crate::packed::pack_b::<E, f64, GenericKernel, A8, A4>(e, layout, transb, k, n, b, ldb)
+        }
+    }
+}
+
+pub unsafe fn dgemm_packed_a<E: Executor>(
+    e: &E,
+    transb: bool,
+    n: usize,
+    alpha: f64,
+    a: &PackedA<f64>,
+    b: *const f64,
+    ldb: usize,
+    beta: f64,
+    c: *mut f64,
+    ldc: usize,
+) -> Result<(), GemmError> {
+    match params::double::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => crate::packed::gemm_packed_a::<E, f64, AvxKernel<f64, GenericKernel>, A8, A4>(
+            e, transb, n, alpha, a, b, ldb, beta, c, ldc,
+        ),
+
+        _ => crate::packed::gemm_packed_a::<E, f64, GenericKernel, A8, A4>(
+            e, transb, n, alpha, a, b, ldb, beta, c, ldc,
+        ),
+    }
+}
+
+pub unsafe fn dgemm_packed_b<E: Executor>(
+    e: &E,
+    transa: bool,
+    m: usize,
+    alpha: f64,
+    a: *const f64,
+    lda: usize,
+    b: &PackedB<f64>,
+    beta: f64,
+    c: *mut f64,
+    ldc: usize,
+) -> Result<(), GemmError> {
+    match params::double::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => crate::packed::gemm_packed_b::<E, f64, AvxKernel<f64, GenericKernel>, A8, A4>(
+            e, transa, m, alpha, a, lda, b, beta, c, ldc,
+        ),
+
+        _ => crate::packed::gemm_packed_b::<E, f64, GenericKernel, A8, A4>(
+            e, transa, m, alpha, a, lda, b, beta, c, ldc,
+        ),
+    }
+}
+
+// All f64 kernels share the blocking parameters, so the size holds for any of them
+pub fn dgemm_workspace_size(layout: Layout, m: usize, n: usize, k: usize) -> usize {
+    crate::context::workspace_size::<f64, GenericKernel, A8, A4>(layout, m, n, k)
+}
+
+pub unsafe fn dgemm_with_context<E: Executor>(
+    ctx: &mut GemmContext<f64>,
+    e: &E,
+    layout: Layout,
+    transa: bool,
+    transb: bool,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: f64,
+    a: *const f64,
+    lda: usize,
+    b: *const f64,
+    ldb: usize,
+    beta: f64,
+    c: *mut f64,
+    ldc: usize,
+) -> Result<(), GemmError> {
+    match params::double::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => {
+            crate::context::gemm_with_context::<E, f64, AvxKernel<f64, GenericKernel>, A8, A4>(
+                ctx, e, layout, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc,
+            )
+        }
+
+        _ => crate::context::gemm_with_context::<E, f64, GenericKernel, A8, A4>(
+            ctx, e, layout, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc,
+        ),
+    }
+}
+
+pub fn dgemm_blocking() -> Blocking {
+    params::double::blocking()
+}
+
+pub fn dgemm_set_blocking(blocking: Blocking) -> Result<(), GemmError> {
+    params::double::set_blocking(blocking)
+}
+
+pub fn dgemm_isa() -> Isa {
+    params::double::isa()
+}
+
+pub fn dgemm_set_isa(isa: Isa) -> Result<(), GemmError> {
+    params::double::set_isa(isa)
+}
@@ -0,0 +1,228 @@
+//! Element-wise operations fused into the store of C, applied by the kernels
+//! to the final value of every element.
+
+use crate::matrix::Number;
+
+/// Operation on the elements of C. Rows and columns are those of C as the
+/// caller sees it.
+pub trait Epilogue<F: Number>: Copy + Send + Sync + 'static {
+    /// `apply` returns its argument, the kernels skip the epilogue
+    const IDENTITY: bool = false;
+
+    /// The epilogue of the part of C starting at `row`, `col`
+    unsafe fn sub(&self, row: usize, col: usize) -> Self;
+
+    /// Final value of `C[row, col]` computed as `x`
+    unsafe fn apply(&self, row: usize, col: usize, x: F) -> F;
+
+    /// The epilogue as a [`Linear`] one, which the vector kernels apply to
+    /// their registers. Other epilogues are applied element by element.
+    #[inline]
+    fn linear(&self) -> Option<Linear<F>> {
+        None
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct NoEpilogue;
+
+impl<F: Number> Epilogue<F> for NoEpilogue {
+    const IDENTITY: bool = true;
+
+    #[inline]
+    unsafe fn sub(&self, _row: usize, _col: usize) -> Self {
+        NoEpilogue
+    }
+
+    #[inline]
+    unsafe fn apply(&self, _row: usize, _col: usize, x: F) -> F {
+        x
+    }
+}
+
+// Row-major C is computed as its column-major transpose
+#[derive(Copy, Clone)]
+pub(crate) struct Transposed<P>(pub(crate) P);
+
+impl<F: Number, P: Epilogue<F>> Epilogue<F> for Transposed<P> {
+    const IDENTITY: bool = P::IDENTITY;
+
+    #[inline]
+    unsafe fn sub(&self, row: usize, col: usize) -> Self {
+        Transposed(self.0.sub(col, row))
+    }
+
+    #[inline]
+    unsafe fn apply(&self, row: usize, col: usize, x: F) -> F {
+        self.0.apply(col, row, x)
+    }
+
+    #[inline]
+    fn linear(&self) -> Option<Linear<F>> {
+        self.0.linear().map(|linear| Linear {
+            scale: linear.scale.transposed(),
+            bias: linear.bias.transposed(),
+            ..linear
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Activation {
+    Identity,
+    Relu,
+    /// tanh approximation of GELU
+    Gelu,
+    Sigmoid,
+    Tanh,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum Bias<F> {
+    None,
+    /// One element per row of C
+    Row(*const F),
+    /// One element per column of C
+    Col(*const F),
+}
+
+impl<F: Copy> Bias<F> {
+    #[inline]
+    fn transposed(self) -> Self {
+        match self {
+            Bias::None => Bias::None,
+            Bias::Row(x) => Bias::Col(x),
+            Bias::Col(x) => Bias::Row(x),
+        }
+    }
+
+    #[inline]
+    unsafe fn sub(self, row: usize, col: usize) -> Self {
+        match self {
+            Bias::None => Bias::None,
+            Bias::Row(x) => Bias::Row(x.add(row)),
+            Bias::Col(x) => Bias::Col(x.add(col)),
+        }
+    }
+}
+
+/// `clamp(relu(scale * x + bias), min, max)` of every element `x` of C, with
+/// one scale and one bias per row or per column of C. `relu` is optional.
+#[derive(Copy, Clone, Debug)]
+pub struct Linear<F> {
+    pub scale: Bias<F>,
+    pub bias: Bias<F>,
+    pub relu: bool,
+    pub clamp: Option<(F, F)>,
+}
+
+impl<F: Copy> Linear<F> {
+    /// The epilogue of the part of C starting at `row`, `col`
+    ///
+    /// # Safety
+    ///
+    /// The scale and the bias have to hold elements up to `row` or `col`.
+    #[inline]
+    pub unsafe fn sub(&self, row: usize, col: usize) -> Self {
+        Linear {
+            scale: self.scale.sub(row, col),
+            bias: self.bias.sub(row, col),
+            relu: self.relu,
+            clamp: self.clamp,
+        }
+    }
+}
+
+/// `clamp(activation(scale[col] * x + bias), min, max)` of every element `x`
+/// of C, where `scale` holds one element per column (output channel) of C
+/// and `clamp` is `(min, max)`.
+#[derive(Copy, Clone, Debug)]
+pub struct Fused<F> {
+    pub scale: Option<*const F>,
+    pub bias: Bias<F>,
+    pub activation: Activation,
+    pub clamp: Option<(F, F)>,
+}
+
+unsafe impl<F> Send for Fused<F> {}
+unsafe impl<F> Sync for Fused<F> {}
+
+impl<F> Default for Fused<F> {
+    fn default() -> Self {
+        Fused {
+            scale: None,
+            bias: Bias::None,
+            activation: Activation::Identity,
+            clamp: None,
+        }
+    }
+}
+
+macro_rules! fused {
+    ($t: ident) => {
+        impl Epilogue<$t> for Fused<$t> {
+            #[inline]
+            unsafe fn sub(&self, row: usize, col: usize) -> Self {
+                Fused {
+                    scale: self.scale.map(|scale| scale.add(col)),
+                    bias: self.bias.sub(row, col),
+                    ..*self
+                }
+            }
+
+            #[inline]
+            unsafe fn apply(&self, row: usize, col: usize, x: $t) -> $t {
+                let mut x = x;
+
+                if let Some(scale) = self.scale {
+                    x *= *scale.add(col);
+                }
+
+                match self.bias {
+                    Bias::None => {}
+                    Bias::Row(bias) => x += *bias.add(row),
+                    Bias::Col(bias) => x += *bias.add(col),
+                }
+
+                x = match self.activation {
+                    Activation::Identity => x,
+                    Activation::Relu => x.max(0.0),
+                    Activation::Gelu => {
+                        use std::$t::consts::{FRAC_1_SQRT_2, FRAC_2_SQRT_PI};
+
+                        // sqrt(2 / pi) * (x + 0.044715 * x^3)
+                        let inner = FRAC_2_SQRT_PI * FRAC_1_SQRT_2 * (x + 0.044715 * x * x * x);
+                        0.5 * x * (1.0 + inner.tanh())
+                    }
+                    Activation::Sigmoid => 1.0 / (1.0 + (-x).exp()),
+                    Activation::Tanh => x.tanh(),
+                };
+
+                if let Some((min, max)) = self.clamp {
+                    x = x.max(min).min(max);
+                }
+
+                x
+            }
+
+            #[inline]
+            fn linear(&self) -> Option<Linear<$t>> {
+                let relu = match self.activation {
+                    Activation::Identity => false,
+                    Activation::Relu => true,
+                    _ => return None,
+                };
+
+                Some(Linear {
+                    scale: self.scale.map_or(Bias::None, Bias::Col),
+                    bias: self.bias,
+                    relu,
+                    clamp: self.clamp,
+                })
+            }
+        }
+    };
+}
+
+fused!(f32);
+fused!(f64);
@@ -0,0 +1,95 @@
+use crate::kernel::isa::Isa;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    A,
+    B,
+    C,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GemmError {
+    LeadingDimension {
+        operand: Operand,
+        ld: usize,
+        min: usize,
+    },
+    BufferTooSmall {
+        operand: Operand,
+        len: usize,
+        required: usize,
+    },
+    Overflow {
+        operand: Operand,
+    },
+    WorkspaceTooSmall {
+        len: usize,
+        required: usize,
+    },
+    InvalidBlocking {
+        mc: usize,
+        kc: usize,
+        nc: usize,
+    },
+    UnsupportedIsa {
+        isa: Isa,
+    },
+    PackedMismatch {
+        operand: Operand,
+    },
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operand::A => write!(f, "A"),
+            Operand::B => write!(f, "B"),
+            Operand::C => write!(f, "C"),
+        }
+    }
+}
+
+impl fmt::Display for GemmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GemmError::LeadingDimension { operand, ld, min } => write!(
+                f,
+                "leading dimension of {} is {}, expected at least {}",
+                operand, ld, min
+            ),
+            GemmError::BufferTooSmall {
+                operand,
+                len,
+                required,
+            } => write!(
+                f,
+                "buffer of {} has {} elements, expected at least {}",
+                operand, len, required
+            ),
+            GemmError::Overflow { operand } => {
+                write!(f, "size of {} overflows usize", operand)
+            }
+            GemmError::WorkspaceTooSmall { len, required } => write!(
+                f,
+                "workspace has {} elements, expected at least {}",
+                len, required
+            ),
+            GemmError::InvalidBlocking { mc, kc, nc } => write!(
+                f,
+                "blocking MC={} KC={} NC={} does not fit the micro-kernel",
+                mc, kc, nc
+            ),
+            GemmError::UnsupportedIsa { isa } => {
+                write!(f, "{} kernel is not available on this CPU", isa.name())
+            }
+            GemmError::PackedMismatch { operand } => write!(
+                f,
+                "{} was packed for a different kernel or blocking",
+                operand
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GemmError {}
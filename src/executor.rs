@@ -8,6 +8,10 @@ pub trait Executor {
     );
 
     fn synchronize(&self) {}
+
+    fn num_threads(&self) -> usize {
+        1
+    }
 }
 
 pub struct DefaultExecutor;
@@ -36,14 +40,23 @@ impl Executor for RayonExecutor {
         step: usize,
         f: F,
     ) {
-        let end = 1 + ((end - 1) / step);
+        if start >= end {
+            return;
+        }
+
+        let steps = 1 + (end - start - 1) / step;
 
         use rayon::prelude::*;
-        (start..end)
+        (0..steps)
             .into_par_iter()
-            .map(|x| x * step)
+            .map(|x| start + x * step)
             .for_each(|x| f(x));
     }
+
+    #[inline]
+    fn num_threads(&self) -> usize {
+        rayon::current_num_threads()
+    }
 }
 
 pub struct ThreadPoolExecutor {
@@ -99,4 +112,9 @@ impl Executor for ThreadPoolExecutor {
     fn synchronize(&self) {
         self.thread_pool.join();
     }
+
+    #[inline]
+    fn num_threads(&self) -> usize {
+        self.thread_pool.max_count()
+    }
 }
@@ -0,0 +1,193 @@
+use crate::cblas::*;
+use std::os::raw::{c_char, c_int};
+
+unsafe fn trans(t: *const c_char) -> c_int {
+    match *t as u8 {
+        b'N' | b'n' => CBLAS_NO_TRANS,
+        b'T' | b't' => CBLAS_TRANS,
+        b'C' | b'c' => CBLAS_CONJ_TRANS,
+        _ => 0,
+    }
+}
+
+// Reports an invalid argument like the reference BLAS, by its position
+// counted from 1; the call that reported it then returns without effect
+fn xerbla(routine: &str, info: c_int) {
+    eprintln!(
+        " ** On entry to {} parameter number {} had an illegal value",
+        routine, info
+    );
+}
+
+macro_rules! fortran_gemm {
+    ($name: ident, $cblas: ident, $t: ty) => {
+        #[no_mangle]
+        pub unsafe extern "C" fn $name(
+            transa: *const c_char,
+            transb: *const c_char,
+            m: *const c_int,
+            n: *const c_int,
+            k: *const c_int,
+            alpha: *const $t,
+            a: *const $t,
+            lda: *const c_int,
+            b: *const $t,
+            ldb: *const c_int,
+            beta: *const $t,
+            c: *mut $t,
+            ldc: *const c_int,
+        ) {
+            let (transa, transb) = (trans(transa), trans(transb));
+
+            // the Fortran arguments are the CBLAS ones without the order
+            let info = gemm_info(
+                CBLAS_COL_MAJOR,
+                transa,
+                transb,
+                *m,
+                *n,
+                *k,
+                *lda,
+                *ldb,
+                *ldc,
+            );
+            if info != 0 {
+                xerbla(
+                    &stringify!($name).trim_end_matches('_').to_uppercase(),
+                    info - 1,
+                );
+                return;
+            }
+
+            $cblas(
+                CBLAS_COL_MAJOR,
+                transa,
+                transb,
+                *m,
+                *n,
+                *k,
+                *alpha,
+                a,
+                *lda,
+                b,
+                *ldb,
+                *beta,
+                c,
+                *ldc,
+            );
+        }
+    };
+}
+
+fortran_gemm!(sgemm_, cblas_sgemm, f32);
+fortran_gemm!(dgemm_, cblas_dgemm, f64);
+
+macro_rules! fortran_l1 {
+    ($t: ty,
+     $rot: ident => $cblas_rot: ident,
+     $swap: ident => $cblas_swap: ident,
+     $scal: ident => $cblas_scal: ident,
+     $copy: ident => $cblas_copy: ident,
+     $axpy: ident => $cblas_axpy: ident,
+     $dot: ident => $cblas_dot: ident,
+     $nrm2: ident => $cblas_nrm2: ident,
+     $asum: ident => $cblas_asum: ident) => {
+        #[no_mangle]
+        pub unsafe extern "C" fn $rot(
+            n: *const c_int,
+            x: *mut $t,
+            incx: *const c_int,
+            y: *mut $t,
+            incy: *const c_int,
+            c: *const $t,
+            s: *const $t,
+        ) {
+            $cblas_rot(*n, x, *incx, y, *incy, *c, *s);
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $swap(
+            n: *const c_int,
+            x: *mut $t,
+            incx: *const c_int,
+            y: *mut $t,
+            incy: *const c_int,
+        ) {
+            $cblas_swap(*n, x, *incx, y, *incy);
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $scal(
+            n: *const c_int,
+            alpha: *const $t,
+            x: *mut $t,
+            incx: *const c_int,
+        ) {
+            $cblas_scal(*n, *alpha, x, *incx);
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $copy(
+            n: *const c_int,
+            x: *const $t,
+            incx: *const c_int,
+            y: *mut $t,
+            incy: *const c_int,
+        ) {
+            $cblas_copy(*n, x, *incx, y, *incy);
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $axpy(
+            n: *const c_int,
+            alpha: *const $t,
+            x: *const $t,
+            incx: *const c_int,
+            y: *mut $t,
+            incy: *const c_int,
+        ) {
+            $cblas_axpy(*n, *alpha, x, *incx, y, *incy);
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $dot(
+            n: *const c_int,
+            x: *const $t,
+            incx: *const c_int,
+            y: *const $t,
+            incy: *const c_int,
+        ) -> $t {
+            $cblas_dot(*n, x, *incx, y, *incy)
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $nrm2(n: *const c_int, x: *const $t, incx: *const c_int) -> $t {
+            $cblas_nrm2(*n, x, *incx)
+        }
+
+        #[no_mangle]
+        pub unsafe extern "C" fn $asum(n: *const c_int, x: *const $t, incx: *const c_int) -> $t {
+            $cblas_asum(*n, x, *incx)
+        }
+    };
+}
+
+fortran_l1!(f32,
+    srot_ => cblas_srot,
+    sswap_ => cblas_sswap,
+    sscal_ => cblas_sscal,
+    scopy_ => cblas_scopy,
+    saxpy_ => cblas_saxpy,
+    sdot_ => cblas_sdot,
+    snrm2_ => cblas_snrm2,
+    sasum_ => cblas_sasum);
+
+fortran_l1!(f64,
+    drot_ => cblas_drot,
+    dswap_ => cblas_dswap,
+    dscal_ => cblas_dscal,
+    dcopy_ => cblas_dcopy,
+    daxpy_ => cblas_daxpy,
+    ddot_ => cblas_ddot,
+    dnrm2_ => cblas_dnrm2,
+    dasum_ => cblas_dasum);
@@ -1,18 +1,158 @@
 use crate::aligned_alloc;
 use crate::dim::Dim;
-use crate::executor::Executor;
+use crate::epilogue::{Epilogue, NoEpilogue, Transposed};
+use crate::executor::{DefaultExecutor, Executor};
+use crate::half::Input;
 use crate::kernel;
-use crate::kernel::params::single::{KC, MC, NC};
-use crate::kernel::GemmKernel;
+use crate::kernel::{apply_epilogue, store_tile, with_tile, Blocking, GemmKernel, MAX_TILE};
 use crate::matrix::{
-    ConstMatrix, ConstTransposedMatrix, Matrix, MatrixMut, MutMatrix, MutTransposedMatrix, Number,
+    ByteMatrix, ByteStorage, ConjMatrix, ConstMatrix, ConstTransposedMatrix, HalfMatrix, Matrix,
+    MatrixMut, MutMatrix, Number, Storage, StridedMatrix,
 };
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Layout {
+    RowMajor,
+    ColMajor,
+}
+
+/// Operation applied to an operand, 'N', 'T' or 'C' of BLAS
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Transpose {
+    NoTrans,
+    Trans,
+    ConjTrans,
+}
+
+/// Side of the triangular operand of trsm and trmm, 'L' or 'R'
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    // The side in the transpose of the product
+    pub(crate) fn flip(self) -> Side {
+        match self {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+}
+
+/// Triangle of a symmetric or triangular matrix that is stored, 'U' or 'L'
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Uplo {
+    Upper,
+    Lower,
+}
+
+impl Uplo {
+    // The triangle of the transpose, row-major matrices run as column-major
+    pub(crate) fn flip(self) -> Uplo {
+        match self {
+            Uplo::Upper => Uplo::Lower,
+            Uplo::Lower => Uplo::Upper,
+        }
+    }
+}
+
+/// Diagonal of a triangular matrix, 'U' takes it as ones without reading it
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Diag {
+    NonUnit,
+    Unit,
+}
+
 pub unsafe fn gemm<E, F, K, MR, NR>(
     e: &E,
+    layout: Layout,
+    transa: bool,
+    transb: bool,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: F,
+    a: *const F,
+    lda: usize,
+    b: *const F,
+    ldb: usize,
+    beta: F,
+    c: *mut F,
+    ldc: usize,
+) where
+    E: Executor,
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+{
+    gemm_epilogue::<E, F, K, MR, NR, _>(
+        e, layout, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc, NoEpilogue,
+    )
+}
+
+/// Same as [`gemm`] with `epilogue` applied to every element of C once its
+/// value is final, rows and columns counted as in `layout`.
+pub unsafe fn gemm_epilogue<E, F, K, MR, NR, P>(
+    e: &E,
+    layout: Layout,
     transa: bool,
     transb: bool,
-    transc: bool,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: F,
+    a: *const F,
+    lda: usize,
+    b: *const F,
+    ldb: usize,
+    beta: F,
+    c: *mut F,
+    ldc: usize,
+    epilogue: P,
+) where
+    E: Executor,
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+    P: Epilogue<F>,
+{
+    match layout {
+        Layout::ColMajor => gemm_col_major::<E, F, K, MR, NR, _>(
+            e, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc, epilogue,
+        ),
+
+        // row-major C is column-major C^T = op(B)^T * op(A)^T
+        Layout::RowMajor => gemm_col_major::<E, F, K, MR, NR, _>(
+            e,
+            transb,
+            transa,
+            n,
+            m,
+            k,
+            alpha,
+            b,
+            ldb,
+            a,
+            lda,
+            beta,
+            c,
+            ldc,
+            Transposed(epilogue),
+        ),
+    }
+}
+
+/// Same as [`gemm`] with a conjugate-transpose option for the operands,
+/// which makes a difference for complex elements only.
+pub unsafe fn gemm_conj<E, F, K, MR, NR>(
+    e: &E,
+    layout: Layout,
+    transa: Transpose,
+    transb: Transpose,
     m: usize,
     n: usize,
     k: usize,
@@ -31,92 +171,753 @@ pub unsafe fn gemm<E, F, K, MR, NR>(
     NR: Dim,
     K: GemmKernel<F, MR, NR>,
 {
-    match (transa, transb, transc) {
-        (false, false, false) => gemm_template::<E, F, K, MR, NR, _, _, _>(
+    // row-major C is column-major C^T = op(B)^T * op(A)^T, and the
+    // row-major operands read as column-major ones are transposed already
+    let (transa, transb, m, n, a, lda, b, ldb) = match layout {
+        Layout::ColMajor => (transa, transb, m, n, a, lda, b, ldb),
+        Layout::RowMajor => (transb, transa, n, m, b, ldb, a, lda),
+    };
+
+    let c = MutMatrix::new(c, ldc);
+
+    match transa {
+        Transpose::NoTrans => gemm_conj_b::<E, F, K, MR, NR, _, _>(
             e,
+            transb,
             m,
             n,
             k,
             alpha,
             ConstMatrix::new(a, lda),
-            ConstMatrix::new(b, ldb),
+            b,
+            ldb,
             beta,
-            MutMatrix::new(c, ldc),
+            c,
         ),
 
-        (false, false, true) => gemm_template::<E, F, K, MR, NR, _, _, _>(
+        Transpose::Trans => gemm_conj_b::<E, F, K, MR, NR, _, _>(
             e,
+            transb,
             m,
             n,
             k,
             alpha,
-            ConstMatrix::new(a, lda),
+            ConstTransposedMatrix::new(a, lda),
+            b,
+            ldb,
+            beta,
+            c,
+        ),
+
+        Transpose::ConjTrans => gemm_conj_b::<E, F, K, MR, NR, _, _>(
+            e,
+            transb,
+            m,
+            n,
+            k,
+            alpha,
+            ConjMatrix::new(ConstTransposedMatrix::new(a, lda)),
+            b,
+            ldb,
+            beta,
+            c,
+        ),
+    }
+}
+
+unsafe fn gemm_conj_b<E, F, K, MR, NR, A, C>(
+    e: &E,
+    transb: Transpose,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: F,
+    a: A,
+    b: *const F,
+    ldb: usize,
+    beta: F,
+    c: C,
+) where
+    E: Executor,
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+    A: Matrix<F>,
+    C: MatrixMut<F>,
+{
+    match transb {
+        Transpose::NoTrans => gemm_template::<E, F, K, MR, NR, _, _, _, _>(
+            e,
+            m,
+            n,
+            k,
+            alpha,
+            a,
             ConstMatrix::new(b, ldb),
             beta,
-            MutTransposedMatrix::new(c, ldc),
+            c,
+            NoEpilogue,
         ),
 
-        (false, true, false) => gemm_template::<E, F, K, MR, NR, _, _, _>(
+        Transpose::Trans => gemm_template::<E, F, K, MR, NR, _, _, _, _>(
             e,
             m,
             n,
             k,
             alpha,
-            ConstMatrix::new(a, lda),
+            a,
             ConstTransposedMatrix::new(b, ldb),
             beta,
-            MutMatrix::new(c, ldc),
+            c,
+            NoEpilogue,
         ),
 
-        (false, true, true) => gemm_template::<E, F, K, MR, NR, _, _, _>(
+        Transpose::ConjTrans => gemm_template::<E, F, K, MR, NR, _, _, _, _>(
             e,
             m,
             n,
             k,
             alpha,
-            ConstMatrix::new(a, lda),
-            ConstTransposedMatrix::new(b, ldb),
+            a,
+            ConjMatrix::new(ConstTransposedMatrix::new(b, ldb)),
             beta,
-            MutTransposedMatrix::new(c, ldc),
+            c,
+            NoEpilogue,
         ),
+    }
+}
 
-        (true, false, false) => gemm_template::<E, F, K, MR, NR, _, _, _>(
+/// f32 gemm where A and/or B hold f16 or bf16 elements. The kernel packs
+/// them widened to f32.
+pub unsafe fn gemm_mixed<E, K, MR, NR>(
+    e: &E,
+    layout: Layout,
+    transa: bool,
+    transb: bool,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: f32,
+    a: Input,
+    lda: usize,
+    b: Input,
+    ldb: usize,
+    beta: f32,
+    c: *mut f32,
+    ldc: usize,
+) where
+    E: Executor,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<f32, MR, NR>,
+{
+    let (transa, transb, m, n, a, lda, b, ldb) = match layout {
+        Layout::ColMajor => (transa, transb, m, n, a, lda, b, ldb),
+        Layout::RowMajor => (transb, transa, n, m, b, ldb, a, lda),
+    };
+
+    let (rsa, csa) = if transa { (1, lda) } else { (lda, 1) };
+    let (rsb, csb) = if transb { (1, ldb) } else { (ldb, 1) };
+
+    let half = |input, rs, cs| match input {
+        Input::F16(ptr) => HalfMatrix::new(ptr, Storage::F16, rs, cs),
+        Input::Bf16(ptr) => HalfMatrix::new(ptr, Storage::Bf16, rs, cs),
+        Input::F32(_) => unreachable!(),
+    };
+
+    let c = MutMatrix::new(c, ldc);
+
+    match (a, b) {
+        (Input::F32(a), Input::F32(b)) => gemm_col_major::<E, f32, K, MR, NR, _>(
+            e, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c.ptr, ldc, NoEpilogue,
+        ),
+
+        (Input::F32(a), b) => gemm_template::<E, f32, K, MR, NR, _, _, _, _>(
             e,
             m,
             n,
             k,
             alpha,
-            ConstTransposedMatrix::new(a, lda),
-            ConstMatrix::new(b, ldb),
+            StridedMatrix::new(a as *mut f32, rsa, csa),
+            half(b, rsb, csb),
             beta,
-            MutMatrix::new(c, ldc),
+            c,
+            NoEpilogue,
         ),
 
-        (true, false, true) => gemm_template::<E, F, K, MR, NR, _, _, _>(
+        (a, Input::F32(b)) => gemm_template::<E, f32, K, MR, NR, _, _, _, _>(
             e,
             m,
             n,
             k,
             alpha,
+            half(a, rsa, csa),
+            StridedMatrix::new(b as *mut f32, rsb, csb),
+            beta,
+            c,
+            NoEpilogue,
+        ),
+
+        (a, b) => gemm_template::<E, f32, K, MR, NR, _, _, _, _>(
+            e,
+            m,
+            n,
+            k,
+            alpha,
+            half(a, rsa, csa),
+            half(b, rsb, csb),
+            beta,
+            c,
+            NoEpilogue,
+        ),
+    }
+}
+
+/// `C = (A - a_zero) * (B - b_zero)` of u8 A and i8 B, accumulated in i32.
+pub unsafe fn gemm_quantized<E, K, MR, NR>(
+    e: &E,
+    layout: Layout,
+    transa: bool,
+    transb: bool,
+    m: usize,
+    n: usize,
+    k: usize,
+    a: *const u8,
+    lda: usize,
+    a_zero: u8,
+    b: *const i8,
+    ldb: usize,
+    b_zero: i8,
+    c: *mut i32,
+    ldc: usize,
+) where
+    E: Executor,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<i32, MR, NR>,
+{
+    // The kernels take u8 A and i8 B, so instead of swapping the operands of
+    // row-major products their elements are read with swapped strides
+    let row_major = layout == Layout::RowMajor;
+
+    // strides of the rows and of the columns of op(A), op(B) and C
+    let (rsa, csa) = if transa != row_major {
+        (lda, 1)
+    } else {
+        (1, lda)
+    };
+    let (rsb, csb) = if transb != row_major {
+        (ldb, 1)
+    } else {
+        (1, ldb)
+    };
+    let (rsc, csc) = if row_major { (ldc, 1) } else { (1, ldc) };
+
+    let a = ByteMatrix::new(a, ByteStorage::U8, csa, rsa);
+    let b = ByteMatrix::new(b as *const u8, ByteStorage::I8, csb, rsb);
+    let c = StridedMatrix::new(c, csc, rsc);
+
+    gemm_template::<E, i32, K, MR, NR, _, _, _, _>(e, m, n, k, 1, a, b, 0, c, NoEpilogue);
+
+    if a_zero == 0 && b_zero == 0 {
+        return;
+    }
+
+    // (A - za) * (B - zb) = A * B - zb * rowsum(A) - za * colsum(B) + k * za * zb
+    let (za, zb) = (a_zero as i32, b_zero as i32);
+
+    let row_sums: Vec<i32> = (0..m)
+        .map(|i| {
+            if zb != 0 {
+                (0..k).map(|p| a.get(p, i)).sum()
+            } else {
+                0
+            }
+        })
+        .collect();
+    let col_sums: Vec<i32> = (0..n)
+        .map(|j| {
+            if za != 0 {
+                (0..k).map(|p| b.get(j, p)).sum()
+            } else {
+                0
+            }
+        })
+        .collect();
+
+    let offset = k as i32 * za * zb;
+
+    for (j, col_sum) in col_sums.iter().enumerate() {
+        for (i, row_sum) in row_sums.iter().enumerate() {
+            *c.index_mut(j, i) += offset - zb * row_sum - za * col_sum;
+        }
+    }
+}
+
+/// `C = alpha * A * B + beta * C` where every element `X[i, j]` lives at
+/// `x + i * rsx + j * csx`.
+pub unsafe fn gemm_strided<E, F, K, MR, NR>(
+    e: &E,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: F,
+    a: *const F,
+    rsa: usize,
+    csa: usize,
+    b: *const F,
+    rsb: usize,
+    csb: usize,
+    beta: F,
+    c: *mut F,
+    rsc: usize,
+    csc: usize,
+) where
+    E: Executor,
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+{
+    gemm_template::<E, F, K, MR, NR, _, _, _, _>(
+        e,
+        m,
+        n,
+        k,
+        alpha,
+        StridedMatrix::new(a as *mut F, csa, rsa),
+        StridedMatrix::new(b as *mut F, csb, rsb),
+        beta,
+        StridedMatrix::new(c, csc, rsc),
+        NoEpilogue,
+    )
+}
+
+/// Computes `batch` independent products of the same shape. Operand `i` of
+/// the batch starts `i * stride_x` elements after `x`.
+pub unsafe fn gemm_batch_strided<E, F, K, MR, NR>(
+    e: &E,
+    layout: Layout,
+    transa: bool,
+    transb: bool,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: F,
+    a: *const F,
+    lda: usize,
+    stride_a: usize,
+    b: *const F,
+    ldb: usize,
+    stride_b: usize,
+    beta: F,
+    c: *mut F,
+    ldc: usize,
+    stride_c: usize,
+    batch: usize,
+) where
+    E: Executor,
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+{
+    let (transa, transb, m, n, a, lda, stride_a, b, ldb, stride_b) = match layout {
+        Layout::ColMajor => (transa, transb, m, n, a, lda, stride_a, b, ldb, stride_b),
+        Layout::RowMajor => (transb, transa, n, m, b, ldb, stride_b, a, lda, stride_a),
+    };
+
+    let c = MutMatrix::new(c, ldc);
+
+    macro_rules! batch {
+        ($a:expr, $b:expr) => {
+            gemm_batch_template::<E, F, K, MR, NR, _, _, _>(
+                e, m, n, k, alpha, $a, stride_a, $b, stride_b, beta, c, stride_c, batch,
+            )
+        };
+    }
+
+    match (transa, transb) {
+        (false, false) => batch!(ConstMatrix::new(a, lda), ConstMatrix::new(b, ldb)),
+        (false, true) => batch!(ConstMatrix::new(a, lda), ConstTransposedMatrix::new(b, ldb)),
+        (true, false) => batch!(ConstTransposedMatrix::new(a, lda), ConstMatrix::new(b, ldb)),
+        (true, true) => batch!(
             ConstTransposedMatrix::new(a, lda),
+            ConstTransposedMatrix::new(b, ldb)
+        ),
+    }
+}
+
+unsafe fn gemm_batch_template<E, F, K, MR, NR, A, B, C>(
+    e: &E,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: F,
+    a: A,
+    stride_a: usize,
+    b: B,
+    stride_b: usize,
+    beta: F,
+    c: C,
+    stride_c: usize,
+    batch: usize,
+) where
+    E: Executor,
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+    A: Matrix<F>,
+    B: Matrix<F>,
+    C: MatrixMut<F>,
+{
+    if m == 0 || n == 0 || batch == 0 {
+        return;
+    }
+
+    let blocking = K::blocking();
+    let (pa_len, pb_len) = packing_lens::<F>(blocking, m, n, k);
+
+    // With fewer entries than threads each product is parallelized instead
+    let threads = e.num_threads();
+    if batch < threads {
+        let packed_a = aligned_alloc::Alloc::new(pa_len * std::mem::size_of::<F>());
+        let packed_b = aligned_alloc::Alloc::new(pb_len * std::mem::size_of::<F>());
+
+        for idx in 0..batch {
+            gemm_blocked::<E, F, K, MR, NR, _, _, _, _>(
+                e,
+                m,
+                n,
+                k,
+                alpha,
+                a.offset(idx * stride_a),
+                b.offset(idx * stride_b),
+                beta,
+                c.offset(idx * stride_c),
+                blocking,
+                packed_a.ptr(),
+                packed_b.ptr(),
+                NoEpilogue,
+            );
+        }
+
+        return;
+    }
+
+    // One pair of packing buffers per chunk, each chunk runs its share of
+    // the batch sequentially
+    let chunks = threads;
+
+    let buf = aligned_alloc::Alloc::new(chunks * (pa_len + pb_len) * std::mem::size_of::<F>());
+    let packed = MutMatrix::new(buf.ptr::<F>(), pa_len + pb_len);
+
+    e.execute(0, chunks, 1, move |t| {
+        let pa = packed.row_mut(t);
+        let pb = pa.add(pa_len);
+
+        for idx in t * batch / chunks..(t + 1) * batch / chunks {
+            gemm_blocked::<_, F, K, MR, NR, _, _, _, _>(
+                &DefaultExecutor,
+                m,
+                n,
+                k,
+                alpha,
+                a.offset(idx * stride_a),
+                b.offset(idx * stride_b),
+                beta,
+                c.offset(idx * stride_c),
+                blocking,
+                pa,
+                pb,
+                NoEpilogue,
+            );
+        }
+    });
+
+    e.synchronize();
+}
+
+/// One problem of a grouped GEMM, with the same meaning of fields as the
+/// arguments of [`gemm`].
+#[derive(Copy, Clone, Debug)]
+pub struct GemmProblem<F: Number> {
+    pub layout: Layout,
+    pub transa: bool,
+    pub transb: bool,
+    pub m: usize,
+    pub n: usize,
+    pub k: usize,
+    pub alpha: F,
+    pub a: *const F,
+    pub lda: usize,
+    pub b: *const F,
+    pub ldb: usize,
+    pub beta: F,
+    pub c: *mut F,
+    pub ldc: usize,
+}
+
+unsafe impl<F: Number> Send for GemmProblem<F> {}
+unsafe impl<F: Number> Sync for GemmProblem<F> {}
+
+impl<F: Number> GemmProblem<F> {
+    fn flops(&self) -> usize {
+        self.m * self.n * std::cmp::max(self.k, 1)
+    }
+
+    pub(crate) fn packing_lens(&self, blocking: Blocking) -> (usize, usize) {
+        // row-major problems run as their column-major transpose
+        match self.layout {
+            Layout::ColMajor => packing_lens::<F>(blocking, self.m, self.n, self.k),
+            Layout::RowMajor => packing_lens::<F>(blocking, self.n, self.m, self.k),
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub(crate) struct Shared<T>(pub(crate) *const T);
+
+unsafe impl<T> Send for Shared<T> {}
+unsafe impl<T> Sync for Shared<T> {}
+
+#[derive(Copy, Clone)]
+struct Bin {
+    start: usize,
+    end: usize,
+    offset: usize,
+    pa_len: usize,
+}
+
+/// Computes every problem of the group. Problems bigger than a thread's
+/// fair share of the work are parallelized internally, the rest are spread
+/// over the executor's threads balancing the flop count.
+pub unsafe fn gemm_grouped<E, F, K, MR, NR>(e: &E, problems: &[GemmProblem<F>])
+where
+    E: Executor,
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+{
+    let blocking = K::blocking();
+    let threads = e.num_threads();
+    let total: usize = problems.iter().map(GemmProblem::flops).sum();
+
+    let mut small = Vec::with_capacity(problems.len());
+
+    for (idx, p) in problems.iter().enumerate() {
+        if p.m == 0 || p.n == 0 {
+            continue;
+        }
+
+        if threads > 1 && p.flops() * threads >= total {
+            let (pa_len, pb_len) = p.packing_lens(blocking);
+            let buf = aligned_alloc::Alloc::new((pa_len + pb_len) * std::mem::size_of::<F>());
+            let (pa, pb) = (buf.ptr(), buf.ptr::<F>().add(pa_len));
+
+            gemm_problem::<E, F, K, MR, NR>(e, p, blocking, pa, pb);
+        } else {
+            small.push(idx);
+        }
+    }
+
+    if small.is_empty() {
+        return;
+    }
+
+    // longest processing time first: the biggest problems are placed first,
+    // each into the least loaded bin
+    small.sort_by_key(|&idx| std::cmp::Reverse(problems[idx].flops()));
+
+    let bins = std::cmp::min(threads, small.len());
+    let mut load = vec![0; bins];
+    let mut assigned = vec![Vec::new(); bins];
+
+    for idx in small {
+        let (t, _) = load.iter().enumerate().min_by_key(|&(_, l)| *l).unwrap();
+
+        load[t] += problems[idx].flops();
+        assigned[t].push(idx);
+    }
+
+    let mut order = Vec::with_capacity(problems.len());
+    let mut placement = Vec::with_capacity(bins);
+    let mut offset = 0;
+
+    for indices in &assigned {
+        let (mut pa_len, mut pb_len) = (0, 0);
+
+        for &idx in indices {
+            let (a_len, b_len) = problems[idx].packing_lens(blocking);
+
+            pa_len = std::cmp::max(pa_len, a_len);
+            pb_len = std::cmp::max(pb_len, b_len);
+        }
+
+        placement.push(Bin {
+            start: order.len(),
+            end: order.len() + indices.len(),
+            offset,
+            pa_len,
+        });
+
+        order.extend_from_slice(indices);
+        offset += pa_len + pb_len;
+    }
+
+    let buf = aligned_alloc::Alloc::new(offset * std::mem::size_of::<F>());
+    let packed = MutMatrix::new(buf.ptr::<F>(), 1);
+
+    let problems = Shared(problems.as_ptr());
+    let order = Shared(order.as_ptr());
+    let placement = Shared(placement.as_ptr());
+
+    e.execute(0, bins, 1, move |t| {
+        let bin = *placement.0.add(t);
+        let pa = packed.row_mut(bin.offset);
+        let pb = pa.add(bin.pa_len);
+
+        for i in bin.start..bin.end {
+            let p = &*problems.0.add(*order.0.add(i));
+
+            gemm_problem::<_, F, K, MR, NR>(&DefaultExecutor, p, blocking, pa, pb);
+        }
+    });
+
+    e.synchronize();
+}
+
+pub(crate) unsafe fn gemm_problem<E, F, K, MR, NR>(
+    e: &E,
+    p: &GemmProblem<F>,
+    blocking: Blocking,
+    packed_a: *mut F,
+    packed_b: *mut F,
+) where
+    E: Executor,
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+{
+    let (transa, transb, m, n, a, lda, b, ldb) = match p.layout {
+        Layout::ColMajor => (p.transa, p.transb, p.m, p.n, p.a, p.lda, p.b, p.ldb),
+        Layout::RowMajor => (p.transb, p.transa, p.n, p.m, p.b, p.ldb, p.a, p.lda),
+    };
+
+    let c = MutMatrix::new(p.c, p.ldc);
+
+    macro_rules! run {
+        ($a:expr, $b:expr) => {
+            gemm_blocked::<E, F, K, MR, NR, _, _, _, _>(
+                e, m, n, p.k, p.alpha, $a, $b, p.beta, c, blocking, packed_a, packed_b, NoEpilogue,
+            )
+        };
+    }
+
+    match (transa, transb) {
+        (false, false) => run!(ConstMatrix::new(a, lda), ConstMatrix::new(b, ldb)),
+        (false, true) => run!(ConstMatrix::new(a, lda), ConstTransposedMatrix::new(b, ldb)),
+        (true, false) => run!(ConstTransposedMatrix::new(a, lda), ConstMatrix::new(b, ldb)),
+        (true, true) => run!(
+            ConstTransposedMatrix::new(a, lda),
+            ConstTransposedMatrix::new(b, ldb)
+        ),
+    }
+}
+
+// Packing buffer lengths for one m x n x k product, each rounded up to keep
+// buffers carved from a shared allocation on a 32-byte boundary.
+pub(crate) fn packing_lens<F: Number>(
+    blocking: Blocking,
+    m: usize,
+    n: usize,
+    k: usize,
+) -> (usize, usize) {
+    let Blocking { mc, kc, nc } = blocking;
+
+    let align = 32 / std::mem::size_of::<F>();
+    let kc = std::cmp::min(kc, std::cmp::max(k, 1));
+
+    let pa_len = std::cmp::min(mc, m) * kc;
+    let pb_len = kc * std::cmp::min(nc, n);
+
+    (
+        (pa_len + align - 1) & !(align - 1),
+        (pb_len + align - 1) & !(align - 1),
+    )
+}
+
+unsafe fn gemm_col_major<E, F, K, MR, NR, P>(
+    e: &E,
+    transa: bool,
+    transb: bool,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: F,
+    a: *const F,
+    lda: usize,
+    b: *const F,
+    ldb: usize,
+    beta: F,
+    c: *mut F,
+    ldc: usize,
+    epilogue: P,
+) where
+    E: Executor,
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+    P: Epilogue<F>,
+{
+    let c = MutMatrix::new(c, ldc);
+
+    match (transa, transb) {
+        (false, false) => gemm_template::<E, F, K, MR, NR, _, _, _, _>(
+            e,
+            m,
+            n,
+            k,
+            alpha,
+            ConstMatrix::new(a, lda),
             ConstMatrix::new(b, ldb),
             beta,
-            MutTransposedMatrix::new(c, ldc),
+            c,
+            epilogue,
         ),
 
-        (true, true, false) => gemm_template::<E, F, K, MR, NR, _, _, _>(
+        (false, true) => gemm_template::<E, F, K, MR, NR, _, _, _, _>(
             e,
             m,
             n,
             k,
             alpha,
-            ConstTransposedMatrix::new(a, lda),
+            ConstMatrix::new(a, lda),
             ConstTransposedMatrix::new(b, ldb),
             beta,
-            MutMatrix::new(c, ldc),
+            c,
+            epilogue,
         ),
 
-        (true, true, true) => gemm_template::<E, F, K, MR, NR, _, _, _>(
+        (true, false) => gemm_template::<E, F, K, MR, NR, _, _, _, _>(
+            e,
+            m,
+            n,
+            k,
+            alpha,
+            ConstTransposedMatrix::new(a, lda),
+            ConstMatrix::new(b, ldb),
+            beta,
+            c,
+            epilogue,
+        ),
+
+        (true, true) => gemm_template::<E, F, K, MR, NR, _, _, _, _>(
             e,
             m,
             n,
@@ -125,12 +926,13 @@ pub unsafe fn gemm<E, F, K, MR, NR>(
             ConstTransposedMatrix::new(a, lda),
             ConstTransposedMatrix::new(b, ldb),
             beta,
-            MutTransposedMatrix::new(c, ldc),
+            c,
+            epilogue,
         ),
     }
 }
 
-unsafe fn gemm_template<E, F, K, MR, NR, A, B, C>(
+pub(crate) unsafe fn gemm_template<E, F, K, MR, NR, A, B, C, P>(
     e: &E,
     m: usize,
     n: usize,
@@ -140,6 +942,7 @@ unsafe fn gemm_template<E, F, K, MR, NR, A, B, C>(
     b: B,
     beta: F,
     c: C,
+    epilogue: P,
 ) where
     E: Executor,
     F: Number,
@@ -149,34 +952,214 @@ unsafe fn gemm_template<E, F, K, MR, NR, A, B, C>(
     A: Matrix<F>,
     B: Matrix<F>,
     C: MatrixMut<F>,
+    P: Epilogue<F>,
 {
-    let packed_a = aligned_alloc::Alloc::new(MC * KC * std::mem::size_of::<F>());
-    let packed_b = aligned_alloc::Alloc::new(KC * NC * std::mem::size_of::<F>());
-
-    for j in (0..n).step_by(NC) {
-        let j_b = std::cmp::min(n - j, NC);
-        for p in (0..k).step_by(KC) {
-            let p_b = std::cmp::min(k - p, KC);
-            for i in (0..m).step_by(MC) {
-                let i_b = std::cmp::min(m - i, MC);
-
-                let pa = MutMatrix::new(packed_a.ptr::<F>(), p_b);
-                let pb = MutMatrix::new(packed_b.ptr::<F>(), p_b);
-
-                inner_kernel::<E, F, K, MR, NR, _, _, _>(
-                    e,
-                    i_b,
-                    j_b,
-                    p_b,
-                    alpha,
-                    a.sub(p, i),
-                    b.sub(j, p),
-                    beta,
-                    c.sub(j, i),
-                    pa,
-                    pb,
-                    i == 0,
-                );
+    if k == 0 {
+        scale_c(m, n, beta, c);
+        apply_epilogue(m, n, c, epilogue);
+        return;
+    }
+
+    let blocking = K::blocking();
+    let Blocking { mc, kc, nc } = blocking;
+
+    let packed_a = aligned_alloc::Alloc::new(mc * kc * std::mem::size_of::<F>());
+    let packed_b = aligned_alloc::Alloc::new(kc * nc * std::mem::size_of::<F>());
+
+    gemm_blocked::<E, F, K, MR, NR, _, _, _, _>(
+        e,
+        m,
+        n,
+        k,
+        alpha,
+        a,
+        b,
+        beta,
+        c,
+        blocking,
+        packed_a.ptr(),
+        packed_b.ptr(),
+        epilogue,
+    );
+}
+
+// An operand of the blocked loops. Matrices are packed by the loops as they
+// go, operands packed beforehand have no matrix and give their panels, with
+// the last one padded with zeros.
+pub(crate) trait Operand<F: Number>: Copy + Send + Sync + 'static {
+    type Matrix: Matrix<F>;
+
+    fn matrix(self) -> Option<Self::Matrix>;
+
+    // The panels of the KC block at depth `p` from row (A) or column (B)
+    // `index` on, `p_b` deep. Only called when there is no matrix.
+    unsafe fn panels(self, p: usize, index: usize, p_b: usize) -> MutMatrix<F>;
+}
+
+// A matrix operand, packed by the loops as they go
+#[derive(Copy, Clone)]
+pub(crate) struct Raw<M>(pub M);
+
+impl<F: Number, M: Matrix<F>> Operand<F> for Raw<M> {
+    type Matrix = M;
+
+    #[inline]
+    fn matrix(self) -> Option<M> {
+        Some(self.0)
+    }
+
+    unsafe fn panels(self, _p: usize, _index: usize, _p_b: usize) -> MutMatrix<F> {
+        unreachable!("matrices are packed by the blocked loops")
+    }
+}
+
+// Runs the blocked loops with caller-owned packing buffers, which must hold
+// at least `min(MC, m) * min(KC, k)` and `min(KC, k) * min(NC, n)` elements.
+pub(crate) unsafe fn gemm_blocked<E, F, K, MR, NR, A, B, C, P>(
+    e: &E,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: F,
+    a: A,
+    b: B,
+    beta: F,
+    c: C,
+    blocking: Blocking,
+    packed_a: *mut F,
+    packed_b: *mut F,
+    epilogue: P,
+) where
+    E: Executor,
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+    A: Matrix<F>,
+    B: Matrix<F>,
+    C: MatrixMut<F>,
+    P: Epilogue<F>,
+{
+    gemm_operands::<E, F, K, MR, NR, _, _, _, _>(
+        e,
+        m,
+        n,
+        k,
+        alpha,
+        Raw(a),
+        Raw(b),
+        beta,
+        c,
+        blocking,
+        packed_a,
+        packed_b,
+        epilogue,
+    );
+}
+
+// Same as `gemm_blocked` for operands that may be packed beforehand, whose
+// buffer is then not used.
+pub(crate) unsafe fn gemm_operands<E, F, K, MR, NR, A, B, C, P>(
+    e: &E,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: F,
+    a: A,
+    b: B,
+    beta: F,
+    c: C,
+    blocking: Blocking,
+    packed_a: *mut F,
+    packed_b: *mut F,
+    epilogue: P,
+) where
+    E: Executor,
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+    A: Operand<F>,
+    B: Operand<F>,
+    C: MatrixMut<F>,
+    P: Epilogue<F>,
+{
+    if k == 0 {
+        scale_c(m, n, beta, c);
+        apply_epilogue(m, n, c, epilogue);
+        return;
+    }
+
+    let Blocking { mc, kc, nc } = blocking;
+
+    for j in (0..n).step_by(nc) {
+        let j_b = std::cmp::min(n - j, nc);
+        for p in (0..k).step_by(kc) {
+            let p_b = std::cmp::min(k - p, kc);
+
+            // beta is applied by the first KC block only, the rest accumulate
+            let beta = if p == 0 { beta } else { F::ONE };
+
+            for i in (0..m).step_by(mc) {
+                let i_b = std::cmp::min(m - i, mc);
+
+                let (a, pa) = match a.matrix() {
+                    Some(a) => (Some(a.sub(p, i)), MutMatrix::new(packed_a, p_b)),
+                    None => (None, a.panels(p, i, p_b)),
+                };
+                let (b, pb) = match b.matrix() {
+                    Some(b) => (Some(b.sub(j, p)), MutMatrix::new(packed_b, p_b)),
+                    None => (None, b.panels(p, j, p_b)),
+                };
+
+                let c = c.sub(j, i);
+
+                // the epilogue goes with the last KC block, once C is final
+                if p + p_b == k {
+                    inner_kernel::<E, F, K, MR, NR, _, _, _, _>(
+                        e,
+                        i_b,
+                        j_b,
+                        p_b,
+                        alpha,
+                        a,
+                        b,
+                        beta,
+                        c,
+                        pa,
+                        pb,
+                        i == 0,
+                        epilogue.sub(i, j),
+                    );
+                } else {
+                    inner_kernel::<E, F, K, MR, NR, _, _, _, _>(
+                        e,
+                        i_b,
+                        j_b,
+                        p_b,
+                        alpha,
+                        a,
+                        b,
+                        beta,
+                        c,
+                        pa,
+                        pb,
+                        i == 0,
+                        NoEpilogue,
+                    );
+                }
+            }
+        }
+    }
+}
+
+pub(crate) unsafe fn scale_c<F: Number, C: MatrixMut<F>>(m: usize, n: usize, beta: F, c: C) {
+    for j in 0..n {
+        for i in 0..m {
+            if beta == F::ZERO {
+                c.set(j, i, F::ZERO);
+            } else {
+                c.set(j, i, beta * c.get(j, i));
             }
         }
     }
@@ -201,19 +1184,23 @@ unsafe fn gemm_template<E, F, K, MR, NR, A, B, C>(
 // +-----------------------------+----+
 //
 
-unsafe fn inner_kernel<E, F, K, MR, NR, A, B, C>(
+// Operands packed beforehand come as `None` with their panels in `pa`/`pb`.
+// Their last panel is padded, so it goes through the main kernel into a tile
+// instead of through the sup kernels, which read the matrices.
+unsafe fn inner_kernel<E, F, K, MR, NR, A, B, C, P>(
     e: &E,
     m: usize,
     n: usize,
     k: usize,
     alpha: F,
-    a: A,
-    b: B,
+    a: Option<A>,
+    b: Option<B>,
     beta: F,
     c: C,
     pa: MutMatrix<F>,
     pb: MutMatrix<F>,
     first_time: bool,
+    epilogue: P,
 ) where
     E: Executor,
     F: Number,
@@ -223,47 +1210,117 @@ unsafe fn inner_kernel<E, F, K, MR, NR, A, B, C>(
     A: Matrix<F>,
     B: Matrix<F>,
     C: MatrixMut<F>,
+    P: Epilogue<F>,
 {
-    let n_left = n % NR::DIM;
-    let n_main = n - n_left;
-
-    let m_left = m % MR::DIM;
-    let m_main = m - m_left;
+    let m_main = match a {
+        Some(_) => m - m % MR::DIM,
+        None => m.div_ceil(MR::DIM) * MR::DIM,
+    };
+    let n_main = match b {
+        Some(_) => n - n % NR::DIM,
+        None => n.div_ceil(NR::DIM) * NR::DIM,
+    };
 
-    if first_time {
-        e.execute(0, n_main, NR::DIM, move |_j| K::pack_row_b(b, pb));
+    if let (Some(b), true) = (b, first_time) {
+        e.execute(0, n_main, NR::DIM, move |j| {
+            K::pack_row_b(b.sub_row(j), pb.sub_row(j))
+        });
     }
 
-    e.execute(0, m_main, MR::DIM, move |i| {
-        K::pack_row_a(a.sub_col(i), pa.sub_row(i))
-    });
+    if let Some(a) = a {
+        e.execute(0, m_main, MR::DIM, move |i| {
+            K::pack_row_a(a.sub_col(i), pa.sub_row(i))
+        });
+    }
 
     e.synchronize();
 
     e.execute(0, n_main, NR::DIM, move |j| {
+        let cols = std::cmp::min(NR::DIM, n - j);
+
         // Section TL
         for i in (0..m_main).step_by(MR::DIM) {
-            K::main_tl(alpha, pa.sub_row(i), pb.sub_row(j), beta, c.sub(j, i));
+            let rows = std::cmp::min(MR::DIM, m - i);
+
+            if rows == MR::DIM && cols == NR::DIM {
+                K::main_tl(
+                    alpha,
+                    pa.sub_row(i),
+                    pb.sub_row(j),
+                    beta,
+                    c.sub(j, i),
+                    epilogue.sub(i, j),
+                );
+            } else {
+                let mut tile = [F::ZERO; MAX_TILE];
+                let t = MutMatrix::new(tile.as_mut_ptr(), MR::DIM);
+
+                K::main_tl(alpha, pa.sub_row(i), pb.sub_row(j), F::ZERO, t, NoEpilogue);
+                store_tile(t, rows, cols, beta, c.sub(j, i), epilogue.sub(i, j));
+            }
         }
 
         // Section TR
-        for i in m_main..m {
-            K::sup_tr(alpha, a.sub_col(i), pb.sub_row(j), beta, c.sub(j, i));
+        if let Some(a) = a {
+            for i in m_main..m {
+                if cols == NR::DIM {
+                    K::sup_tr(
+                        alpha,
+                        a.sub_col(i),
+                        pb.sub_row(j),
+                        beta,
+                        c.sub(j, i),
+                        epilogue.sub(i, j),
+                    );
+                } else {
+                    with_tile(1, cols, beta, c.sub(j, i), epilogue.sub(i, j), |t| {
+                        K::sup_tr(alpha, a.sub_col(i), pb.sub_row(j), F::ZERO, t, NoEpilogue)
+                    });
+                }
+            }
         }
     });
 
-    e.execute(0, n_left * m_main, MR::DIM, move |ji| {
-        let j = n_main + ji / m_main;
-        let i = ji % m_main;
+    if let Some(b) = b {
+        let n_left = n - n_main;
 
-        // Section BL
-        K::sup_bl(alpha, pa.sub_row(i), b.sub_row(j), beta, c.sub(j, i));
-    });
+        e.execute(0, n_left * m_main, MR::DIM, move |ji| {
+            let j = n_main + ji / m_main;
+            let i = ji % m_main;
+            let rows = std::cmp::min(MR::DIM, m - i);
 
-    // Section BR
-    for j in n_main..n {
-        for i in m_main..m {
-            K::sup_br(k, alpha, a.sub_col(i), b.sub_row(j), beta, c.sub(j, i))
+            // Section BL
+            if rows == MR::DIM {
+                K::sup_bl(
+                    alpha,
+                    pa.sub_row(i),
+                    b.sub_row(j),
+                    beta,
+                    c.sub(j, i),
+                    epilogue.sub(i, j),
+                );
+            } else {
+                with_tile(rows, 1, beta, c.sub(j, i), epilogue.sub(i, j), |t| {
+                    K::sup_bl(alpha, pa.sub_row(i), b.sub_row(j), F::ZERO, t, NoEpilogue)
+                });
+            }
+        });
+
+        // Section BR
+        if let Some(a) = a {
+            for j in n_main..n {
+                for i in m_main..m {
+                    K::sup_br(
+                        k,
+                        alpha,
+                        a.sub_col(i),
+                        b.sub_row(j),
+                        beta,
+                        c.sub(j, i),
+                        epilogue.sub(i, j),
+                    )
+                }
+            }
         }
     }
 
@@ -0,0 +1,122 @@
+use crate::executor::Executor;
+use crate::gemm::{Layout, Shared};
+use crate::matrix::Number;
+
+// y = alpha * A * x + beta * y of a column-major m x n A, y is contiguous
+pub(crate) type GemvN<F> = unsafe fn(usize, usize, F, *const F, usize, *const F, usize, F, *mut F);
+
+// y = alpha * A^T * x + beta * y of a column-major m x n A, x is contiguous
+pub(crate) type GemvT<F> = unsafe fn(usize, usize, F, *const F, usize, *const F, F, *mut F, usize);
+
+// Products smaller than this many elements of A run on the calling thread
+const PARALLEL_LEN: usize = 1 << 16;
+
+// Parts of y are multiples of this many elements, a block of the kernels
+const ALIGN: usize = 32;
+
+/// y = alpha * op(A) * x + beta * y of an m x n A, x and y have `n` and `m`
+/// elements (the other way round for op(A) = A^T). The elements of y are
+/// split between the threads of `e`.
+pub(crate) unsafe fn gemv<E: Executor, F: Number>(
+    e: &E,
+    layout: Layout,
+    trans: bool,
+    m: usize,
+    n: usize,
+    alpha: F,
+    a: *const F,
+    lda: usize,
+    x: *const F,
+    incx: usize,
+    beta: F,
+    y: *mut F,
+    incy: usize,
+    gemv_n: GemvN<F>,
+    gemv_t: GemvT<F>,
+) {
+    // row-major A is column-major A^T
+    let (trans, m, n) = match layout {
+        Layout::ColMajor => (trans, m, n),
+        Layout::RowMajor => (!trans, n, m),
+    };
+
+    let (len, inner) = if trans { (n, m) } else { (m, n) };
+
+    if len == 0 {
+        return;
+    }
+
+    let parts = if len * inner >= PARALLEL_LEN {
+        e.num_threads()
+    } else {
+        1
+    };
+    let step = len.div_ceil(parts).div_ceil(ALIGN) * ALIGN;
+    let parts = len.div_ceil(step);
+
+    let a = Shared(a);
+
+    if trans {
+        // the kernel reads x with unit stride
+        let packed_x;
+        let x = if incx == 1 {
+            x
+        } else {
+            packed_x = (0..m).map(|i| *x.add(i * incx)).collect::<Vec<_>>();
+            packed_x.as_ptr()
+        };
+        let x = Shared(x);
+        let y = Shared(y as *const F);
+
+        e.execute(0, parts, 1, move |part| {
+            let j = part * step;
+            let cols = std::cmp::min(n - j, step);
+
+            gemv_t(
+                m,
+                cols,
+                alpha,
+                a.0.add(j * lda),
+                lda,
+                x.0,
+                beta,
+                (y.0 as *mut F).add(j * incy),
+                incy,
+            );
+        });
+        e.synchronize();
+    } else {
+        // the kernel updates y with unit stride
+        let mut packed_y = Vec::new();
+        let y_unit = if incy == 1 {
+            y
+        } else {
+            packed_y.extend((0..m).map(|i| *y.add(i * incy)));
+            packed_y.as_mut_ptr()
+        };
+        let x = Shared(x);
+        let y_unit = Shared(y_unit as *const F);
+
+        e.execute(0, parts, 1, move |part| {
+            let i = part * step;
+            let rows = std::cmp::min(m - i, step);
+
+            gemv_n(
+                rows,
+                n,
+                alpha,
+                a.0.add(i),
+                lda,
+                x.0,
+                incx,
+                beta,
+                (y_unit.0 as *mut F).add(i),
+            );
+        });
+        e.synchronize();
+
+        for (i, &yi) in packed_y.iter().enumerate() {
+            *y.add(i * incy) = yi;
+        }
+    }
+}
@@ -0,0 +1,109 @@
+//! IEEE 754 half precision (f16) and bfloat16 (bf16) elements, both kept in
+//! `u16` bit patterns.
+
+use crate::matrix::Storage;
+
+/// Operand of [`sgemm_mixed`](crate::sgemm_mixed)
+#[derive(Copy, Clone, Debug)]
+pub enum Input {
+    F32(*const f32),
+    F16(*const u16),
+    Bf16(*const u16),
+}
+
+pub fn f16_to_f32(h: u16) -> f32 {
+    let sign = ((h & 0x8000) as u32) << 16;
+    let exp = ((h >> 10) & 0x1f) as u32;
+    let man = (h & 0x3ff) as u32;
+
+    let bits = match (exp, man) {
+        (0, 0) => sign,
+
+        // subnormal, normalized for f32
+        (0, _) => {
+            let shift = man.leading_zeros() - 21;
+            sign | ((113 - shift) << 23) | (((man << shift) & 0x3ff) << 13)
+        }
+
+        // infinity and NaN
+        (0x1f, _) => sign | 0x7f80_0000 | (man << 13),
+
+        _ => sign | ((exp + 112) << 23) | (man << 13),
+    };
+
+    f32::from_bits(bits)
+}
+
+/// Rounds to the nearest f16, ties to even
+pub fn f32_to_f16(x: f32) -> u16 {
+    let bits = x.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xff) as i32;
+    let man = bits & 0x7f_ffff;
+
+    if exp == 0xff {
+        let nan = if man != 0 { 0x200 } else { 0 };
+        return sign | 0x7c00 | nan;
+    }
+
+    let exp = exp - 127 + 15;
+    if exp >= 0x1f {
+        return sign | 0x7c00;
+    }
+
+    let (half, rem, mid) = if exp <= 0 {
+        if exp < -10 {
+            return sign;
+        }
+
+        // subnormal: the hidden bit becomes explicit
+        let shift = (14 - exp) as u32;
+        let man = man | 0x80_0000;
+        (man >> shift, man & ((1 << shift) - 1), 1 << (shift - 1))
+    } else {
+        (((exp as u32) << 10) | (man >> 13), man & 0x1fff, 0x1000)
+    };
+
+    // a carry out of the mantissa moves to the next exponent, up to infinity
+    let round = rem > mid || (rem == mid && half & 1 == 1);
+    sign | (half + round as u32) as u16
+}
+
+pub fn bf16_to_f32(h: u16) -> f32 {
+    f32::from_bits((h as u32) << 16)
+}
+
+/// Rounds to the nearest bf16, ties to even
+pub fn f32_to_bf16(x: f32) -> u16 {
+    let bits = x.to_bits();
+
+    if x.is_nan() {
+        return ((bits >> 16) | 0x40) as u16;
+    }
+
+    ((bits + 0x7fff + ((bits >> 16) & 1)) >> 16) as u16
+}
+
+#[inline]
+pub(crate) fn widen(storage: Storage, bits: u16) -> f32 {
+    match storage {
+        Storage::F16 => f16_to_f32(bits),
+        Storage::Bf16 => bf16_to_f32(bits),
+        Storage::Native => f32::from_bits(bits as u32),
+    }
+}
+
+// Widens `len` contiguous elements, f16 with F16C where the CPU has it
+#[inline]
+pub(crate) unsafe fn widen_slice(storage: Storage, src: *const u16, dst: *mut f32, len: usize) {
+    #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+    {
+        if storage == Storage::F16 && crate::kernel::isa::f16c() {
+            return crate::kernel::avx::f16c::cvtph_ps(src, dst, len);
+        }
+    }
+
+    for i in 0..len {
+        *dst.add(i) = widen(storage, *src.add(i));
+    }
+}
@@ -0,0 +1,15 @@
+use super::intrinsics::*;
+
+#[target_feature(enable = "avx,f16c")]
+pub(crate) unsafe fn cvtph_ps(src: *const u16, dst: *mut f32, len: usize) {
+    let main = len - len % 8;
+
+    for i in (0..main).step_by(8) {
+        let h = _mm_loadu_si128(src.add(i) as *const __m128i);
+        _mm256_storeu_ps(dst.add(i), _mm256_cvtph_ps(h));
+    }
+
+    for i in main..len {
+        *dst.add(i) = crate::half::f16_to_f32(*src.add(i));
+    }
+}
@@ -1,6 +1,7 @@
 use super::intrinsics::*;
 
-#[inline(always)]
+#[inline]
+#[target_feature(enable = "avx")]
 pub unsafe fn hsum_ps(v: __m256) -> f32 {
     let qhigh = _mm256_extractf128_ps(v, 1);
     let qlow = _mm256_castps256_ps128(v);
@@ -13,7 +14,8 @@ pub unsafe fn hsum_ps(v: __m256) -> f32 {
     _mm_cvtss_f32(_mm_add_ss(high, low))
 }
 
-#[inline(always)]
+#[inline]
+#[target_feature(enable = "avx")]
 pub unsafe fn hsum_pd(v: __m256d) -> f64 {
     let vhigh = _mm256_extractf128_pd(v, 1);
     let vlow = _mm256_castpd256_pd128(v);
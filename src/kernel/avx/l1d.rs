@@ -2,6 +2,7 @@ use super::fma::{fmadd_pd, fmsub_pd};
 use super::hsum::hsum_pd;
 use super::intrinsics::*;
 
+#[target_feature(enable = "avx,fma")]
 pub unsafe fn drot(
     n: usize,
     mut x: *mut f64,
@@ -62,6 +63,7 @@ pub unsafe fn drot(
     }
 }
 
+#[target_feature(enable = "avx,fma")]
 pub unsafe fn dswap(n: usize, mut x: *mut f64, incx: usize, mut y: *mut f64, incy: usize) {
     if incx == 1 && incy == 1 {
         for _ in 0..n / 16 {
@@ -109,6 +111,7 @@ pub unsafe fn dswap(n: usize, mut x: *mut f64, incx: usize, mut y: *mut f64, inc
     }
 }
 
+#[target_feature(enable = "avx,fma")]
 pub unsafe fn dscal(n: usize, a: f64, mut x: *mut f64, incx: usize) {
     if incx == 1 {
         let a0 = _mm256_broadcast_sd(&a);
@@ -154,6 +157,7 @@ pub unsafe fn dscal(n: usize, a: f64, mut x: *mut f64, incx: usize) {
     }
 }
 
+#[target_feature(enable = "avx,fma")]
 pub unsafe fn dcopy(n: usize, mut x: *const f64, incx: usize, mut y: *mut f64, incy: usize) {
     if incx == 1 && incy == 1 {
         for _ in 0..n / 32 {
@@ -192,6 +196,7 @@ pub unsafe fn dcopy(n: usize, mut x: *const f64, incx: usize, mut y: *mut f64, i
     }
 }
 
+#[target_feature(enable = "avx,fma")]
 pub unsafe fn daxpy(
     n: usize,
     a: f64,
@@ -234,6 +239,7 @@ pub unsafe fn daxpy(
     }
 }
 
+#[target_feature(enable = "avx,fma")]
 pub unsafe fn ddot(
     n: usize,
     mut x: *const f64,
@@ -286,6 +292,7 @@ pub unsafe fn ddot(
     }
 }
 
+#[target_feature(enable = "avx,fma")]
 pub unsafe fn dnrm2(n: usize, mut x: *const f64, incx: usize) -> f64 {
     if incx == 1 {
         let mut acc0 = _mm256_setzero_pd();
@@ -327,6 +334,7 @@ pub unsafe fn dnrm2(n: usize, mut x: *const f64, incx: usize) -> f64 {
     }
 }
 
+#[target_feature(enable = "avx,fma")]
 pub unsafe fn dasum(n: usize, mut x: *const f64, incx: usize) -> f64 {
     if incx == 1 {
         let mask = _mm256_broadcast_sd(&f64::from_bits(0x7FFF_FFFF_FFFF_FFFF));
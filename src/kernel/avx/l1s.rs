@@ -2,6 +2,7 @@ use super::fma::{fmadd_ps, fmsub_ps};
 use super::hsum::hsum_ps;
 use super::intrinsics::*;
 
+#[target_feature(enable = "avx,fma")]
 pub unsafe fn srot(
     n: usize,
     mut x: *mut f32,
@@ -62,6 +63,7 @@ pub unsafe fn srot(
     }
 }
 
+#[target_feature(enable = "avx,fma")]
 pub unsafe fn sswap(n: usize, mut x: *mut f32, incx: usize, mut y: *mut f32, incy: usize) {
     if incx == 1 && incy == 1 {
         for _ in 0..n / 32 {
@@ -109,6 +111,7 @@ pub unsafe fn sswap(n: usize, mut x: *mut f32, incx: usize, mut y: *mut f32, inc
     }
 }
 
+#[target_feature(enable = "avx,fma")]
 pub unsafe fn sscal(n: usize, a: f32, mut x: *mut f32, incx: usize) {
     if incx == 1 {
         let a0 = _mm256_broadcast_ss(&a);
@@ -154,6 +157,7 @@ pub unsafe fn sscal(n: usize, a: f32, mut x: *mut f32, incx: usize) {
     }
 }
 
+#[target_feature(enable = "avx,fma")]
 pub unsafe fn scopy(n: usize, mut x: *const f32, incx: usize, mut y: *mut f32, incy: usize) {
     if incx == 1 && incy == 1 {
         for _ in 0..n / 64 {
@@ -192,6 +196,7 @@ pub unsafe fn scopy(n: usize, mut x: *const f32, incx: usize, mut y: *mut f32, i
     }
 }
 
+#[target_feature(enable = "avx,fma")]
 pub unsafe fn saxpy(
     n: usize,
     a: f32,
@@ -234,6 +239,7 @@ pub unsafe fn saxpy(
     }
 }
 
+#[target_feature(enable = "avx,fma")]
 pub unsafe fn sdot(
     n: usize,
     mut x: *const f32,
@@ -287,6 +293,7 @@ pub unsafe fn sdot(
     }
 }
 
+#[target_feature(enable = "avx,fma")]
 pub unsafe fn snrm2(n: usize, mut x: *const f32, incx: usize) -> f32 {
     if incx == 1 {
         let mut acc0 = _mm256_setzero_ps();
@@ -328,6 +335,7 @@ pub unsafe fn snrm2(n: usize, mut x: *const f32, incx: usize) -> f32 {
     }
 }
 
+#[target_feature(enable = "avx,fma")]
 pub unsafe fn sasum(n: usize, mut x: *const f32, incx: usize) -> f32 {
     if incx == 1 {
         let mask = _mm256_broadcast_ss(&f32::from_bits(0x7FFF_FFFF));
@@ -0,0 +1,159 @@
+use super::fma::fmadd_pd;
+use super::hsum::hsum_pd;
+use super::intrinsics::*;
+
+// Same as generic::l2d::dgemv_n. A block of 16 rows of y stays in registers
+// while the columns of A stream through.
+#[target_feature(enable = "avx,fma")]
+pub unsafe fn dgemv_n(
+    m: usize,
+    n: usize,
+    alpha: f64,
+    a: *const f64,
+    lda: usize,
+    x: *const f64,
+    incx: usize,
+    beta: f64,
+    y: *mut f64,
+) {
+    let beta0 = _mm256_broadcast_sd(&beta);
+
+    let load_y = |y: *mut f64| {
+        if beta == 0.0 {
+            _mm256_setzero_pd()
+        } else {
+            _mm256_mul_pd(beta0, _mm256_loadu_pd(y))
+        }
+    };
+
+    let mut i = 0;
+
+    while i + 16 <= m {
+        let mut y0 = load_y(y.add(i));
+        let mut y1 = load_y(y.add(i + 4));
+        let mut y2 = load_y(y.add(i + 8));
+        let mut y3 = load_y(y.add(i + 12));
+
+        for j in 0..n {
+            let xj = _mm256_set1_pd(alpha * *x.add(j * incx));
+            let col = a.add(i + j * lda);
+
+            y0 = fmadd_pd(_mm256_loadu_pd(col), xj, y0);
+            y1 = fmadd_pd(_mm256_loadu_pd(col.add(4)), xj, y1);
+            y2 = fmadd_pd(_mm256_loadu_pd(col.add(8)), xj, y2);
+            y3 = fmadd_pd(_mm256_loadu_pd(col.add(12)), xj, y3);
+        }
+
+        _mm256_storeu_pd(y.add(i), y0);
+        _mm256_storeu_pd(y.add(i + 4), y1);
+        _mm256_storeu_pd(y.add(i + 8), y2);
+        _mm256_storeu_pd(y.add(i + 12), y3);
+
+        i += 16;
+    }
+
+    while i + 4 <= m {
+        let mut y0 = load_y(y.add(i));
+
+        for j in 0..n {
+            let xj = _mm256_set1_pd(alpha * *x.add(j * incx));
+            y0 = fmadd_pd(_mm256_loadu_pd(a.add(i + j * lda)), xj, y0);
+        }
+
+        _mm256_storeu_pd(y.add(i), y0);
+
+        i += 4;
+    }
+
+    for i in i..m {
+        let yi = y.add(i);
+
+        let mut acc = if beta == 0.0 { 0.0 } else { beta * *yi };
+        for j in 0..n {
+            acc += alpha * *x.add(j * incx) * *a.add(i + j * lda);
+        }
+
+        *yi = acc;
+    }
+}
+
+// Same as generic::l2d::dgemv_t, four columns of A share the loads of x.
+#[target_feature(enable = "avx,fma")]
+pub unsafe fn dgemv_t(
+    m: usize,
+    n: usize,
+    alpha: f64,
+    a: *const f64,
+    lda: usize,
+    x: *const f64,
+    beta: f64,
+    y: *mut f64,
+    incy: usize,
+) {
+    let m_main = m - m % 4;
+
+    let store_y = |j: usize, acc: f64| {
+        let yj = y.add(j * incy);
+        *yj = if beta == 0.0 {
+            alpha * acc
+        } else {
+            alpha * acc + beta * *yj
+        };
+    };
+
+    let mut j = 0;
+
+    while j + 4 <= n {
+        let c0 = a.add(j * lda);
+        let c1 = c0.add(lda);
+        let c2 = c1.add(lda);
+        let c3 = c2.add(lda);
+
+        let mut acc0 = _mm256_setzero_pd();
+        let mut acc1 = _mm256_setzero_pd();
+        let mut acc2 = _mm256_setzero_pd();
+        let mut acc3 = _mm256_setzero_pd();
+
+        for i in (0..m_main).step_by(4) {
+            let x0 = _mm256_loadu_pd(x.add(i));
+
+            acc0 = fmadd_pd(_mm256_loadu_pd(c0.add(i)), x0, acc0);
+            acc1 = fmadd_pd(_mm256_loadu_pd(c1.add(i)), x0, acc1);
+            acc2 = fmadd_pd(_mm256_loadu_pd(c2.add(i)), x0, acc2);
+            acc3 = fmadd_pd(_mm256_loadu_pd(c3.add(i)), x0, acc3);
+        }
+
+        let mut sum = [hsum_pd(acc0), hsum_pd(acc1), hsum_pd(acc2), hsum_pd(acc3)];
+
+        for i in m_main..m {
+            let xi = *x.add(i);
+
+            sum[0] += *c0.add(i) * xi;
+            sum[1] += *c1.add(i) * xi;
+            sum[2] += *c2.add(i) * xi;
+            sum[3] += *c3.add(i) * xi;
+        }
+
+        for (t, &acc) in sum.iter().enumerate() {
+            store_y(j + t, acc);
+        }
+
+        j += 4;
+    }
+
+    for j in j..n {
+        let col = a.add(j * lda);
+
+        let mut acc0 = _mm256_setzero_pd();
+        for i in (0..m_main).step_by(4) {
+            acc0 = fmadd_pd(_mm256_loadu_pd(col.add(i)), _mm256_loadu_pd(x.add(i)), acc0);
+        }
+
+        let mut acc = hsum_pd(acc0);
+        for i in m_main..m {
+            acc += *col.add(i) * *x.add(i);
+        }
+
+        store_y(j, acc);
+    }
+}
@@ -0,0 +1,159 @@
+use super::fma::fmadd_ps;
+use super::hsum::hsum_ps;
+use super::intrinsics::*;
+
+// Same as generic::l2s::sgemv_n. A block of 32 rows of y stays in registers
+// while the columns of A stream through.
+#[target_feature(enable = "avx,fma")]
+pub unsafe fn sgemv_n(
+    m: usize,
+    n: usize,
+    alpha: f32,
+    a: *const f32,
+    lda: usize,
+    x: *const f32,
+    incx: usize,
+    beta: f32,
+    y: *mut f32,
+) {
+    let beta0 = _mm256_broadcast_ss(&beta);
+
+    let load_y = |y: *mut f32| {
+        if beta == 0.0 {
+            _mm256_setzero_ps()
+        } else {
+            _mm256_mul_ps(beta0, _mm256_loadu_ps(y))
+        }
+    };
+
+    let mut i = 0;
+
+    while i + 32 <= m {
+        let mut y0 = load_y(y.add(i));
+        let mut y1 = load_y(y.add(i + 8));
+        let mut y2 = load_y(y.add(i + 16));
+        let mut y3 = load_y(y.add(i + 24));
+
+        for j in 0..n {
+            let xj = _mm256_set1_ps(alpha * *x.add(j * incx));
+            let col = a.add(i + j * lda);
+
+            y0 = fmadd_ps(_mm256_loadu_ps(col), xj, y0);
+            y1 = fmadd_ps(_mm256_loadu_ps(col.add(8)), xj, y1);
+            y2 = fmadd_ps(_mm256_loadu_ps(col.add(16)), xj, y2);
+            y3 = fmadd_ps(_mm256_loadu_ps(col.add(24)), xj, y3);
+        }
+
+        _mm256_storeu_ps(y.add(i), y0);
+        _mm256_storeu_ps(y.add(i + 8), y1);
+        _mm256_storeu_ps(y.add(i + 16), y2);
+        _mm256_storeu_ps(y.add(i + 24), y3);
+
+        i += 32;
+    }
+
+    while i + 8 <= m {
+        let mut y0 = load_y(y.add(i));
+
+        for j in 0..n {
+            let xj = _mm256_set1_ps(alpha * *x.add(j * incx));
+            y0 = fmadd_ps(_mm256_loadu_ps(a.add(i + j * lda)), xj, y0);
+        }
+
+        _mm256_storeu_ps(y.add(i), y0);
+
+        i += 8;
+    }
+
+    for i in i..m {
+        let yi = y.add(i);
+
+        let mut acc = if beta == 0.0 { 0.0 } else { beta * *yi };
+        for j in 0..n {
+            acc += alpha * *x.add(j * incx) * *a.add(i + j * lda);
+        }
+
+        *yi = acc;
+    }
+}
+
+// Same as generic::l2s::sgemv_t, four columns of A share the loads of x.
+#[target_feature(enable = "avx,fma")]
+pub unsafe fn sgemv_t(
+    m: usize,
+    n: usize,
+    alpha: f32,
+    a: *const f32,
+    lda: usize,
+    x: *const f32,
+    beta: f32,
+    y: *mut f32,
+    incy: usize,
+) {
+    let m_main = m - m % 8;
+
+    let store_y = |j: usize, acc: f32| {
+        let yj = y.add(j * incy);
+        *yj = if beta == 0.0 {
+            alpha * acc
+        } else {
+            alpha * acc + beta * *yj
+        };
+    };
+
+    let mut j = 0;
+
+    while j + 4 <= n {
+        let c0 = a.add(j * lda);
+        let c1 = c0.add(lda);
+        let c2 = c1.add(lda);
+        let c3 = c2.add(lda);
+
+        let mut acc0 = _mm256_setzero_ps();
+        let mut acc1 = _mm256_setzero_ps();
+        let mut acc2 = _mm256_setzero_ps();
+        let mut acc3 = _mm256_setzero_ps();
+
+        for i in (0..m_main).step_by(8) {
+            let x0 = _mm256_loadu_ps(x.add(i));
+
+            acc0 = fmadd_ps(_mm256_loadu_ps(c0.add(i)), x0, acc0);
+            acc1 = fmadd_ps(_mm256_loadu_ps(c1.add(i)), x0, acc1);
+            acc2 = fmadd_ps(_mm256_loadu_ps(c2.add(i)), x0, acc2);
+            acc3 = fmadd_ps(_mm256_loadu_ps(c3.add(i)), x0, acc3);
+        }
+
+        let mut sum = [hsum_ps(acc0), hsum_ps(acc1), hsum_ps(acc2), hsum_ps(acc3)];
+
+        for i in m_main..m {
+            let xi = *x.add(i);
+
+            sum[0] += *c0.add(i) * xi;
+            sum[1] += *c1.add(i) * xi;
+            sum[2] += *c2.add(i) * xi;
+            sum[3] += *c3.add(i) * xi;
+        }
+
+        for (t, &acc) in sum.iter().enumerate() {
+            store_y(j + t, acc);
+        }
+
+        j += 4;
+    }
+
+    for j in j..n {
+        let col = a.add(j * lda);
+
+        let mut acc0 = _mm256_setzero_ps();
+        for i in (0..m_main).step_by(8) {
+            acc0 = fmadd_ps(_mm256_loadu_ps(col.add(i)), _mm256_loadu_ps(x.add(i)), acc0);
+        }
+
+        let mut acc = hsum_ps(acc0);
+        for i in m_main..m {
+            acc += *col.add(i) * *x.add(i);
+        }
+
+        store_y(j, acc);
+    }
+}
@@ -0,0 +1,79 @@
+use super::fma::{fmadd_ps, fnmadd_ps};
+use super::intrinsics::*;
+use crate::complex::Complex;
+use crate::matrix::{Matrix, MatrixMut, MutMatrix, Number};
+
+// The packed panels keep the real and imaginary parts of a k-step apart
+// (see generic::l3c), so every column of C is accumulated in one register
+// of real and one of imaginary parts
+#[target_feature(enable = "avx,fma")]
+pub(crate) unsafe fn cgemm_ukr_8x4<C: MatrixMut<Complex<f32>>>(
+    k: usize,
+    alpha: Complex<f32>,
+    pa: MutMatrix<Complex<f32>>,
+    pb: MutMatrix<Complex<f32>>,
+    beta: Complex<f32>,
+    c: C,
+) {
+    let mut re0 = _mm256_setzero_ps();
+    let mut re1 = _mm256_setzero_ps();
+    let mut re2 = _mm256_setzero_ps();
+    let mut re3 = _mm256_setzero_ps();
+    let mut im0 = _mm256_setzero_ps();
+    let mut im1 = _mm256_setzero_ps();
+    let mut im2 = _mm256_setzero_ps();
+    let mut im3 = _mm256_setzero_ps();
+
+    let mut pa = pa.ptr() as *const f32;
+    let mut pb = pb.ptr() as *const f32;
+
+    for _ in 0..k {
+        let ar = _mm256_loadu_ps(pa);
+        let ai = _mm256_loadu_ps(pa.add(8));
+
+        let br = _mm256_broadcast_ss(&*pb);
+        let bi = _mm256_broadcast_ss(&*pb.add(4));
+        re0 = fnmadd_ps(ai, bi, fmadd_ps(ar, br, re0));
+        im0 = fmadd_ps(ai, br, fmadd_ps(ar, bi, im0));
+
+        let br = _mm256_broadcast_ss(&*pb.add(1));
+        let bi = _mm256_broadcast_ss(&*pb.add(5));
+        re1 = fnmadd_ps(ai, bi, fmadd_ps(ar, br, re1));
+        im1 = fmadd_ps(ai, br, fmadd_ps(ar, bi, im1));
+
+        let br = _mm256_broadcast_ss(&*pb.add(2));
+        let bi = _mm256_broadcast_ss(&*pb.add(6));
+        re2 = fnmadd_ps(ai, bi, fmadd_ps(ar, br, re2));
+        im2 = fmadd_ps(ai, br, fmadd_ps(ar, bi, im2));
+
+        let br = _mm256_broadcast_ss(&*pb.add(3));
+        let bi = _mm256_broadcast_ss(&*pb.add(7));
+        re3 = fnmadd_ps(ai, bi, fmadd_ps(ar, br, re3));
+        im3 = fmadd_ps(ai, br, fmadd_ps(ar, bi, im3));
+
+        pa = pa.add(16);
+        pb = pb.add(8);
+    }
+
+    let mut re = [0.0f32; 8];
+    let mut im = [0.0f32; 8];
+
+    for (j, &(cr, ci)) in [(re0, im0), (re1, im1), (re2, im2), (re3, im3)]
+        .iter()
+        .enumerate()
+    {
+        _mm256_storeu_ps(re.as_mut_ptr(), cr);
+        _mm256_storeu_ps(im.as_mut_ptr(), ci);
+
+        for i in 0..8 {
+            let elem = alpha * Complex::new(re[i], im[i]);
+            let cij = c.index_mut(j, i);
+
+            if beta != Complex::ZERO {
+                *cij = elem + beta * *cij;
+            } else {
+                *cij = elem;
+            }
+        }
+    }
+}
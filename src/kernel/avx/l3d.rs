@@ -1,14 +1,46 @@
 use super::fma::fmadd_pd;
 use super::intrinsics::*;
+use crate::epilogue::{Bias, Linear};
+use crate::matrix::{Matrix, MatrixMut, MutMatrix};
+
+// `linear` of the 4 rows from `row` of column `col` of C
+#[inline]
+#[target_feature(enable = "avx")]
+unsafe fn linear_pd(x: __m256d, linear: &Linear<f64>, row: usize, col: usize) -> __m256d {
+    let mut x = x;
+
+    match linear.scale {
+        Bias::None => {}
+        Bias::Row(scale) => x = _mm256_mul_pd(x, _mm256_loadu_pd(scale.add(row))),
+        Bias::Col(scale) => x = _mm256_mul_pd(x, _mm256_broadcast_sd(&*scale.add(col))),
+    }
+
+    match linear.bias {
+        Bias::None => {}
+        Bias::Row(bias) => x = _mm256_add_pd(x, _mm256_loadu_pd(bias.add(row))),
+        Bias::Col(bias) => x = _mm256_add_pd(x, _mm256_broadcast_sd(&*bias.add(col))),
+    }
+
+    if linear.relu {
+        x = _mm256_max_pd(x, _mm256_setzero_pd());
+    }
+
+    if let Some((min, max)) = linear.clamp {
+        x = _mm256_min_pd(_mm256_max_pd(x, _mm256_set1_pd(min)), _mm256_set1_pd(max));
+    }
+
+    x
+}
 
-pub(crate) unsafe fn dgemm_ukr_8x4(
+#[target_feature(enable = "avx,fma")]
+pub(crate) unsafe fn dgemm_ukr_8x4<C: MatrixMut<f64>>(
     k: usize,
     alpha: f64,
-    pa: *const f64,
-    pb: *const f64,
+    pa: MutMatrix<f64>,
+    pb: MutMatrix<f64>,
     beta: f64,
-    c: *mut f64,
-    ldc: usize,
+    c: C,
+    linear: Option<Linear<f64>>,
 ) {
     let mut mt00 = _mm256_setzero_pd();
     let mut mt01 = _mm256_setzero_pd();
@@ -19,8 +51,8 @@ pub(crate) unsafe fn dgemm_ukr_8x4(
     let mut mt12 = _mm256_setzero_pd();
     let mut mt13 = _mm256_setzero_pd();
 
-    let mut pa = pa;
-    let mut pb = pb;
+    let mut pa = pa.ptr();
+    let mut pb = pb.ptr();
 
     for _ in 0..k {
         let a0 = _mm256_loadu_pd(pa);
@@ -55,10 +87,10 @@ pub(crate) unsafe fn dgemm_ukr_8x4(
     mt12 = _mm256_mul_pd(alpha, mt12);
     mt13 = _mm256_mul_pd(alpha, mt13);
 
-    let ccol0 = c;
-    let ccol1 = c.add(ldc);
-    let ccol2 = c.add(ldc * 2);
-    let ccol3 = c.add(ldc * 3);
+    let ccol0 = c.ptr_mut();
+    let ccol1 = c.row_mut(1);
+    let ccol2 = c.row_mut(2);
+    let ccol3 = c.row_mut(3);
 
     if beta != 0.0 {
         let beta = _mm256_broadcast_sd(&beta);
@@ -73,6 +105,17 @@ pub(crate) unsafe fn dgemm_ukr_8x4(
         mt13 = fmadd_pd(beta, _mm256_loadu_pd(ccol3.add(4)), mt13);
     }
 
+    if let Some(linear) = linear {
+        mt00 = linear_pd(mt00, &linear, 0, 0);
+        mt01 = linear_pd(mt01, &linear, 0, 1);
+        mt02 = linear_pd(mt02, &linear, 0, 2);
+        mt03 = linear_pd(mt03, &linear, 0, 3);
+        mt10 = linear_pd(mt10, &linear, 4, 0);
+        mt11 = linear_pd(mt11, &linear, 4, 1);
+        mt12 = linear_pd(mt12, &linear, 4, 2);
+        mt13 = linear_pd(mt13, &linear, 4, 3);
+    }
+
     _mm256_storeu_pd(ccol0, mt00);
     _mm256_storeu_pd(ccol1, mt01);
     _mm256_storeu_pd(ccol2, mt02);
@@ -83,13 +126,15 @@ pub(crate) unsafe fn dgemm_ukr_8x4(
     _mm256_storeu_pd(ccol3.add(4), mt13);
 }
 
-pub(crate) unsafe fn dgemm_sup_8x1(
+#[target_feature(enable = "avx,fma")]
+pub(crate) unsafe fn dgemm_sup_8x1<B: Matrix<f64>, C: MatrixMut<f64>>(
     k: usize,
     alpha: f64,
-    pa: *const f64,
-    b: *const f64,
+    pa: MutMatrix<f64>,
+    b: B,
     beta: f64,
-    c: *mut f64,
+    c: C,
+    linear: Option<Linear<f64>>,
 ) {
     let mut mt0 = _mm256_setzero_pd();
     let mut mt1 = _mm256_setzero_pd();
@@ -98,16 +143,16 @@ pub(crate) unsafe fn dgemm_sup_8x1(
     let mut b = b;
 
     for _ in 0..k {
-        let a0 = _mm256_loadu_pd(pa);
-        let a1 = _mm256_loadu_pd(pa.add(4));
+        let a0 = _mm256_loadu_pd(pa.ptr());
+        let a1 = _mm256_loadu_pd(pa.col(4));
 
-        let b0 = _mm256_broadcast_sd(&*b);
+        let b0 = _mm256_broadcast_sd(&*b.ptr());
 
         mt0 = fmadd_pd(a0, b0, mt0);
         mt1 = fmadd_pd(a1, b0, mt1);
 
-        pa = pa.add(8);
-        b = b.add(1);
+        pa.shift_col(8);
+        b.inc_col();
     }
 
     let alpha = _mm256_broadcast_sd(&alpha);
@@ -115,17 +160,26 @@ pub(crate) unsafe fn dgemm_sup_8x1(
     mt0 = _mm256_mul_pd(alpha, mt0);
     mt1 = _mm256_mul_pd(alpha, mt1);
 
+    let ccol0 = c.ptr_mut();
+    let ccol1 = c.ptr_mut().add(4);
+
     if beta != 0.0 {
         let beta = _mm256_broadcast_sd(&beta);
 
-        mt0 = fmadd_pd(beta, _mm256_loadu_pd(c), mt0);
-        mt1 = fmadd_pd(beta, _mm256_loadu_pd(c.add(4)), mt1);
+        mt0 = fmadd_pd(beta, _mm256_loadu_pd(ccol0), mt0);
+        mt1 = fmadd_pd(beta, _mm256_loadu_pd(ccol1), mt1);
+    }
+
+    if let Some(linear) = linear {
+        mt0 = linear_pd(mt0, &linear, 0, 0);
+        mt1 = linear_pd(mt1, &linear, 4, 0);
     }
 
-    _mm256_storeu_pd(c, mt0);
-    _mm256_storeu_pd(c.add(4), mt1);
+    _mm256_storeu_pd(ccol0, mt0);
+    _mm256_storeu_pd(ccol1, mt1);
 }
 
+#[target_feature(enable = "avx,fma")]
 pub(crate) unsafe fn dgemm_pa_8x(k: usize, a: *const f64, lda: usize, pa: *mut f64) {
     let mut a = a;
     let mut pa = pa;
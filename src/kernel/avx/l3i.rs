@@ -0,0 +1,73 @@
+use super::intrinsics::*;
+use crate::matrix::{Matrix, MatrixMut, MutMatrix};
+
+// Same layout of the panels as igemm_ukr of the generic kernel, one group of
+// four k-steps of A fills a register.
+//
+// vpmaddubsw saturates the sum of two u8 * i8 products to i16, so A is split
+// into its nibbles: a sum of two nibble * i8 products always fits, and
+// vpmaddwd weighs the high nibble products by 16 while widening them to i32.
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn igemm_ukr_8x6<C: MatrixMut<i32>>(
+    k: usize,
+    alpha: i32,
+    pa: MutMatrix<i32>,
+    pb: MutMatrix<i32>,
+    beta: i32,
+    c: C,
+) {
+    let nibble = _mm256_set1_epi8(0x0f);
+    let ones = _mm256_set1_epi16(1);
+    let sixteens = _mm256_set1_epi16(16);
+
+    let mut c0 = _mm256_setzero_si256();
+    let mut c1 = _mm256_setzero_si256();
+    let mut c2 = _mm256_setzero_si256();
+    let mut c3 = _mm256_setzero_si256();
+    let mut c4 = _mm256_setzero_si256();
+    let mut c5 = _mm256_setzero_si256();
+
+    let mut pa = pa.ptr() as *const u8;
+    let mut pb = pb.ptr() as *const u8;
+
+    macro_rules! step {
+        ($acc:ident, $lo:expr, $hi:expr, $j:expr) => {
+            let b = _mm256_set1_epi32((pb.add($j * 4) as *const i32).read_unaligned());
+            let lo = _mm256_madd_epi16(_mm256_maddubs_epi16($lo, b), ones);
+            let hi = _mm256_madd_epi16(_mm256_maddubs_epi16($hi, b), sixteens);
+            $acc = _mm256_add_epi32($acc, _mm256_add_epi32(lo, hi));
+        };
+    }
+
+    for _ in 0..k.div_ceil(4) {
+        let a = _mm256_loadu_si256(pa as *const __m256i);
+        let lo = _mm256_and_si256(a, nibble);
+        let hi = _mm256_and_si256(_mm256_srli_epi16(a, 4), nibble);
+
+        step!(c0, lo, hi, 0);
+        step!(c1, lo, hi, 1);
+        step!(c2, lo, hi, 2);
+        step!(c3, lo, hi, 3);
+        step!(c4, lo, hi, 4);
+        step!(c5, lo, hi, 5);
+
+        pa = pa.add(32);
+        pb = pb.add(24);
+    }
+
+    let mut tile = [0i32; 8];
+
+    for (j, &acc) in [c0, c1, c2, c3, c4, c5].iter().enumerate() {
+        _mm256_storeu_si256(tile.as_mut_ptr() as *mut __m256i, acc);
+
+        for (i, &elem) in tile.iter().enumerate() {
+            let cij = c.index_mut(j, i);
+
+            if beta != 0 {
+                *cij = alpha * elem + beta * *cij;
+            } else {
+                *cij = alpha * elem;
+            }
+        }
+    }
+}
@@ -1,17 +1,51 @@
 use super::fma::fmadd_ps;
 use super::intrinsics::*;
+use crate::epilogue::{Bias, Linear};
 use crate::kernel::params::single::{MR, NR};
 use crate::matrix::{Matrix, MatrixMut, MutMatrix};
 use crunchy::unroll;
 
+// `linear` of the 8 rows from `row` of column `col` of C
 #[inline]
+#[target_feature(enable = "avx")]
+unsafe fn linear_ps(x: __m256, linear: &Linear<f32>, row: usize, col: usize) -> __m256 {
+    let mut x = x;
+
+    match linear.scale {
+        Bias::None => {}
+        Bias::Row(scale) => x = _mm256_mul_ps(x, _mm256_loadu_ps(scale.add(row))),
+        Bias::Col(scale) => x = _mm256_mul_ps(x, _mm256_broadcast_ss(&*scale.add(col))),
+    }
+
+    match linear.bias {
+        Bias::None => {}
+        Bias::Row(bias) => x = _mm256_add_ps(x, _mm256_loadu_ps(bias.add(row))),
+        Bias::Col(bias) => x = _mm256_add_ps(x, _mm256_broadcast_ss(&*bias.add(col))),
+    }
+
+    // max and min return their second operand for NaN, like f32::max and
+    // f32::min
+    if linear.relu {
+        x = _mm256_max_ps(x, _mm256_setzero_ps());
+    }
+
+    if let Some((min, max)) = linear.clamp {
+        x = _mm256_min_ps(_mm256_max_ps(x, _mm256_set1_ps(min)), _mm256_set1_ps(max));
+    }
+
+    x
+}
+
+#[inline]
+#[target_feature(enable = "avx,fma")]
 pub(crate) unsafe fn sgemm_ukr_16x8<C: MatrixMut<f32>>(
     k: usize,
-    _alpha: f32,
+    alpha: f32,
     pa: MutMatrix<f32>,
     pb: MutMatrix<f32>,
-    _beta: f32,
+    beta: f32,
     c: C,
+    linear: Option<Linear<f32>>,
 ) {
     let mut mt00 = _mm256_setzero_ps();
     let mut mt01 = _mm256_setzero_ps();
@@ -107,25 +141,25 @@ pub(crate) unsafe fn sgemm_ukr_16x8<C: MatrixMut<f32>>(
         pb = pb.add(NR);
     }
 
-    // let alpha = _mm256_broadcast_ss(&alpha);
-
-    // mt00 = _mm256_mul_ps(alpha, mt00);
-    // mt01 = _mm256_mul_ps(alpha, mt01);
-    // mt02 = _mm256_mul_ps(alpha, mt02);
-    // mt03 = _mm256_mul_ps(alpha, mt03);
-    // mt04 = _mm256_mul_ps(alpha, mt04);
-    // // mt05 = _mm256_mul_ps(alpha, mt05);
-    // // mt06 = _mm256_mul_ps(alpha, mt06);
-    // // mt07 = _mm256_mul_ps(alpha, mt07);
-
-    // mt10 = _mm256_mul_ps(alpha, mt10);
-    // mt11 = _mm256_mul_ps(alpha, mt11);
-    // mt12 = _mm256_mul_ps(alpha, mt12);
-    // mt13 = _mm256_mul_ps(alpha, mt13);
-    // mt14 = _mm256_mul_ps(alpha, mt14);
-    // // mt15 = _mm256_mul_ps(alpha, mt15);
-    // // mt16 = _mm256_mul_ps(alpha, mt16);
-    // // mt17 = _mm256_mul_ps(alpha, mt17);
+    let alpha = _mm256_broadcast_ss(&alpha);
+
+    mt00 = _mm256_mul_ps(alpha, mt00);
+    mt01 = _mm256_mul_ps(alpha, mt01);
+    mt02 = _mm256_mul_ps(alpha, mt02);
+    mt03 = _mm256_mul_ps(alpha, mt03);
+    mt04 = _mm256_mul_ps(alpha, mt04);
+    // mt05 = _mm256_mul_ps(alpha, mt05);
+    // mt06 = _mm256_mul_ps(alpha, mt06);
+    // mt07 = _mm256_mul_ps(alpha, mt07);
+
+    mt10 = _mm256_mul_ps(alpha, mt10);
+    mt11 = _mm256_mul_ps(alpha, mt11);
+    mt12 = _mm256_mul_ps(alpha, mt12);
+    mt13 = _mm256_mul_ps(alpha, mt13);
+    mt14 = _mm256_mul_ps(alpha, mt14);
+    // mt15 = _mm256_mul_ps(alpha, mt15);
+    // mt16 = _mm256_mul_ps(alpha, mt16);
+    // mt17 = _mm256_mul_ps(alpha, mt17);
 
     let ccol0 = c.ptr_mut();
     let ccol1 = c.row_mut(1);
@@ -136,39 +170,41 @@ pub(crate) unsafe fn sgemm_ukr_16x8<C: MatrixMut<f32>>(
     // let ccol6 = c.row_mut(6);
     // let ccol7 = c.add(ldc * 7);
 
-    mt00 = _mm256_add_ps(_mm256_loadu_ps(ccol0), mt00);
-    mt01 = _mm256_add_ps(_mm256_loadu_ps(ccol1), mt01);
-    mt02 = _mm256_add_ps(_mm256_loadu_ps(ccol2), mt02);
-    mt03 = _mm256_add_ps(_mm256_loadu_ps(ccol3), mt03);
-    mt04 = _mm256_add_ps(_mm256_loadu_ps(ccol4), mt04);
-
-    mt10 = _mm256_add_ps(_mm256_loadu_ps(ccol0.add(8)), mt10);
-    mt11 = _mm256_add_ps(_mm256_loadu_ps(ccol1.add(8)), mt11);
-    mt12 = _mm256_add_ps(_mm256_loadu_ps(ccol2.add(8)), mt12);
-    mt13 = _mm256_add_ps(_mm256_loadu_ps(ccol3.add(8)), mt13);
-    mt14 = _mm256_add_ps(_mm256_loadu_ps(ccol4.add(8)), mt14);
-
-    // if beta != 0.0 {
-    //     let beta = _mm256_broadcast_ss(&beta);
-
-    //     mt00 = fmadd_ps(beta, _mm256_loadu_ps(ccol0), mt00);
-    //     mt01 = fmadd_ps(beta, _mm256_loadu_ps(ccol1), mt01);
-    //     mt02 = fmadd_ps(beta, _mm256_loadu_ps(ccol2), mt02);
-    //     mt03 = fmadd_ps(beta, _mm256_loadu_ps(ccol3), mt03);
-    //     mt04 = fmadd_ps(beta, _mm256_loadu_ps(ccol4), mt04);
-    //     // mt05 = fmadd_ps(beta, _mm256_loadu_ps(ccol5), mt05);
-    //     // mt06 = fmadd_ps(beta, _mm256_loadu_ps(ccol6), mt06);
-    //     // mt07 = fmadd_ps(beta, _mm256_loadu_ps(ccol7), mt07);
-
-    //     mt10 = fmadd_ps(beta, _mm256_loadu_ps(ccol0.add(8)), mt10);
-    //     mt11 = fmadd_ps(beta, _mm256_loadu_ps(ccol1.add(8)), mt11);
-    //     mt12 = fmadd_ps(beta, _mm256_loadu_ps(ccol2.add(8)), mt12);
-    //     mt13 = fmadd_ps(beta, _mm256_loadu_ps(ccol3.add(8)), mt13);
-    //     mt14 = fmadd_ps(beta, _mm256_loadu_ps(ccol4.add(8)), mt14);
-    //     // mt15 = fmadd_ps(beta, _mm256_loadu_ps(ccol5.add(8)), mt15);
-    //     // mt16 = fmadd_ps(beta, _mm256_loadu_ps(ccol6.add(8)), mt16);
-    //     // mt17 = fmadd_ps(beta, _mm256_loadu_ps(ccol7.add(8)), mt17);
-    // }
+    if beta != 0.0 {
+        let beta = _mm256_broadcast_ss(&beta);
+
+        mt00 = fmadd_ps(beta, _mm256_loadu_ps(ccol0), mt00);
+        mt01 = fmadd_ps(beta, _mm256_loadu_ps(ccol1), mt01);
+        mt02 = fmadd_ps(beta, _mm256_loadu_ps(ccol2), mt02);
+        mt03 = fmadd_ps(beta, _mm256_loadu_ps(ccol3), mt03);
+        mt04 = fmadd_ps(beta, _mm256_loadu_ps(ccol4), mt04);
+        // mt05 = fmadd_ps(beta, _mm256_loadu_ps(ccol5), mt05);
+        // mt06 = fmadd_ps(beta, _mm256_loadu_ps(ccol6), mt06);
+        // mt07 = fmadd_ps(beta, _mm256_loadu_ps(ccol7), mt07);
+
+        mt10 = fmadd_ps(beta, _mm256_loadu_ps(ccol0.add(8)), mt10);
+        mt11 = fmadd_ps(beta, _mm256_loadu_ps(ccol1.add(8)), mt11);
+        mt12 = fmadd_ps(beta, _mm256_loadu_ps(ccol2.add(8)), mt12);
+        mt13 = fmadd_ps(beta, _mm256_loadu_ps(ccol3.add(8)), mt13);
+        mt14 = fmadd_ps(beta, _mm256_loadu_ps(ccol4.add(8)), mt14);
+        // mt15 = fmadd_ps(beta, _mm256_loadu_ps(ccol5.add(8)), mt15);
+        // mt16 = fmadd_ps(beta, _mm256_loadu_ps(ccol6.add(8)), mt16);
+        // mt17 = fmadd_ps(beta, _mm256_loadu_ps(ccol7.add(8)), mt17);
+    }
+
+    if let Some(linear) = linear {
+        mt00 = linear_ps(mt00, &linear, 0, 0);
+        mt01 = linear_ps(mt01, &linear, 0, 1);
+        mt02 = linear_ps(mt02, &linear, 0, 2);
+        mt03 = linear_ps(mt03, &linear, 0, 3);
+        mt04 = linear_ps(mt04, &linear, 0, 4);
+
+        mt10 = linear_ps(mt10, &linear, 8, 0);
+        mt11 = linear_ps(mt11, &linear, 8, 1);
+        mt12 = linear_ps(mt12, &linear, 8, 2);
+        mt13 = linear_ps(mt13, &linear, 8, 3);
+        mt14 = linear_ps(mt14, &linear, 8, 4);
+    }
 
     _mm256_storeu_ps(ccol0, mt00);
     _mm256_storeu_ps(ccol1, mt01);
@@ -189,13 +225,15 @@ pub(crate) unsafe fn sgemm_ukr_16x8<C: MatrixMut<f32>>(
     // _mm256_storeu_ps(ccol7.add(8), mt17);
 }
 
+#[target_feature(enable = "avx,fma")]
 pub(crate) unsafe fn sgemm_sup_16x1<B: Matrix<f32>, C: MatrixMut<f32>>(
     k: usize,
-    _alpha: f32,
+    alpha: f32,
     pa: MutMatrix<f32>,
     b: B,
-    _beta: f32,
+    beta: f32,
     c: C,
+    linear: Option<Linear<f32>>,
 ) {
     let mut mt0 = _mm256_setzero_ps();
     let mut mt1 = _mm256_setzero_ps();
@@ -207,7 +245,7 @@ pub(crate) unsafe fn sgemm_sup_16x1<B: Matrix<f32>, C: MatrixMut<f32>>(
         let a0 = _mm256_load_ps(pa.ptr());
         let a1 = _mm256_load_ps(pa.col(8));
 
-        let b0 = _mm256_broadcast_ss(&*b.ptr());
+        let b0 = _mm256_set1_ps(b.get(0, 0));
 
         mt0 = fmadd_ps(a0, b0, mt0);
         mt1 = fmadd_ps(a1, b0, mt1);
@@ -216,28 +254,31 @@ pub(crate) unsafe fn sgemm_sup_16x1<B: Matrix<f32>, C: MatrixMut<f32>>(
         b.inc_col();
     }
 
-    // let alpha = _mm256_broadcast_ss(&alpha);
+    let alpha = _mm256_broadcast_ss(&alpha);
 
-    // mt0 = _mm256_mul_ps(alpha, mt0);
-    // mt1 = _mm256_mul_ps(alpha, mt1);
+    mt0 = _mm256_mul_ps(alpha, mt0);
+    mt1 = _mm256_mul_ps(alpha, mt1);
 
     let ccol0 = c.ptr_mut();
     let ccol1 = c.ptr_mut().add(8);
 
-    // if beta != 0.0 {
-    //     let beta = _mm256_broadcast_ss(&beta);
+    if beta != 0.0 {
+        let beta = _mm256_broadcast_ss(&beta);
 
-    //     mt0 = fmadd_ps(beta, _mm256_loadu_ps(c), mt0);
-    //     mt1 = fmadd_ps(beta, _mm256_loadu_ps(c.add(8)), mt1);
-    // }
+        mt0 = fmadd_ps(beta, _mm256_loadu_ps(ccol0), mt0);
+        mt1 = fmadd_ps(beta, _mm256_loadu_ps(ccol1), mt1);
+    }
 
-    mt0 = _mm256_add_ps(_mm256_loadu_ps(ccol0), mt0);
-    mt1 = _mm256_add_ps(_mm256_loadu_ps(ccol1), mt1);
+    if let Some(linear) = linear {
+        mt0 = linear_ps(mt0, &linear, 0, 0);
+        mt1 = linear_ps(mt1, &linear, 8, 0);
+    }
 
     _mm256_storeu_ps(ccol0, mt0);
     _mm256_storeu_ps(ccol1, mt1);
 }
 
+#[target_feature(enable = "avx,fma")]
 pub(crate) unsafe fn sgemm_pa_16x(k: usize, a: *const f32, lda: usize, pa: *mut f32) {
     let mut a = a;
     let mut pa = pa;
@@ -0,0 +1,77 @@
+use super::fma::{fmadd_pd, fnmadd_pd};
+use super::intrinsics::*;
+use crate::complex::Complex;
+use crate::matrix::{Matrix, MatrixMut, MutMatrix, Number};
+
+// Same layout of the panels as cgemm_ukr_8x4
+#[target_feature(enable = "avx,fma")]
+pub(crate) unsafe fn zgemm_ukr_4x4<C: MatrixMut<Complex<f64>>>(
+    k: usize,
+    alpha: Complex<f64>,
+    pa: MutMatrix<Complex<f64>>,
+    pb: MutMatrix<Complex<f64>>,
+    beta: Complex<f64>,
+    c: C,
+) {
+    let mut re0 = _mm256_setzero_pd();
+    let mut re1 = _mm256_setzero_pd();
+    let mut re2 = _mm256_setzero_pd();
+    let mut re3 = _mm256_setzero_pd();
+    let mut im0 = _mm256_setzero_pd();
+    let mut im1 = _mm256_setzero_pd();
+    let mut im2 = _mm256_setzero_pd();
+    let mut im3 = _mm256_setzero_pd();
+
+    let mut pa = pa.ptr() as *const f64;
+    let mut pb = pb.ptr() as *const f64;
+
+    for _ in 0..k {
+        let ar = _mm256_loadu_pd(pa);
+        let ai = _mm256_loadu_pd(pa.add(4));
+
+        let br = _mm256_broadcast_sd(&*pb);
+        let bi = _mm256_broadcast_sd(&*pb.add(4));
+        re0 = fnmadd_pd(ai, bi, fmadd_pd(ar, br, re0));
+        im0 = fmadd_pd(ai, br, fmadd_pd(ar, bi, im0));
+
+        let br = _mm256_broadcast_sd(&*pb.add(1));
+        let bi = _mm256_broadcast_sd(&*pb.add(5));
+        re1 = fnmadd_pd(ai, bi, fmadd_pd(ar, br, re1));
+        im1 = fmadd_pd(ai, br, fmadd_pd(ar, bi, im1));
+
+        let br = _mm256_broadcast_sd(&*pb.add(2));
+        let bi = _mm256_broadcast_sd(&*pb.add(6));
+        re2 = fnmadd_pd(ai, bi, fmadd_pd(ar, br, re2));
+        im2 = fmadd_pd(ai, br, fmadd_pd(ar, bi, im2));
+
+        let br = _mm256_broadcast_sd(&*pb.add(3));
+        let bi = _mm256_broadcast_sd(&*pb.add(7));
+        re3 = fnmadd_pd(ai, bi, fmadd_pd(ar, br, re3));
+        im3 = fmadd_pd(ai, br, fmadd_pd(ar, bi, im3));
+
+        pa = pa.add(8);
+        pb = pb.add(8);
+    }
+
+    let mut re = [0.0f64; 4];
+    let mut im = [0.0f64; 4];
+
+    for (j, &(cr, ci)) in [(re0, im0), (re1, im1), (re2, im2), (re3, im3)]
+        .iter()
+        .enumerate()
+    {
+        _mm256_storeu_pd(re.as_mut_ptr(), cr);
+        _mm256_storeu_pd(im.as_mut_ptr(), ci);
+
+        for i in 0..4 {
+            let elem = alpha * Complex::new(re[i], im[i]);
+            let cij = c.index_mut(j, i);
+
+            if beta != Complex::ZERO {
+                *cij = elem + beta * *cij;
+            } else {
+                *cij = elem;
+            }
+        }
+    }
+}
@@ -1,14 +1,25 @@
+pub mod f16c;
 mod fma;
 mod hsum;
 mod intrinsics;
 pub mod l1d;
 pub mod l1s;
+pub mod l2d;
+pub mod l2s;
+pub mod l3c;
 pub mod l3d;
+pub mod l3i;
 pub mod l3s;
+pub mod l3z;
 
+use crate::complex::Complex;
 use crate::dim::*;
-use crate::kernel::{GemmKernel, GemmKernelSup, GemmKernelSupMr, GemmKernelSupNr};
-use crate::matrix::{Matrix, MatrixMut, MutMatrix, Number};
+use crate::epilogue::Epilogue;
+use crate::kernel::{
+    apply_epilogue, params, store_tile, with_tile, Blocking, GemmKernel, GemmKernelSup,
+    GemmKernelSupMr, GemmKernelSupNr,
+};
+use crate::matrix::{Matrix, MatrixMut, MutMatrix, Number, Storage};
 use core::marker::PhantomData;
 
 pub struct AvxKernel<F: Number, I>(PhantomData<fn(F, I)>);
@@ -18,14 +29,15 @@ where
     I: GemmKernelSupNr<f32, A5>,
 {
     #[inline]
-    unsafe fn sup_tr<A: Matrix<f32>, C: MatrixMut<f32>>(
+    unsafe fn sup_tr<A: Matrix<f32>, C: MatrixMut<f32>, P: Epilogue<f32>>(
         alpha: f32,
         a: A,
         pb: MutMatrix<f32>,
         beta: f32,
         c: C,
+        epilogue: P,
     ) {
-        I::sup_tr(alpha, a, pb, beta, c);
+        I::sup_tr(alpha, a, pb, beta, c, epilogue);
     }
 }
 
@@ -34,14 +46,31 @@ where
     I: GemmKernelSupMr<f32, A16>,
 {
     #[inline]
-    unsafe fn sup_bl<B: Matrix<f32>, C: MatrixMut<f32>>(
+    unsafe fn sup_bl<B: Matrix<f32>, C: MatrixMut<f32>, P: Epilogue<f32>>(
         alpha: f32,
         pa: MutMatrix<f32>,
         b: B,
         beta: f32,
         c: C,
+        epilogue: P,
     ) {
-        self::l3s::sgemm_sup_16x1(pa.stride, alpha, pa, b, beta, c);
+        use crate::kernel::params::single::MR;
+
+        if c.col_stride() == 1 {
+            let linear = epilogue.linear();
+
+            self::l3s::sgemm_sup_16x1(pa.stride, alpha, pa, b, beta, c, linear);
+
+            if linear.is_none() {
+                apply_epilogue(MR, 1, c, epilogue);
+            }
+        } else {
+            let mut tile = [0.0; MR];
+            let t = MutMatrix::new(tile.as_mut_ptr(), MR);
+
+            self::l3s::sgemm_sup_16x1(pa.stride, alpha, pa, b, 0.0, t, None);
+            store_tile(t, MR, 1, beta, c, epilogue);
+        }
     }
 }
 
@@ -50,15 +79,16 @@ where
     I: GemmKernelSup<f32>,
 {
     #[inline]
-    unsafe fn sup_br<A: Matrix<f32>, B: Matrix<f32>, C: MatrixMut<f32>>(
+    unsafe fn sup_br<A: Matrix<f32>, B: Matrix<f32>, C: MatrixMut<f32>, P: Epilogue<f32>>(
         k: usize,
         alpha: f32,
         a: A,
         b: B,
         beta: f32,
         c: C,
+        epilogue: P,
     ) {
-        I::sup_br(k, alpha, a, b, beta, c);
+        I::sup_br(k, alpha, a, b, beta, c, epilogue);
     }
 }
 
@@ -66,12 +96,17 @@ impl<I> GemmKernel<f32, A16, A5> for AvxKernel<f32, I>
 where
     I: GemmKernel<f32, A16, A5>,
 {
+    #[inline]
+    fn blocking() -> Blocking {
+        params::single::blocking()
+    }
+
     #[inline]
     unsafe fn pack_row_a<A: Matrix<f32>>(a: A, pa: MutMatrix<f32>) {
-        if a.is_transposed() {
-            I::pack_row_a(a, pa);
+        if a.col_stride() == 1 && a.storage() == Storage::Native {
+            self::l3s::sgemm_pa_16x(pa.stride, a.ptr(), a.row_stride(), pa.ptr_mut());
         } else {
-            self::l3s::sgemm_pa_16x(pa.stride, a.ptr(), a.stride(), pa.ptr_mut());
+            I::pack_row_a(a, pa);
         }
     }
 
@@ -81,13 +116,453 @@ where
     }
 
     #[inline]
-    unsafe fn main_tl<C: MatrixMut<f32>>(
+    unsafe fn main_tl<C: MatrixMut<f32>, P: Epilogue<f32>>(
         alpha: f32,
         pa: MutMatrix<f32>,
         pb: MutMatrix<f32>,
         beta: f32,
         c: C,
+        epilogue: P,
+    ) {
+        use crate::kernel::params::single::{MR, NR};
+
+        if c.col_stride() == 1 {
+            let linear = epilogue.linear();
+
+            self::l3s::sgemm_ukr_16x8(pa.stride, alpha, pa, pb, beta, c, linear);
+
+            if linear.is_none() {
+                apply_epilogue(MR, NR, c, epilogue);
+            }
+        } else {
+            let mut tile = [0.0; MR * NR];
+            let t = MutMatrix::new(tile.as_mut_ptr(), MR);
+
+            self::l3s::sgemm_ukr_16x8(pa.stride, alpha, pa, pb, 0.0, t, None);
+            store_tile(t, MR, NR, beta, c, epilogue);
+        }
+    }
+}
+
+impl<I> GemmKernelSupNr<f64, A4> for AvxKernel<f64, I>
+where
+    I: GemmKernelSupNr<f64, A4>,
+{
+    #[inline]
+    unsafe fn sup_tr<A: Matrix<f64>, C: MatrixMut<f64>, P: Epilogue<f64>>(
+        alpha: f64,
+        a: A,
+        pb: MutMatrix<f64>,
+        beta: f64,
+        c: C,
+        epilogue: P,
+    ) {
+        I::sup_tr(alpha, a, pb, beta, c, epilogue);
+    }
+}
+
+impl<I> GemmKernelSupMr<f64, A8> for AvxKernel<f64, I>
+where
+    I: GemmKernelSupMr<f64, A8>,
+{
+    #[inline]
+    unsafe fn sup_bl<B: Matrix<f64>, C: MatrixMut<f64>, P: Epilogue<f64>>(
+        alpha: f64,
+        pa: MutMatrix<f64>,
+        b: B,
+        beta: f64,
+        c: C,
+        epilogue: P,
     ) {
-        self::l3s::sgemm_ukr_16x8(pa.stride, alpha, pa, pb, beta, c);
+        use crate::kernel::params::double::MR;
+
+        if c.col_stride() == 1 {
+            let linear = epilogue.linear();
+
+            self::l3d::dgemm_sup_8x1(pa.stride, alpha, pa, b, beta, c, linear);
+
+            if linear.is_none() {
+                apply_epilogue(MR, 1, c, epilogue);
+            }
+        } else {
+            let mut tile = [0.0; MR];
+            let t = MutMatrix::new(tile.as_mut_ptr(), MR);
+
+            self::l3d::dgemm_sup_8x1(pa.stride, alpha, pa, b, 0.0, t, None);
+            store_tile(t, MR, 1, beta, c, epilogue);
+        }
+    }
+}
+
+impl<I> GemmKernelSup<f64> for AvxKernel<f64, I>
+where
+    I: GemmKernelSup<f64>,
+{
+    #[inline]
+    unsafe fn sup_br<A: Matrix<f64>, B: Matrix<f64>, C: MatrixMut<f64>, P: Epilogue<f64>>(
+        k: usize,
+        alpha: f64,
+        a: A,
+        b: B,
+        beta: f64,
+        c: C,
+        epilogue: P,
+    ) {
+        I::sup_br(k, alpha, a, b, beta, c, epilogue);
+    }
+}
+
+impl<I> GemmKernel<f64, A8, A4> for AvxKernel<f64, I>
+where
+    I: GemmKernel<f64, A8, A4>,
+{
+    #[inline]
+    fn blocking() -> Blocking {
+        params::double::blocking()
+    }
+
+    #[inline]
+    unsafe fn pack_row_a<A: Matrix<f64>>(a: A, pa: MutMatrix<f64>) {
+        if a.col_stride() == 1 {
+            self::l3d::dgemm_pa_8x(pa.stride, a.ptr(), a.row_stride(), pa.ptr_mut());
+        } else {
+            I::pack_row_a(a, pa);
+        }
+    }
+
+    #[inline]
+    unsafe fn pack_row_b<B: Matrix<f64>>(b: B, pb: MutMatrix<f64>) {
+        I::pack_row_b(b, pb);
+    }
+
+    #[inline]
+    unsafe fn main_tl<C: MatrixMut<f64>, P: Epilogue<f64>>(
+        alpha: f64,
+        pa: MutMatrix<f64>,
+        pb: MutMatrix<f64>,
+        beta: f64,
+        c: C,
+        epilogue: P,
+    ) {
+        use crate::kernel::params::double::{MR, NR};
+
+        if c.col_stride() == 1 {
+            let linear = epilogue.linear();
+
+            self::l3d::dgemm_ukr_8x4(pa.stride, alpha, pa, pb, beta, c, linear);
+
+            if linear.is_none() {
+                apply_epilogue(MR, NR, c, epilogue);
+            }
+        } else {
+            let mut tile = [0.0; MR * NR];
+            let t = MutMatrix::new(tile.as_mut_ptr(), MR);
+
+            self::l3d::dgemm_ukr_8x4(pa.stride, alpha, pa, pb, 0.0, t, None);
+            store_tile(t, MR, NR, beta, c, epilogue);
+        }
+    }
+}
+
+impl<I> GemmKernelSupNr<Complex<f32>, A4> for AvxKernel<Complex<f32>, I>
+where
+    I: GemmKernelSupNr<Complex<f32>, A4>,
+{
+    #[inline]
+    unsafe fn sup_tr<
+        A: Matrix<Complex<f32>>,
+        C: MatrixMut<Complex<f32>>,
+        P: Epilogue<Complex<f32>>,
+    >(
+        alpha: Complex<f32>,
+        a: A,
+        pb: MutMatrix<Complex<f32>>,
+        beta: Complex<f32>,
+        c: C,
+        epilogue: P,
+    ) {
+        I::sup_tr(alpha, a, pb, beta, c, epilogue);
+    }
+}
+
+impl<I> GemmKernelSupMr<Complex<f32>, A8> for AvxKernel<Complex<f32>, I>
+where
+    I: GemmKernelSupMr<Complex<f32>, A8>,
+{
+    #[inline]
+    unsafe fn sup_bl<
+        B: Matrix<Complex<f32>>,
+        C: MatrixMut<Complex<f32>>,
+        P: Epilogue<Complex<f32>>,
+    >(
+        alpha: Complex<f32>,
+        pa: MutMatrix<Complex<f32>>,
+        b: B,
+        beta: Complex<f32>,
+        c: C,
+        epilogue: P,
+    ) {
+        I::sup_bl(alpha, pa, b, beta, c, epilogue);
+    }
+}
+
+impl<I> GemmKernelSup<Complex<f32>> for AvxKernel<Complex<f32>, I>
+where
+    I: GemmKernelSup<Complex<f32>>,
+{
+    #[inline]
+    unsafe fn sup_br<
+        A: Matrix<Complex<f32>>,
+        B: Matrix<Complex<f32>>,
+        C: MatrixMut<Complex<f32>>,
+        P: Epilogue<Complex<f32>>,
+    >(
+        k: usize,
+        alpha: Complex<f32>,
+        a: A,
+        b: B,
+        beta: Complex<f32>,
+        c: C,
+        epilogue: P,
+    ) {
+        I::sup_br(k, alpha, a, b, beta, c, epilogue);
+    }
+}
+
+impl<I> GemmKernel<Complex<f32>, A8, A4> for AvxKernel<Complex<f32>, I>
+where
+    I: GemmKernel<Complex<f32>, A8, A4>,
+{
+    #[inline]
+    fn blocking() -> Blocking {
+        params::complex_single::blocking()
+    }
+
+    #[inline]
+    unsafe fn pack_row_a<A: Matrix<Complex<f32>>>(a: A, pa: MutMatrix<Complex<f32>>) {
+        I::pack_row_a(a, pa);
+    }
+
+    #[inline]
+    unsafe fn pack_row_b<B: Matrix<Complex<f32>>>(b: B, pb: MutMatrix<Complex<f32>>) {
+        I::pack_row_b(b, pb);
+    }
+
+    #[inline]
+    unsafe fn main_tl<C: MatrixMut<Complex<f32>>, P: Epilogue<Complex<f32>>>(
+        alpha: Complex<f32>,
+        pa: MutMatrix<Complex<f32>>,
+        pb: MutMatrix<Complex<f32>>,
+        beta: Complex<f32>,
+        c: C,
+        epilogue: P,
+    ) {
+        use crate::kernel::params::complex_single::{MR, NR};
+
+        if P::IDENTITY {
+            self::l3c::cgemm_ukr_8x4(pa.stride, alpha, pa, pb, beta, c);
+        } else {
+            with_tile(MR, NR, beta, c, epilogue, |t| {
+                self::l3c::cgemm_ukr_8x4(pa.stride, alpha, pa, pb, Complex::ZERO, t)
+            });
+        }
+    }
+}
+
+impl<I> GemmKernelSupNr<Complex<f64>, A4> for AvxKernel<Complex<f64>, I>
+where
+    I: GemmKernelSupNr<Complex<f64>, A4>,
+{
+    #[inline]
+    unsafe fn sup_tr<
+        A: Matrix<Complex<f64>>,
+        C: MatrixMut<Complex<f64>>,
+        P: Epilogue<Complex<f64>>,
+    >(
+        alpha: Complex<f64>,
+        a: A,
+        pb: MutMatrix<Complex<f64>>,
+        beta: Complex<f64>,
+        c: C,
+        epilogue: P,
+    ) {
+        I::sup_tr(alpha, a, pb, beta, c, epilogue);
+    }
+}
+
+impl<I> GemmKernelSupMr<Complex<f64>, A4> for AvxKernel<Complex<f64>, I>
+where
+    I: GemmKernelSupMr<Complex<f64>, A4>,
+{
+    #[inline]
+    unsafe fn sup_bl<
+        B: Matrix<Complex<f64>>,
+        C: MatrixMut<Complex<f64>>,
+        P: Epilogue<Complex<f64>>,
+    >(
+        alpha: Complex<f64>,
+        pa: MutMatrix<Complex<f64>>,
+        b: B,
+        beta: Complex<f64>,
+        c: C,
+        epilogue: P,
+    ) {
+        I::sup_bl(alpha, pa, b, beta, c, epilogue);
+    }
+}
+
+impl<I> GemmKernelSup<Complex<f64>> for AvxKernel<Complex<f64>, I>
+where
+    I: GemmKernelSup<Complex<f64>>,
+{
+    #[inline]
+    unsafe fn sup_br<
+        A: Matrix<Complex<f64>>,
+        B: Matrix<Complex<f64>>,
+        C: MatrixMut<Complex<f64>>,
+        P: Epilogue<Complex<f64>>,
+    >(
+        k: usize,
+        alpha: Complex<f64>,
+        a: A,
+        b: B,
+        beta: Complex<f64>,
+        c: C,
+        epilogue: P,
+    ) {
+        I::sup_br(k, alpha, a, b, beta, c, epilogue);
+    }
+}
+
+impl<I> GemmKernel<Complex<f64>, A4, A4> for AvxKernel<Complex<f64>, I>
+where
+    I: GemmKernel<Complex<f64>, A4, A4>,
+{
+    #[inline]
+    fn blocking() -> Blocking {
+        params::complex_double::blocking()
+    }
+
+    #[inline]
+    unsafe fn pack_row_a<A: Matrix<Complex<f64>>>(a: A, pa: MutMatrix<Complex<f64>>) {
+        I::pack_row_a(a, pa);
+    }
+
+    #[inline]
+    unsafe fn pack_row_b<B: Matrix<Complex<f64>>>(b: B, pb: MutMatrix<Complex<f64>>) {
+        I::pack_row_b(b, pb);
+    }
+
+    #[inline]
+    unsafe fn main_tl<C: MatrixMut<Complex<f64>>, P: Epilogue<Complex<f64>>>(
+        alpha: Complex<f64>,
+        pa: MutMatrix<Complex<f64>>,
+        pb: MutMatrix<Complex<f64>>,
+        beta: Complex<f64>,
+        c: C,
+        epilogue: P,
+    ) {
+        use crate::kernel::params::complex_double::{MR, NR};
+
+        if P::IDENTITY {
+            self::l3z::zgemm_ukr_4x4(pa.stride, alpha, pa, pb, beta, c);
+        } else {
+            with_tile(MR, NR, beta, c, epilogue, |t| {
+                self::l3z::zgemm_ukr_4x4(pa.stride, alpha, pa, pb, Complex::ZERO, t)
+            });
+        }
+    }
+}
+
+impl<I> GemmKernelSupNr<i32, A6> for AvxKernel<i32, I>
+where
+    I: GemmKernelSupNr<i32, A6>,
+{
+    #[inline]
+    unsafe fn sup_tr<A: Matrix<i32>, C: MatrixMut<i32>, P: Epilogue<i32>>(
+        alpha: i32,
+        a: A,
+        pb: MutMatrix<i32>,
+        beta: i32,
+        c: C,
+        epilogue: P,
+    ) {
+        I::sup_tr(alpha, a, pb, beta, c, epilogue);
+    }
+}
+
+impl<I> GemmKernelSupMr<i32, A8> for AvxKernel<i32, I>
+where
+    I: GemmKernelSupMr<i32, A8>,
+{
+    #[inline]
+    unsafe fn sup_bl<B: Matrix<i32>, C: MatrixMut<i32>, P: Epilogue<i32>>(
+        alpha: i32,
+        pa: MutMatrix<i32>,
+        b: B,
+        beta: i32,
+        c: C,
+        epilogue: P,
+    ) {
+        I::sup_bl(alpha, pa, b, beta, c, epilogue);
+    }
+}
+
+impl<I> GemmKernelSup<i32> for AvxKernel<i32, I>
+where
+    I: GemmKernelSup<i32>,
+{
+    #[inline]
+    unsafe fn sup_br<A: Matrix<i32>, B: Matrix<i32>, C: MatrixMut<i32>, P: Epilogue<i32>>(
+        k: usize,
+        alpha: i32,
+        a: A,
+        b: B,
+        beta: i32,
+        c: C,
+        epilogue: P,
+    ) {
+        I::sup_br(k, alpha, a, b, beta, c, epilogue);
+    }
+}
+
+// Needs AVX2, which `Isa::Avx` does not imply
+impl<I> GemmKernel<i32, A8, A6> for AvxKernel<i32, I>
+where
+    I: GemmKernel<i32, A8, A6>,
+{
+    #[inline]
+    fn blocking() -> Blocking {
+        params::quantized::blocking()
+    }
+
+    #[inline]
+    unsafe fn pack_row_a<A: Matrix<i32>>(a: A, pa: MutMatrix<i32>) {
+        I::pack_row_a(a, pa);
+    }
+
+    #[inline]
+    unsafe fn pack_row_b<B: Matrix<i32>>(b: B, pb: MutMatrix<i32>) {
+        I::pack_row_b(b, pb);
+    }
+
+    #[inline]
+    unsafe fn main_tl<C: MatrixMut<i32>, P: Epilogue<i32>>(
+        alpha: i32,
+        pa: MutMatrix<i32>,
+        pb: MutMatrix<i32>,
+        beta: i32,
+        c: C,
+        epilogue: P,
+    ) {
+        use crate::kernel::params::quantized::{MR, NR};
+
+        if P::IDENTITY {
+            self::l3i::igemm_ukr_8x6(pa.stride, alpha, pa, pb, beta, c);
+        } else {
+            with_tile(MR, NR, beta, c, epilogue, |t| {
+                self::l3i::igemm_ukr_8x6(pa.stride, alpha, pa, pb, 0, t)
+            });
+        }
     }
 }
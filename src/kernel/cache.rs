@@ -0,0 +1,220 @@
+use crate::kernel::Blocking;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CacheLevel {
+    pub size: usize,
+    pub ways: usize,
+    pub line: usize,
+}
+
+impl CacheLevel {
+    // Bytes held by one way of the cache
+    fn way_size(&self) -> usize {
+        self.size / std::cmp::max(self.ways, 1)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Caches {
+    pub l1d: Option<CacheLevel>,
+    pub l2: Option<CacheLevel>,
+    pub l3: Option<CacheLevel>,
+}
+
+impl Caches {
+    fn set(&mut self, level: u32, cache: CacheLevel) {
+        match level {
+            1 => self.l1d = Some(cache),
+            2 => self.l2 = Some(cache),
+            3 => self.l3 = Some(cache),
+            _ => (),
+        }
+    }
+}
+
+/// Data cache hierarchy of the current CPU, from CPUID on x86 and from
+/// `/sys/devices/system/cpu/cpu0/cache` otherwise.
+pub fn detect() -> Caches {
+    let caches = cpuid();
+
+    if caches.l1d.is_some() {
+        caches
+    } else {
+        sysfs()
+    }
+}
+
+/// Analytical blocking model of BLIS (Low et al., "Analytical Modeling Is
+/// Enough for High-Performance BLIS"):
+///
+/// * an MR x KC micro-panel of A and a KC x NR micro-panel of B share L1,
+/// * the MC x KC block of A stays in L2 next to one micro-panel of B,
+/// * the KC x NC block of B stays in L3 next to the block of A.
+///
+/// In each level one way is left to the streaming data of C. Levels which
+/// are unknown keep the value of `default`, and caches too small for the
+/// model (a 2-way L1 leaves no way to A) give `default`.
+pub fn blocking<F>(caches: &Caches, mr: usize, nr: usize, default: Blocking) -> Blocking {
+    let size = std::mem::size_of::<F>();
+    let kc_align = std::cmp::max(32 / size, 1);
+
+    let mut blocking = default;
+
+    if let Some(l1) = caches.l1d {
+        let ways_a = (l1.ways.saturating_sub(1) * mr) / (mr + nr);
+        let kc = ways_a * l1.way_size() / (mr * size);
+
+        blocking.kc = kc - kc % kc_align;
+
+        if blocking.kc == 0 {
+            return default;
+        }
+    }
+
+    if let Some(l2) = caches.l2 {
+        let ways_b = ways_for(blocking.kc * nr * size, &l2);
+        let ways_a = l2.ways.saturating_sub(1 + ways_b);
+        let mc = ways_a * l2.way_size() / (blocking.kc * size);
+
+        blocking.mc = mc - mc % mr;
+
+        if blocking.mc == 0 {
+            return default;
+        }
+    }
+
+    if let Some(l3) = caches.l3 {
+        let ways_a = ways_for(blocking.mc * blocking.kc * size, &l3);
+        let ways_b = l3.ways.saturating_sub(1 + ways_a);
+        let nc = ways_b * l3.way_size() / (blocking.kc * size);
+
+        blocking.nc = nc - nc % nr;
+    }
+
+    match blocking.validate::<F>(mr, nr) {
+        Ok(()) => blocking,
+        Err(_) => default,
+    }
+}
+
+fn ways_for(bytes: usize, cache: &CacheLevel) -> usize {
+    let way = cache.way_size();
+
+    bytes / way + usize::from(!bytes.is_multiple_of(way))
+}
+
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+fn cpuid() -> Caches {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::{__cpuid, __cpuid_count};
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::{__cpuid, __cpuid_count};
+
+    let mut caches = Caches::default();
+
+    // the intrinsics are safe on newer compilers
+    #[allow(unused_unsafe)]
+    unsafe {
+        let vendor = __cpuid(0);
+        let amd = (vendor.ebx, vendor.edx, vendor.ecx) == (0x6874_7541, 0x6974_6e65, 0x444d_4163);
+
+        // deterministic cache parameters: leaf 4 on Intel, 0x8000001d on AMD
+        let leaf = if amd {
+            if __cpuid(0x8000_0000).eax < 0x8000_001d {
+                return caches;
+            }
+            0x8000_001d
+        } else {
+            if vendor.eax < 4 {
+                return caches;
+            }
+            4
+        };
+
+        for index in 0..16 {
+            let r = __cpuid_count(leaf, index);
+
+            // 1 is a data cache, 3 a unified one
+            let kind = r.eax & 0x1f;
+            if kind == 0 {
+                break;
+            }
+            if kind != 1 && kind != 3 {
+                continue;
+            }
+
+            let level = (r.eax >> 5) & 0x7;
+            let line = (r.ebx & 0xfff) as usize + 1;
+            let partitions = ((r.ebx >> 12) & 0x3ff) as usize + 1;
+            let ways = ((r.ebx >> 22) & 0x3ff) as usize + 1;
+            let sets = r.ecx as usize + 1;
+
+            caches.set(
+                level,
+                CacheLevel {
+                    size: ways * partitions * line * sets,
+                    ways,
+                    line,
+                },
+            );
+        }
+    }
+
+    caches
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "x86")))]
+fn cpuid() -> Caches {
+    Caches::default()
+}
+
+fn sysfs() -> Caches {
+    use std::fs;
+
+    let mut caches = Caches::default();
+
+    for index in 0..16 {
+        let dir = format!("/sys/devices/system/cpu/cpu0/cache/index{}", index);
+        let read = |name: &str| fs::read_to_string(format!("{}/{}", dir, name)).ok();
+
+        let kind = match read("type") {
+            Some(kind) => kind,
+            None => break,
+        };
+        if kind.trim() == "Instruction" {
+            continue;
+        }
+
+        let level = read("level").and_then(|s| s.trim().parse().ok());
+        let size = read("size").and_then(|s| parse_size(s.trim()));
+        let ways = read("ways_of_associativity").and_then(|s| s.trim().parse().ok());
+        let line = read("coherency_line_size").and_then(|s| s.trim().parse().ok());
+
+        // fully associative caches report 0 ways
+        if let (Some(level), Some(size), Some(ways), Some(line)) = (level, size, ways, line) {
+            if ways > 0 {
+                caches.set(level, CacheLevel { size, ways, line });
+            }
+        }
+    }
+
+    caches
+}
+
+// Sizes in sysfs look like "48K" or "30M"
+pub(crate) fn parse_size(s: &str) -> Option<usize> {
+    let (digits, unit) = match s.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        Some((i, _)) => s.split_at(i),
+        None => (s, ""),
+    };
+
+    let shift = match unit {
+        "" => 0,
+        "K" => 10,
+        "M" => 20,
+        "G" => 30,
+        _ => return None,
+    };
+
+    digits.parse::<usize>().ok().map(|size| size << shift)
+}
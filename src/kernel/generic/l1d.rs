@@ -0,0 +1,94 @@
+pub unsafe fn drot(
+    n: usize,
+    mut x: *mut f64,
+    incx: usize,
+    mut y: *mut f64,
+    incy: usize,
+    c: f64,
+    s: f64,
+) {
+    for _ in 0..n {
+        let x0 = *x;
+        let y0 = *y;
+
+        *x = c * x0 + s * y0;
+        *y = c * y0 - s * x0;
+
+        x = x.add(incx);
+        y = y.add(incy);
+    }
+}
+
+pub unsafe fn dswap(n: usize, mut x: *mut f64, incx: usize, mut y: *mut f64, incy: usize) {
+    for _ in 0..n {
+        std::ptr::swap(x, y);
+
+        x = x.add(incx);
+        y = y.add(incy);
+    }
+}
+
+pub unsafe fn dscal(n: usize, a: f64, mut x: *mut f64, incx: usize) {
+    for _ in 0..n {
+        *x *= a;
+        x = x.add(incx);
+    }
+}
+
+pub unsafe fn dcopy(n: usize, mut x: *const f64, incx: usize, mut y: *mut f64, incy: usize) {
+    for _ in 0..n {
+        *y = *x;
+        x = x.add(incx);
+        y = y.add(incy);
+    }
+}
+
+pub unsafe fn daxpy(
+    n: usize,
+    a: f64,
+    mut x: *const f64,
+    incx: usize,
+    mut y: *mut f64,
+    incy: usize,
+) {
+    for _ in 0..n {
+        *y += a * *x;
+        x = x.add(incx);
+        y = y.add(incy);
+    }
+}
+
+pub unsafe fn ddot(
+    n: usize,
+    mut x: *const f64,
+    incx: usize,
+    mut y: *const f64,
+    incy: usize,
+) -> f64 {
+    let mut acc = 0.0;
+    for _ in 0..n {
+        acc += *x * *y;
+        x = x.add(incx);
+        y = y.add(incy);
+    }
+    acc
+}
+
+pub unsafe fn dnrm2(n: usize, mut x: *const f64, incx: usize) -> f64 {
+    let mut acc = 0.0;
+    for _ in 0..n {
+        let x0 = *x;
+        acc += x0 * x0;
+        x = x.add(incx);
+    }
+    acc.sqrt()
+}
+
+pub unsafe fn dasum(n: usize, mut x: *const f64, incx: usize) -> f64 {
+    let mut acc = 0.0;
+    for _ in 0..n {
+        acc += (*x).abs();
+        x = x.add(incx);
+    }
+    acc
+}
@@ -0,0 +1,94 @@
+pub unsafe fn srot(
+    n: usize,
+    mut x: *mut f32,
+    incx: usize,
+    mut y: *mut f32,
+    incy: usize,
+    c: f32,
+    s: f32,
+) {
+    for _ in 0..n {
+        let x0 = *x;
+        let y0 = *y;
+
+        *x = c * x0 + s * y0;
+        *y = c * y0 - s * x0;
+
+        x = x.add(incx);
+        y = y.add(incy);
+    }
+}
+
+pub unsafe fn sswap(n: usize, mut x: *mut f32, incx: usize, mut y: *mut f32, incy: usize) {
+    for _ in 0..n {
+        std::ptr::swap(x, y);
+
+        x = x.add(incx);
+        y = y.add(incy);
+    }
+}
+
+pub unsafe fn sscal(n: usize, a: f32, mut x: *mut f32, incx: usize) {
+    for _ in 0..n {
+        *x *= a;
+        x = x.add(incx);
+    }
+}
+
+pub unsafe fn scopy(n: usize, mut x: *const f32, incx: usize, mut y: *mut f32, incy: usize) {
+    for _ in 0..n {
+        *y = *x;
+        x = x.add(incx);
+        y = y.add(incy);
+    }
+}
+
+pub unsafe fn saxpy(
+    n: usize,
+    a: f32,
+    mut x: *const f32,
+    incx: usize,
+    mut y: *mut f32,
+    incy: usize,
+) {
+    for _ in 0..n {
+        *y += a * *x;
+        x = x.add(incx);
+        y = y.add(incy);
+    }
+}
+
+pub unsafe fn sdot(
+    n: usize,
+    mut x: *const f32,
+    incx: usize,
+    mut y: *const f32,
+    incy: usize,
+) -> f32 {
+    let mut acc = 0.0;
+    for _ in 0..n {
+        acc += *x * *y;
+        x = x.add(incx);
+        y = y.add(incy);
+    }
+    acc
+}
+
+pub unsafe fn snrm2(n: usize, mut x: *const f32, incx: usize) -> f32 {
+    let mut acc = 0.0;
+    for _ in 0..n {
+        let x0 = *x;
+        acc += x0 * x0;
+        x = x.add(incx);
+    }
+    acc.sqrt()
+}
+
+pub unsafe fn sasum(n: usize, mut x: *const f32, incx: usize) -> f32 {
+    let mut acc = 0.0;
+    for _ in 0..n {
+        acc += (*x).abs();
+        x = x.add(incx);
+    }
+    acc
+}
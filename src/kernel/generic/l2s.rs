@@ -0,0 +1,55 @@
+// y = alpha * A * x + beta * y of a column-major m x n A, y is contiguous
+pub unsafe fn sgemv_n(
+    m: usize,
+    n: usize,
+    alpha: f32,
+    a: *const f32,
+    lda: usize,
+    x: *const f32,
+    incx: usize,
+    beta: f32,
+    y: *mut f32,
+) {
+    for i in 0..m {
+        let yi = y.add(i);
+        *yi = if beta == 0.0 { 0.0 } else { beta * *yi };
+    }
+
+    for j in 0..n {
+        let xj = alpha * *x.add(j * incx);
+        let col = a.add(j * lda);
+
+        for i in 0..m {
+            *y.add(i) += xj * *col.add(i);
+        }
+    }
+}
+
+// y = alpha * A^T * x + beta * y of a column-major m x n A, x is contiguous
+pub unsafe fn sgemv_t(
+    m: usize,
+    n: usize,
+    alpha: f32,
+    a: *const f32,
+    lda: usize,
+    x: *const f32,
+    beta: f32,
+    y: *mut f32,
+    incy: usize,
+) {
+    for j in 0..n {
+        let col = a.add(j * lda);
+
+        let mut acc = 0.0;
+        for i in 0..m {
+            acc += *col.add(i) * *x.add(i);
+        }
+
+        let yj = y.add(j * incy);
+        *yj = if beta == 0.0 {
+            alpha * acc
+        } else {
+            alpha * acc + beta * *yj
+        };
+    }
+}
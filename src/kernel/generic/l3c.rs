@@ -0,0 +1,189 @@
+// Complex kernels shared by both precisions. A packed k-step of MR (or NR)
+// elements keeps the MR real parts in front of the MR imaginary parts, so
+// that vector kernels load each of them into its own register. Conjugated
+// operands are conjugated while packing.
+
+use crate::complex::Complex;
+use crate::matrix::{Matrix, MatrixMut, MutMatrix, Number};
+
+// Largest MR * NR of the complex kernels
+const MAX_TILE: usize = 32;
+
+#[inline]
+unsafe fn load<F: Copy>(p: *const F, len: usize, i: usize) -> Complex<F> {
+    Complex::new(*p.add(i), *p.add(len + i))
+}
+
+#[inline]
+unsafe fn store<F: Copy>(p: *mut F, len: usize, i: usize, x: Complex<F>) {
+    *p.add(i) = x.re;
+    *p.add(len + i) = x.im;
+}
+
+#[inline]
+unsafe fn update<F, C>(
+    alpha: Complex<F>,
+    elem: Complex<F>,
+    beta: Complex<F>,
+    c: C,
+    j: usize,
+    i: usize,
+) where
+    Complex<F>: Number,
+    C: MatrixMut<Complex<F>>,
+{
+    let cij = c.index_mut(j, i);
+
+    if beta != Complex::ZERO {
+        *cij = alpha * elem + beta * *cij;
+    } else {
+        *cij = alpha * elem;
+    }
+}
+
+pub(crate) unsafe fn cgemm_pa<F, A>(k: usize, mr: usize, a: A, pa: *mut Complex<F>)
+where
+    F: Copy,
+    Complex<F>: Number,
+    A: Matrix<Complex<F>>,
+{
+    let mut a = a;
+    let mut pa = pa as *mut F;
+
+    for _ in 0..k {
+        for i in 0..mr {
+            store(pa, mr, i, a.get(0, i));
+        }
+
+        a.inc_row();
+        pa = pa.add(2 * mr);
+    }
+}
+
+pub(crate) unsafe fn cgemm_pb<F, B>(k: usize, nr: usize, b: B, pb: *mut Complex<F>)
+where
+    F: Copy,
+    Complex<F>: Number,
+    B: Matrix<Complex<F>>,
+{
+    let mut b = b;
+    let mut pb = pb as *mut F;
+
+    for _ in 0..k {
+        for j in 0..nr {
+            store(pb, nr, j, b.get(j, 0));
+        }
+
+        b.inc_col();
+        pb = pb.add(2 * nr);
+    }
+}
+
+pub(crate) unsafe fn cgemm_ukr<F, C>(
+    k: usize,
+    mr: usize,
+    nr: usize,
+    alpha: Complex<F>,
+    pa: MutMatrix<Complex<F>>,
+    pb: MutMatrix<Complex<F>>,
+    beta: Complex<F>,
+    c: C,
+) where
+    F: Copy,
+    Complex<F>: Number,
+    C: MatrixMut<Complex<F>>,
+{
+    let mut acc = [Complex::<F>::ZERO; MAX_TILE];
+
+    let mut pa = pa.ptr() as *const F;
+    let mut pb = pb.ptr() as *const F;
+
+    for _ in 0..k {
+        for j in 0..nr {
+            let b0 = load(pb, nr, j);
+
+            for i in 0..mr {
+                acc[j * mr + i] = acc[j * mr + i] + load(pa, mr, i) * b0;
+            }
+        }
+
+        pa = pa.add(2 * mr);
+        pb = pb.add(2 * nr);
+    }
+
+    for j in 0..nr {
+        for i in 0..mr {
+            update(alpha, acc[j * mr + i], beta, c, j, i);
+        }
+    }
+}
+
+pub(crate) unsafe fn cgemm_sup_1xn<F, A, C>(
+    k: usize,
+    nr: usize,
+    alpha: Complex<F>,
+    a: A,
+    pb: MutMatrix<Complex<F>>,
+    beta: Complex<F>,
+    c: C,
+) where
+    F: Copy,
+    Complex<F>: Number,
+    A: Matrix<Complex<F>>,
+    C: MatrixMut<Complex<F>>,
+{
+    let mut acc = [Complex::<F>::ZERO; MAX_TILE];
+
+    let mut a = a;
+    let mut pb = pb.ptr() as *const F;
+
+    for _ in 0..k {
+        let a0 = a.get(0, 0);
+
+        for (j, elem) in acc.iter_mut().take(nr).enumerate() {
+            *elem = *elem + a0 * load(pb, nr, j);
+        }
+
+        a.inc_row();
+        pb = pb.add(2 * nr);
+    }
+
+    for (j, &elem) in acc.iter().take(nr).enumerate() {
+        update(alpha, elem, beta, c, j, 0);
+    }
+}
+
+pub(crate) unsafe fn cgemm_sup_mx1<F, B, C>(
+    k: usize,
+    mr: usize,
+    alpha: Complex<F>,
+    pa: MutMatrix<Complex<F>>,
+    b: B,
+    beta: Complex<F>,
+    c: C,
+) where
+    F: Copy,
+    Complex<F>: Number,
+    B: Matrix<Complex<F>>,
+    C: MatrixMut<Complex<F>>,
+{
+    let mut acc = [Complex::<F>::ZERO; MAX_TILE];
+
+    let mut pa = pa.ptr() as *const F;
+    let mut b = b;
+
+    for _ in 0..k {
+        let b0 = b.get(0, 0);
+
+        for (i, elem) in acc.iter_mut().take(mr).enumerate() {
+            *elem = *elem + load(pa, mr, i) * b0;
+        }
+
+        pa = pa.add(2 * mr);
+        b.inc_col();
+    }
+
+    for (i, &elem) in acc.iter().take(mr).enumerate() {
+        update(alpha, elem, beta, c, 0, i);
+    }
+}
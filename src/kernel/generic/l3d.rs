@@ -1,12 +1,13 @@
-pub(crate) unsafe fn dgemm_sup_1x4(
+use crate::kernel::params::double::NR;
+use crate::matrix::{Matrix, MatrixMut, MutMatrix};
+
+pub(crate) unsafe fn dgemm_sup_1x4<A: Matrix<f64>, C: MatrixMut<f64>>(
     k: usize,
     alpha: f64,
-    a: *const f64,
-    lda: usize,
-    pb: *const f64,
+    a: A,
+    pb: MutMatrix<f64>,
     beta: f64,
-    c: *mut f64,
-    ldc: usize,
+    c: C,
 ) {
     let mut c0 = 0.0;
     let mut c1 = 0.0;
@@ -17,15 +18,15 @@ pub(crate) unsafe fn dgemm_sup_1x4(
     let mut pb = pb;
 
     for _ in 0..k {
-        let a0 = *a;
+        let a0 = *a.ptr();
 
-        c0 += *pb * a0;
-        c1 += *pb.add(1) * a0;
-        c2 += *pb.add(2) * a0;
-        c3 += *pb.add(3) * a0;
+        c0 += *pb.ptr() * a0;
+        c1 += *pb.col(1) * a0;
+        c2 += *pb.col(2) * a0;
+        c3 += *pb.col(3) * a0;
 
-        a = a.add(lda);
-        pb = pb.add(4);
+        a.inc_row();
+        pb.shift_col(NR);
     }
 
     c0 *= alpha;
@@ -33,16 +34,16 @@ pub(crate) unsafe fn dgemm_sup_1x4(
     c2 *= alpha;
     c3 *= alpha;
 
-    let ccol0 = c;
-    let ccol1 = c.add(ldc);
-    let ccol2 = c.add(ldc * 2);
-    let ccol3 = c.add(ldc * 3);
+    let ccol0 = c.ptr_mut();
+    let ccol1 = c.row_mut(1);
+    let ccol2 = c.row_mut(2);
+    let ccol3 = c.row_mut(3);
 
     if beta != 0.0 {
-        c0 *= beta * *ccol0;
-        c1 *= beta * *ccol1;
-        c2 *= beta * *ccol2;
-        c3 *= beta * *ccol3;
+        c0 += beta * *ccol0;
+        c1 += beta * *ccol1;
+        c2 += beta * *ccol2;
+        c3 += beta * *ccol3;
     }
 
     *ccol0 = c0;
@@ -69,6 +70,157 @@ pub(crate) unsafe fn dgemm_pb_x4(k: usize, b: *const f64, ldb: usize, pb: *mut f
         bcol1 = bcol1.add(1);
         bcol2 = bcol2.add(1);
         bcol3 = bcol3.add(1);
-        pb = pb.add(4);
+        pb = pb.add(NR);
+    }
+}
+
+pub(crate) unsafe fn dgemm_pb_t(k: usize, b: *const f64, ldb: usize, pb: *mut f64) {
+    let mut b = b;
+    let mut pb = pb;
+
+    for _ in 0..k {
+        for j in 0..NR {
+            *pb.add(j) = *b.add(j);
+        }
+
+        pb = pb.add(NR);
+        b = b.add(ldb);
+    }
+}
+
+pub(crate) unsafe fn dgemm_pa_t(k: usize, a: *const f64, lda: usize, pa: *mut f64) {
+    use crate::kernel::params::double::MR;
+    let mut a = a;
+    let mut pa = pa;
+
+    for _ in 0..k {
+        for j in 0..MR {
+            *pa.add(j) = *a.add(j * lda);
+        }
+
+        a = a.add(1);
+        pa = pa.add(MR);
+    }
+}
+
+pub(crate) unsafe fn dgemm_pa_n(k: usize, a: *const f64, lda: usize, pa: *mut f64) {
+    use crate::kernel::params::double::MR;
+    let mut a = a;
+    let mut pa = pa;
+
+    for _ in 0..k {
+        for j in 0..MR {
+            *pa.add(j) = *a.add(j);
+        }
+
+        a = a.add(lda);
+        pa = pa.add(MR);
+    }
+}
+
+pub(crate) unsafe fn dgemm_ukr_8x4<C: MatrixMut<f64>>(
+    k: usize,
+    alpha: f64,
+    pa: MutMatrix<f64>,
+    pb: MutMatrix<f64>,
+    beta: f64,
+    c: C,
+) {
+    use crate::kernel::params::double::MR;
+
+    let mut acc = [[0.0f64; MR]; NR];
+
+    let mut pa = pa.ptr();
+    let mut pb = pb.ptr();
+
+    for _ in 0..k {
+        for (j, col) in acc.iter_mut().enumerate() {
+            let b0 = *pb.add(j);
+
+            for (i, elem) in col.iter_mut().enumerate() {
+                *elem += *pa.add(i) * b0;
+            }
+        }
+
+        pa = pa.add(MR);
+        pb = pb.add(NR);
+    }
+
+    for (j, col) in acc.iter().enumerate() {
+        for (i, &elem) in col.iter().enumerate() {
+            let cij = c.index_mut(j, i);
+
+            if beta != 0.0 {
+                *cij = alpha * elem + beta * *cij;
+            } else {
+                *cij = alpha * elem;
+            }
+        }
+    }
+}
+
+pub(crate) unsafe fn dgemm_sup_8x1<B: Matrix<f64>, C: MatrixMut<f64>>(
+    k: usize,
+    alpha: f64,
+    pa: MutMatrix<f64>,
+    b: B,
+    beta: f64,
+    c: C,
+) {
+    use crate::kernel::params::double::MR;
+
+    let mut acc = [0.0f64; MR];
+
+    let mut pa = pa.ptr();
+    let mut b = b;
+
+    for _ in 0..k {
+        let b0 = *b.ptr();
+
+        for (i, elem) in acc.iter_mut().enumerate() {
+            *elem += *pa.add(i) * b0;
+        }
+
+        pa = pa.add(MR);
+        b.inc_col();
+    }
+
+    for (i, &elem) in acc.iter().enumerate() {
+        let ci = c.index_mut(0, i);
+
+        if beta != 0.0 {
+            *ci = alpha * elem + beta * *ci;
+        } else {
+            *ci = alpha * elem;
+        }
+    }
+}
+
+pub(crate) unsafe fn dgemm_pa_strided<A: Matrix<f64>>(k: usize, a: A, pa: *mut f64) {
+    use crate::kernel::params::double::MR;
+    let mut a = a;
+    let mut pa = pa;
+
+    for _ in 0..k {
+        for j in 0..MR {
+            *pa.add(j) = a.get(0, j);
+        }
+
+        a.inc_row();
+        pa = pa.add(MR);
+    }
+}
+
+pub(crate) unsafe fn dgemm_pb_strided<B: Matrix<f64>>(k: usize, b: B, pb: *mut f64) {
+    let mut b = b;
+    let mut pb = pb;
+
+    for _ in 0..k {
+        for j in 0..NR {
+            *pb.add(j) = b.get(j, 0);
+        }
+
+        b.inc_col();
+        pb = pb.add(NR);
     }
 }
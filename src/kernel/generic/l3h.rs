@@ -0,0 +1,92 @@
+// Packing of f16 and bf16 operands into f32 panels, so that the f32
+// micro-kernels run on them unchanged. Contiguous runs of elements are
+// widened at once, other layouts element by element.
+
+use crate::half::widen_slice;
+use crate::kernel::params::single::{MR, NR};
+use crate::matrix::{Matrix, Storage};
+
+// Widens `runs` runs of `k` contiguous elements placed `ld` apart, run `r`
+// goes to `dst[p * runs + r]`
+unsafe fn widen_runs(
+    storage: Storage,
+    src: *const u16,
+    ld: usize,
+    runs: usize,
+    k: usize,
+    dst: *mut f32,
+) {
+    const CHUNK: usize = 64;
+
+    let mut tmp = [0.0f32; CHUNK];
+
+    for p0 in (0..k).step_by(CHUNK) {
+        let len = std::cmp::min(CHUNK, k - p0);
+
+        for r in 0..runs {
+            widen_slice(storage, src.add(r * ld + p0), tmp.as_mut_ptr(), len);
+
+            for (p, &x) in tmp[..len].iter().enumerate() {
+                *dst.add((p0 + p) * runs + r) = x;
+            }
+        }
+    }
+}
+
+pub(crate) unsafe fn sgemm_pa_half<A: Matrix<f32>>(k: usize, a: A, pa: *mut f32) {
+    if a.row_stride() == 1 {
+        return widen_runs(
+            a.storage(),
+            a.stored_ptr() as *const u16,
+            a.col_stride(),
+            MR,
+            k,
+            pa,
+        );
+    }
+
+    let mut a = a;
+    let mut pa = pa;
+
+    for _ in 0..k {
+        if a.col_stride() == 1 {
+            widen_slice(a.storage(), a.stored_ptr() as *const u16, pa, MR);
+        } else {
+            for i in 0..MR {
+                *pa.add(i) = a.get(0, i);
+            }
+        }
+
+        a.inc_row();
+        pa = pa.add(MR);
+    }
+}
+
+pub(crate) unsafe fn sgemm_pb_half<B: Matrix<f32>>(k: usize, b: B, pb: *mut f32) {
+    if b.col_stride() == 1 {
+        return widen_runs(
+            b.storage(),
+            b.stored_ptr() as *const u16,
+            b.row_stride(),
+            NR,
+            k,
+            pb,
+        );
+    }
+
+    let mut b = b;
+    let mut pb = pb;
+
+    for _ in 0..k {
+        if b.row_stride() == 1 {
+            widen_slice(b.storage(), b.stored_ptr() as *const u16, pb, NR);
+        } else {
+            for j in 0..NR {
+                *pb.add(j) = b.get(j, 0);
+            }
+        }
+
+        b.inc_col();
+        pb = pb.add(NR);
+    }
+}
@@ -0,0 +1,147 @@
+// u8 x i8 kernels accumulating in i32. The panels are packed as bytes in
+// groups of four k-steps: a group holds four u8 of A per row (i8 of B per
+// column), the layout of one vpmaddubsw operand. The last group of a panel
+// is padded with zeros.
+
+use crate::matrix::{Matrix, MatrixMut, MutMatrix};
+
+// Largest MR * NR of the quantized kernels
+const MAX_TILE: usize = 48;
+
+#[inline]
+fn groups(k: usize) -> usize {
+    k.div_ceil(4)
+}
+
+#[inline]
+unsafe fn update<C: MatrixMut<i32>>(alpha: i32, elem: i32, beta: i32, c: C, j: usize, i: usize) {
+    let cij = c.index_mut(j, i);
+
+    if beta != 0 {
+        *cij = alpha * elem + beta * *cij;
+    } else {
+        *cij = alpha * elem;
+    }
+}
+
+pub(crate) unsafe fn igemm_pa<A: Matrix<i32>>(k: usize, mr: usize, a: A, pa: *mut i32) {
+    let mut pa = pa as *mut u8;
+
+    for g in 0..groups(k) {
+        for i in 0..mr {
+            for t in 0..4 {
+                let p = g * 4 + t;
+                *pa.add(i * 4 + t) = if p < k { a.get(p, i) as u8 } else { 0 };
+            }
+        }
+
+        pa = pa.add(4 * mr);
+    }
+}
+
+pub(crate) unsafe fn igemm_pb<B: Matrix<i32>>(k: usize, nr: usize, b: B, pb: *mut i32) {
+    let mut pb = pb as *mut u8;
+
+    for g in 0..groups(k) {
+        for j in 0..nr {
+            for t in 0..4 {
+                let p = g * 4 + t;
+                *pb.add(j * 4 + t) = if p < k { b.get(j, p) as i8 as u8 } else { 0 };
+            }
+        }
+
+        pb = pb.add(4 * nr);
+    }
+}
+
+pub(crate) unsafe fn igemm_ukr<C: MatrixMut<i32>>(
+    k: usize,
+    mr: usize,
+    nr: usize,
+    alpha: i32,
+    pa: MutMatrix<i32>,
+    pb: MutMatrix<i32>,
+    beta: i32,
+    c: C,
+) {
+    let mut acc = [0i32; MAX_TILE];
+
+    let mut pa = pa.ptr() as *const u8;
+    let mut pb = pb.ptr() as *const i8;
+
+    for _ in 0..groups(k) {
+        for j in 0..nr {
+            for i in 0..mr {
+                for t in 0..4 {
+                    let a = *pa.add(i * 4 + t) as i32;
+                    let b = *pb.add(j * 4 + t) as i32;
+
+                    acc[j * mr + i] += a * b;
+                }
+            }
+        }
+
+        pa = pa.add(4 * mr);
+        pb = pb.add(4 * nr);
+    }
+
+    for j in 0..nr {
+        for i in 0..mr {
+            update(alpha, acc[j * mr + i], beta, c, j, i);
+        }
+    }
+}
+
+pub(crate) unsafe fn igemm_sup_1xn<A: Matrix<i32>, C: MatrixMut<i32>>(
+    k: usize,
+    nr: usize,
+    alpha: i32,
+    a: A,
+    pb: MutMatrix<i32>,
+    beta: i32,
+    c: C,
+) {
+    let mut acc = [0i32; MAX_TILE];
+
+    let pb = pb.ptr() as *const i8;
+
+    for p in 0..k {
+        let a0 = a.get(p, 0);
+        let group = pb.add((p / 4) * 4 * nr + p % 4);
+
+        for (j, elem) in acc.iter_mut().take(nr).enumerate() {
+            *elem += a0 * *group.add(j * 4) as i32;
+        }
+    }
+
+    for (j, &elem) in acc.iter().take(nr).enumerate() {
+        update(alpha, elem, beta, c, j, 0);
+    }
+}
+
+pub(crate) unsafe fn igemm_sup_mx1<B: Matrix<i32>, C: MatrixMut<i32>>(
+    k: usize,
+    mr: usize,
+    alpha: i32,
+    pa: MutMatrix<i32>,
+    b: B,
+    beta: i32,
+    c: C,
+) {
+    let mut acc = [0i32; MAX_TILE];
+
+    let pa = pa.ptr() as *const u8;
+
+    for p in 0..k {
+        let b0 = b.get(0, p);
+        let group = pa.add((p / 4) * 4 * mr + p % 4);
+
+        for (i, elem) in acc.iter_mut().take(mr).enumerate() {
+            *elem += *group.add(i * 4) as i32 * b0;
+        }
+    }
+
+    for (i, &elem) in acc.iter().take(mr).enumerate() {
+        update(alpha, elem, beta, c, 0, i);
+    }
+}
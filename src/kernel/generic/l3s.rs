@@ -3,10 +3,10 @@ use crate::matrix::{Matrix, MatrixMut, MutMatrix};
 
 pub(crate) unsafe fn sgemm_sup_1x8<A: Matrix<f32>, C: MatrixMut<f32>>(
     k: usize,
-    _alpha: f32,
+    alpha: f32,
     a: A,
     pb: MutMatrix<f32>,
-    _beta: f32,
+    beta: f32,
     c: C,
 ) {
     let mut c0 = 0.0f32;
@@ -22,7 +22,7 @@ pub(crate) unsafe fn sgemm_sup_1x8<A: Matrix<f32>, C: MatrixMut<f32>>(
     let mut pb = pb;
 
     for _ in 0..k {
-        let a0 = *a.ptr();
+        let a0 = a.get(0, 0);
 
         c0 += *pb.ptr() * a0;
         c1 += *pb.col(1) * a0;
@@ -37,14 +37,14 @@ pub(crate) unsafe fn sgemm_sup_1x8<A: Matrix<f32>, C: MatrixMut<f32>>(
         pb.shift_col(NR);
     }
 
-    // c0 *= alpha;
-    // c1 *= alpha;
-    // c2 *= alpha;
-    // c3 *= alpha;
-    // c4 *= alpha;
-    // // c5 *= alpha;
-    // // c6 *= alpha;
-    // // c7 *= alpha;
+    c0 *= alpha;
+    c1 *= alpha;
+    c2 *= alpha;
+    c3 *= alpha;
+    c4 *= alpha;
+    // c5 *= alpha;
+    // c6 *= alpha;
+    // c7 *= alpha;
 
     let ccol0 = c.ptr_mut();
     let ccol1 = c.row_mut(1);
@@ -55,22 +55,22 @@ pub(crate) unsafe fn sgemm_sup_1x8<A: Matrix<f32>, C: MatrixMut<f32>>(
     // let ccol6 = c.row_mut(6);
     // let ccol7 = c.add(ldc * 7);
 
-    // if beta != 0.0 {
-    //     c0 += beta * *ccol0;
-    //     c1 += beta * *ccol1;
-    //     c2 += beta * *ccol2;
-    //     c3 += beta * *ccol3;
-    //     c4 += beta * *ccol4;
-    //     // c5 += beta * *ccol5;
-    //     // c6 += beta * *ccol6;
-    //     // c7 += beta * *ccol7;
-    // }
-
-    *ccol0 += c0;
-    *ccol1 += c1;
-    *ccol2 += c2;
-    *ccol3 += c3;
-    *ccol4 += c4;
+    if beta != 0.0 {
+        c0 += beta * *ccol0;
+        c1 += beta * *ccol1;
+        c2 += beta * *ccol2;
+        c3 += beta * *ccol3;
+        c4 += beta * *ccol4;
+        // c5 += beta * *ccol5;
+        // c6 += beta * *ccol6;
+        // c7 += beta * *ccol7;
+    }
+
+    *ccol0 = c0;
+    *ccol1 = c1;
+    *ccol2 = c2;
+    *ccol3 = c3;
+    *ccol4 = c4;
     // *ccol5 = c5;
     // *ccol6 = c6;
     // *ccol7 = c7;
@@ -139,3 +139,125 @@ pub(crate) unsafe fn sgemm_pa_t(k: usize, a: *const f32, lda: usize, pa: *mut f3
         pa = pa.add(MR);
     }
 }
+
+pub(crate) unsafe fn sgemm_pa_n(k: usize, a: *const f32, lda: usize, pa: *mut f32) {
+    use crate::kernel::params::single::MR;
+    let mut a = a;
+    let mut pa = pa;
+
+    for _ in 0..k {
+        for j in 0..MR {
+            *pa.add(j) = *a.add(j);
+        }
+
+        a = a.add(lda);
+        pa = pa.add(MR);
+    }
+}
+
+pub(crate) unsafe fn sgemm_ukr_16x5<C: MatrixMut<f32>>(
+    k: usize,
+    alpha: f32,
+    pa: MutMatrix<f32>,
+    pb: MutMatrix<f32>,
+    beta: f32,
+    c: C,
+) {
+    use crate::kernel::params::single::MR;
+
+    let mut acc = [[0.0f32; MR]; NR];
+
+    let mut pa = pa.ptr();
+    let mut pb = pb.ptr();
+
+    for _ in 0..k {
+        for (j, col) in acc.iter_mut().enumerate() {
+            let b0 = *pb.add(j);
+
+            for (i, elem) in col.iter_mut().enumerate() {
+                *elem += *pa.add(i) * b0;
+            }
+        }
+
+        pa = pa.add(MR);
+        pb = pb.add(NR);
+    }
+
+    for (j, col) in acc.iter().enumerate() {
+        for (i, &elem) in col.iter().enumerate() {
+            let cij = c.index_mut(j, i);
+
+            if beta != 0.0 {
+                *cij = alpha * elem + beta * *cij;
+            } else {
+                *cij = alpha * elem;
+            }
+        }
+    }
+}
+
+pub(crate) unsafe fn sgemm_sup_16x1<B: Matrix<f32>, C: MatrixMut<f32>>(
+    k: usize,
+    alpha: f32,
+    pa: MutMatrix<f32>,
+    b: B,
+    beta: f32,
+    c: C,
+) {
+    use crate::kernel::params::single::MR;
+
+    let mut acc = [0.0f32; MR];
+
+    let mut pa = pa.ptr();
+    let mut b = b;
+
+    for _ in 0..k {
+        let b0 = b.get(0, 0);
+
+        for (i, elem) in acc.iter_mut().enumerate() {
+            *elem += *pa.add(i) * b0;
+        }
+
+        pa = pa.add(MR);
+        b.inc_col();
+    }
+
+    for (i, &elem) in acc.iter().enumerate() {
+        let ci = c.index_mut(0, i);
+
+        if beta != 0.0 {
+            *ci = alpha * elem + beta * *ci;
+        } else {
+            *ci = alpha * elem;
+        }
+    }
+}
+
+pub(crate) unsafe fn sgemm_pa_strided<A: Matrix<f32>>(k: usize, a: A, pa: *mut f32) {
+    use crate::kernel::params::single::MR;
+    let mut a = a;
+    let mut pa = pa;
+
+    for _ in 0..k {
+        for j in 0..MR {
+            *pa.add(j) = a.get(0, j);
+        }
+
+        a.inc_row();
+        pa = pa.add(MR);
+    }
+}
+
+pub(crate) unsafe fn sgemm_pb_strided<B: Matrix<f32>>(k: usize, b: B, pb: *mut f32) {
+    let mut b = b;
+    let mut pb = pb;
+
+    for _ in 0..k {
+        for j in 0..NR {
+            *pb.add(j) = b.get(j, 0);
+        }
+
+        b.inc_col();
+        pb = pb.add(NR);
+    }
+}
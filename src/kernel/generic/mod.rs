@@ -1,91 +1,508 @@
+pub mod l1d;
+pub mod l1s;
+pub mod l2d;
+pub mod l2s;
+pub mod l3c;
 pub mod l3d;
+pub mod l3h;
+pub mod l3i;
 pub mod l3s;
 
+use crate::complex::Complex;
 use crate::dim::*;
-use crate::kernel::{GemmKernel, GemmKernelSup, GemmKernelSupMr, GemmKernelSupNr};
-use crate::matrix::{Matrix, MatrixMut, MutMatrix};
+use crate::epilogue::Epilogue;
+use crate::kernel::params;
+use crate::kernel::{
+    with_tile, Blocking, GemmKernel, GemmKernelSup, GemmKernelSupMr, GemmKernelSupNr,
+};
+use crate::matrix::{Matrix, MatrixMut, MutMatrix, Number, Storage};
 
 pub struct GenericKernel;
 
 impl GemmKernelSupNr<f32, A5> for GenericKernel {
     #[inline]
-    unsafe fn sup_tr<A: Matrix<f32>, C: MatrixMut<f32>>(
+    unsafe fn sup_tr<A: Matrix<f32>, C: MatrixMut<f32>, P: Epilogue<f32>>(
         alpha: f32,
         a: A,
         pb: MutMatrix<f32>,
         beta: f32,
         c: C,
+        epilogue: P,
     ) {
-        self::l3s::sgemm_sup_1x8(pb.stride, alpha, a, pb, beta, c)
+        use crate::kernel::params::single::NR;
+
+        if P::IDENTITY {
+            self::l3s::sgemm_sup_1x8(pb.stride, alpha, a, pb, beta, c)
+        } else {
+            with_tile(1, NR, beta, c, epilogue, |t| {
+                self::l3s::sgemm_sup_1x8(pb.stride, alpha, a, pb, 0.0, t)
+            });
+        }
     }
 }
 
 impl GemmKernelSupMr<f32, A16> for GenericKernel {
     #[inline]
-    unsafe fn sup_bl<B: Matrix<f32>, C: MatrixMut<f32>>(
-        _alpha: f32,
-        _pa: MutMatrix<f32>,
-        _b: B,
-        _beta: f32,
-        _c: C,
+    unsafe fn sup_bl<B: Matrix<f32>, C: MatrixMut<f32>, P: Epilogue<f32>>(
+        alpha: f32,
+        pa: MutMatrix<f32>,
+        b: B,
+        beta: f32,
+        c: C,
+        epilogue: P,
     ) {
-        unimplemented!()
+        use crate::kernel::params::single::MR;
+
+        if P::IDENTITY {
+            self::l3s::sgemm_sup_16x1(pa.stride, alpha, pa, b, beta, c)
+        } else {
+            with_tile(MR, 1, beta, c, epilogue, |t| {
+                self::l3s::sgemm_sup_16x1(pa.stride, alpha, pa, b, 0.0, t)
+            });
+        }
     }
 }
 
-impl GemmKernelSup<f32> for GenericKernel {
+impl<F: Number> GemmKernelSup<F> for GenericKernel {
     #[inline]
-    unsafe fn sup_br<A: Matrix<f32>, B: Matrix<f32>, C: MatrixMut<f32>>(
+    unsafe fn sup_br<A: Matrix<F>, B: Matrix<F>, C: MatrixMut<F>, P: Epilogue<F>>(
         k: usize,
-        alpha: f32,
+        alpha: F,
         a: A,
         b: B,
-        beta: f32,
+        beta: F,
         c: C,
+        epilogue: P,
     ) {
-        let mut elem = 0.0;
+        let mut elem = F::ZERO;
 
         for p in 0..k {
-            elem += *a.row(p) * *b.col(p);
+            elem = elem + a.get(p, 0) * b.get(0, p);
         }
 
-        elem *= alpha;
+        elem = elem * alpha;
 
-        if beta != 0.0 {
-            elem += beta * *c.ptr();
+        if beta != F::ZERO {
+            elem = elem + beta * *c.ptr();
         }
 
-        *c.ptr_mut() += elem;
+        *c.ptr_mut() = epilogue.apply(0, 0, elem);
     }
 }
 
 impl GemmKernel<f32, A16, A5> for GenericKernel {
+    #[inline]
+    fn blocking() -> Blocking {
+        params::single::blocking()
+    }
+
     #[inline]
     unsafe fn pack_row_a<A: Matrix<f32>>(a: A, pa: MutMatrix<f32>) {
-        if a.is_transposed() {
-            self::l3s::sgemm_pa_t(pa.stride, a.ptr(), a.stride(), pa.ptr_mut());
+        if a.storage() != Storage::Native {
+            self::l3h::sgemm_pa_half(pa.stride, a, pa.ptr_mut());
+        } else if a.col_stride() == 1 {
+            self::l3s::sgemm_pa_n(pa.stride, a.ptr(), a.row_stride(), pa.ptr_mut());
+        } else if a.row_stride() == 1 {
+            self::l3s::sgemm_pa_t(pa.stride, a.ptr(), a.col_stride(), pa.ptr_mut());
         } else {
-            unimplemented!()
+            self::l3s::sgemm_pa_strided(pa.stride, a, pa.ptr_mut());
         }
     }
 
     #[inline]
     unsafe fn pack_row_b<B: Matrix<f32>>(b: B, pb: MutMatrix<f32>) {
-        if b.is_transposed() {
-            self::l3s::sgemm_pb_t(pb.stride, b.ptr(), b.stride(), pb.ptr_mut());
+        if b.storage() != Storage::Native {
+            self::l3h::sgemm_pb_half(pb.stride, b, pb.ptr_mut());
+        } else if b.col_stride() == 1 {
+            self::l3s::sgemm_pb_x8(pb.stride, b.ptr(), b.row_stride(), pb.ptr_mut());
+        } else if b.row_stride() == 1 {
+            self::l3s::sgemm_pb_t(pb.stride, b.ptr(), b.col_stride(), pb.ptr_mut());
+        } else {
+            self::l3s::sgemm_pb_strided(pb.stride, b, pb.ptr_mut());
+        }
+    }
+
+    #[inline]
+    unsafe fn main_tl<C: MatrixMut<f32>, P: Epilogue<f32>>(
+        alpha: f32,
+        pa: MutMatrix<f32>,
+        pb: MutMatrix<f32>,
+        beta: f32,
+        c: C,
+        epilogue: P,
+    ) {
+        use crate::kernel::params::single::{MR, NR};
+
+        if P::IDENTITY {
+            self::l3s::sgemm_ukr_16x5(pa.stride, alpha, pa, pb, beta, c)
+        } else {
+            with_tile(MR, NR, beta, c, epilogue, |t| {
+                self::l3s::sgemm_ukr_16x5(pa.stride, alpha, pa, pb, 0.0, t)
+            });
+        }
+    }
+}
+
+impl GemmKernelSupNr<f64, A4> for GenericKernel {
+    #[inline]
+    unsafe fn sup_tr<A: Matrix<f64>, C: MatrixMut<f64>, P: Epilogue<f64>>(
+        alpha: f64,
+        a: A,
+        pb: MutMatrix<f64>,
+        beta: f64,
+        c: C,
+        epilogue: P,
+    ) {
+        use crate::kernel::params::double::NR;
+
+        if P::IDENTITY {
+            self::l3d::dgemm_sup_1x4(pb.stride, alpha, a, pb, beta, c)
+        } else {
+            with_tile(1, NR, beta, c, epilogue, |t| {
+                self::l3d::dgemm_sup_1x4(pb.stride, alpha, a, pb, 0.0, t)
+            });
+        }
+    }
+}
+
+impl GemmKernelSupMr<f64, A8> for GenericKernel {
+    #[inline]
+    unsafe fn sup_bl<B: Matrix<f64>, C: MatrixMut<f64>, P: Epilogue<f64>>(
+        alpha: f64,
+        pa: MutMatrix<f64>,
+        b: B,
+        beta: f64,
+        c: C,
+        epilogue: P,
+    ) {
+        use crate::kernel::params::double::MR;
+
+        if P::IDENTITY {
+            self::l3d::dgemm_sup_8x1(pa.stride, alpha, pa, b, beta, c)
+        } else {
+            with_tile(MR, 1, beta, c, epilogue, |t| {
+                self::l3d::dgemm_sup_8x1(pa.stride, alpha, pa, b, 0.0, t)
+            });
+        }
+    }
+}
+
+impl GemmKernel<f64, A8, A4> for GenericKernel {
+    #[inline]
+    fn blocking() -> Blocking {
+        params::double::blocking()
+    }
+
+    #[inline]
+    unsafe fn pack_row_a<A: Matrix<f64>>(a: A, pa: MutMatrix<f64>) {
+        if a.col_stride() == 1 {
+            self::l3d::dgemm_pa_n(pa.stride, a.ptr(), a.row_stride(), pa.ptr_mut());
+        } else if a.row_stride() == 1 {
+            self::l3d::dgemm_pa_t(pa.stride, a.ptr(), a.col_stride(), pa.ptr_mut());
+        } else {
+            self::l3d::dgemm_pa_strided(pa.stride, a, pa.ptr_mut());
+        }
+    }
+
+    #[inline]
+    unsafe fn pack_row_b<B: Matrix<f64>>(b: B, pb: MutMatrix<f64>) {
+        if b.col_stride() == 1 {
+            self::l3d::dgemm_pb_x4(pb.stride, b.ptr(), b.row_stride(), pb.ptr_mut());
+        } else if b.row_stride() == 1 {
+            self::l3d::dgemm_pb_t(pb.stride, b.ptr(), b.col_stride(), pb.ptr_mut());
+        } else {
+            self::l3d::dgemm_pb_strided(pb.stride, b, pb.ptr_mut());
+        }
+    }
+
+    #[inline]
+    unsafe fn main_tl<C: MatrixMut<f64>, P: Epilogue<f64>>(
+        alpha: f64,
+        pa: MutMatrix<f64>,
+        pb: MutMatrix<f64>,
+        beta: f64,
+        c: C,
+        epilogue: P,
+    ) {
+        use crate::kernel::params::double::{MR, NR};
+
+        if P::IDENTITY {
+            self::l3d::dgemm_ukr_8x4(pa.stride, alpha, pa, pb, beta, c)
+        } else {
+            with_tile(MR, NR, beta, c, epilogue, |t| {
+                self::l3d::dgemm_ukr_8x4(pa.stride, alpha, pa, pb, 0.0, t)
+            });
+        }
+    }
+}
+
+impl GemmKernelSupNr<Complex<f32>, A4> for GenericKernel {
+    #[inline]
+    unsafe fn sup_tr<
+        A: Matrix<Complex<f32>>,
+        C: MatrixMut<Complex<f32>>,
+        P: Epilogue<Complex<f32>>,
+    >(
+        alpha: Complex<f32>,
+        a: A,
+        pb: MutMatrix<Complex<f32>>,
+        beta: Complex<f32>,
+        c: C,
+        epilogue: P,
+    ) {
+        use crate::kernel::params::complex_single::NR;
+
+        if P::IDENTITY {
+            self::l3c::cgemm_sup_1xn(pb.stride, NR, alpha, a, pb, beta, c)
+        } else {
+            with_tile(1, NR, beta, c, epilogue, |t| {
+                self::l3c::cgemm_sup_1xn(pb.stride, NR, alpha, a, pb, Complex::ZERO, t)
+            });
+        }
+    }
+}
+
+impl GemmKernelSupMr<Complex<f32>, A8> for GenericKernel {
+    #[inline]
+    unsafe fn sup_bl<
+        B: Matrix<Complex<f32>>,
+        C: MatrixMut<Complex<f32>>,
+        P: Epilogue<Complex<f32>>,
+    >(
+        alpha: Complex<f32>,
+        pa: MutMatrix<Complex<f32>>,
+        b: B,
+        beta: Complex<f32>,
+        c: C,
+        epilogue: P,
+    ) {
+        use crate::kernel::params::complex_single::MR;
+
+        if P::IDENTITY {
+            self::l3c::cgemm_sup_mx1(pa.stride, MR, alpha, pa, b, beta, c)
+        } else {
+            with_tile(MR, 1, beta, c, epilogue, |t| {
+                self::l3c::cgemm_sup_mx1(pa.stride, MR, alpha, pa, b, Complex::ZERO, t)
+            });
+        }
+    }
+}
+
+impl GemmKernel<Complex<f32>, A8, A4> for GenericKernel {
+    #[inline]
+    fn blocking() -> Blocking {
+        params::complex_single::blocking()
+    }
+
+    #[inline]
+    unsafe fn pack_row_a<A: Matrix<Complex<f32>>>(a: A, pa: MutMatrix<Complex<f32>>) {
+        use crate::kernel::params::complex_single::MR;
+
+        self::l3c::cgemm_pa(pa.stride, MR, a, pa.ptr_mut())
+    }
+
+    #[inline]
+    unsafe fn pack_row_b<B: Matrix<Complex<f32>>>(b: B, pb: MutMatrix<Complex<f32>>) {
+        use crate::kernel::params::complex_single::NR;
+
+        self::l3c::cgemm_pb(pb.stride, NR, b, pb.ptr_mut())
+    }
+
+    #[inline]
+    unsafe fn main_tl<C: MatrixMut<Complex<f32>>, P: Epilogue<Complex<f32>>>(
+        alpha: Complex<f32>,
+        pa: MutMatrix<Complex<f32>>,
+        pb: MutMatrix<Complex<f32>>,
+        beta: Complex<f32>,
+        c: C,
+        epilogue: P,
+    ) {
+        use crate::kernel::params::complex_single::{MR, NR};
+
+        if P::IDENTITY {
+            self::l3c::cgemm_ukr(pa.stride, MR, NR, alpha, pa, pb, beta, c)
+        } else {
+            with_tile(MR, NR, beta, c, epilogue, |t| {
+                self::l3c::cgemm_ukr(pa.stride, MR, NR, alpha, pa, pb, Complex::ZERO, t)
+            });
+        }
+    }
+}
+
+impl GemmKernelSupNr<Complex<f64>, A4> for GenericKernel {
+    #[inline]
+    unsafe fn sup_tr<
+        A: Matrix<Complex<f64>>,
+        C: MatrixMut<Complex<f64>>,
+        P: Epilogue<Complex<f64>>,
+    >(
+        alpha: Complex<f64>,
+        a: A,
+        pb: MutMatrix<Complex<f64>>,
+        beta: Complex<f64>,
+        c: C,
+        epilogue: P,
+    ) {
+        use crate::kernel::params::complex_double::NR;
+
+        if P::IDENTITY {
+            self::l3c::cgemm_sup_1xn(pb.stride, NR, alpha, a, pb, beta, c)
+        } else {
+            with_tile(1, NR, beta, c, epilogue, |t| {
+                self::l3c::cgemm_sup_1xn(pb.stride, NR, alpha, a, pb, Complex::ZERO, t)
+            });
+        }
+    }
+}
+
+impl GemmKernelSupMr<Complex<f64>, A4> for GenericKernel {
+    #[inline]
+    unsafe fn sup_bl<
+        B: Matrix<Complex<f64>>,
+        C: MatrixMut<Complex<f64>>,
+        P: Epilogue<Complex<f64>>,
+    >(
+        alpha: Complex<f64>,
+        pa: MutMatrix<Complex<f64>>,
+        b: B,
+        beta: Complex<f64>,
+        c: C,
+        epilogue: P,
+    ) {
+        use crate::kernel::params::complex_double::MR;
+
+        if P::IDENTITY {
+            self::l3c::cgemm_sup_mx1(pa.stride, MR, alpha, pa, b, beta, c)
+        } else {
+            with_tile(MR, 1, beta, c, epilogue, |t| {
+                self::l3c::cgemm_sup_mx1(pa.stride, MR, alpha, pa, b, Complex::ZERO, t)
+            });
+        }
+    }
+}
+
+impl GemmKernel<Complex<f64>, A4, A4> for GenericKernel {
+    #[inline]
+    fn blocking() -> Blocking {
+        params::complex_double::blocking()
+    }
+
+    #[inline]
+    unsafe fn pack_row_a<A: Matrix<Complex<f64>>>(a: A, pa: MutMatrix<Complex<f64>>) {
+        use crate::kernel::params::complex_double::MR;
+
+        self::l3c::cgemm_pa(pa.stride, MR, a, pa.ptr_mut())
+    }
+
+    #[inline]
+    unsafe fn pack_row_b<B: Matrix<Complex<f64>>>(b: B, pb: MutMatrix<Complex<f64>>) {
+        use crate::kernel::params::complex_double::NR;
+
+        self::l3c::cgemm_pb(pb.stride, NR, b, pb.ptr_mut())
+    }
+
+    #[inline]
+    unsafe fn main_tl<C: MatrixMut<Complex<f64>>, P: Epilogue<Complex<f64>>>(
+        alpha: Complex<f64>,
+        pa: MutMatrix<Complex<f64>>,
+        pb: MutMatrix<Complex<f64>>,
+        beta: Complex<f64>,
+        c: C,
+        epilogue: P,
+    ) {
+        use crate::kernel::params::complex_double::{MR, NR};
+
+        if P::IDENTITY {
+            self::l3c::cgemm_ukr(pa.stride, MR, NR, alpha, pa, pb, beta, c)
+        } else {
+            with_tile(MR, NR, beta, c, epilogue, |t| {
+                self::l3c::cgemm_ukr(pa.stride, MR, NR, alpha, pa, pb, Complex::ZERO, t)
+            });
+        }
+    }
+}
+
+impl GemmKernelSupNr<i32, A6> for GenericKernel {
+    #[inline]
+    unsafe fn sup_tr<A: Matrix<i32>, C: MatrixMut<i32>, P: Epilogue<i32>>(
+        alpha: i32,
+        a: A,
+        pb: MutMatrix<i32>,
+        beta: i32,
+        c: C,
+        epilogue: P,
+    ) {
+        use crate::kernel::params::quantized::NR;
+
+        if P::IDENTITY {
+            self::l3i::igemm_sup_1xn(pb.stride, NR, alpha, a, pb, beta, c)
+        } else {
+            with_tile(1, NR, beta, c, epilogue, |t| {
+                self::l3i::igemm_sup_1xn(pb.stride, NR, alpha, a, pb, 0, t)
+            });
+        }
+    }
+}
+
+impl GemmKernelSupMr<i32, A8> for GenericKernel {
+    #[inline]
+    unsafe fn sup_bl<B: Matrix<i32>, C: MatrixMut<i32>, P: Epilogue<i32>>(
+        alpha: i32,
+        pa: MutMatrix<i32>,
+        b: B,
+        beta: i32,
+        c: C,
+        epilogue: P,
+    ) {
+        use crate::kernel::params::quantized::MR;
+
+        if P::IDENTITY {
+            self::l3i::igemm_sup_mx1(pa.stride, MR, alpha, pa, b, beta, c)
         } else {
-            self::l3s::sgemm_pb_x8(pb.stride, b.ptr(), b.stride(), pb.ptr_mut());
+            with_tile(MR, 1, beta, c, epilogue, |t| {
+                self::l3i::igemm_sup_mx1(pa.stride, MR, alpha, pa, b, 0, t)
+            });
         }
     }
+}
+
+impl GemmKernel<i32, A8, A6> for GenericKernel {
+    #[inline]
+    fn blocking() -> Blocking {
+        params::quantized::blocking()
+    }
 
     #[inline]
-    unsafe fn main_tl<C: MatrixMut<f32>>(
-        _alpha: f32,
-        _pa: MutMatrix<f32>,
-        _pb: MutMatrix<f32>,
-        _beta: f32,
-        _c: C,
+    unsafe fn pack_row_a<A: Matrix<i32>>(a: A, pa: MutMatrix<i32>) {
+        use crate::kernel::params::quantized::MR;
+
+        self::l3i::igemm_pa(pa.stride, MR, a, pa.ptr_mut())
+    }
+
+    #[inline]
+    unsafe fn pack_row_b<B: Matrix<i32>>(b: B, pb: MutMatrix<i32>) {
+        use crate::kernel::params::quantized::NR;
+
+        self::l3i::igemm_pb(pb.stride, NR, b, pb.ptr_mut())
+    }
+
+    #[inline]
+    unsafe fn main_tl<C: MatrixMut<i32>, P: Epilogue<i32>>(
+        alpha: i32,
+        pa: MutMatrix<i32>,
+        pb: MutMatrix<i32>,
+        beta: i32,
+        c: C,
+        epilogue: P,
     ) {
-        unimplemented!()
+        use crate::kernel::params::quantized::{MR, NR};
+
+        if P::IDENTITY {
+            self::l3i::igemm_ukr(pa.stride, MR, NR, alpha, pa, pb, beta, c)
+        } else {
+            with_tile(MR, NR, beta, c, epilogue, |t| {
+                self::l3i::igemm_ukr(pa.stride, MR, NR, alpha, pa, pb, 0, t)
+            });
+        }
     }
 }
@@ -0,0 +1,121 @@
+use crate::error::GemmError;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Isa {
+    Generic = 1,
+    Sse = 2,
+    // AVX together with FMA (Haswell and newer)
+    Avx = 3,
+}
+
+impl Isa {
+    pub fn name(self) -> &'static str {
+        match self {
+            Isa::Generic => "generic",
+            Isa::Sse => "sse",
+            Isa::Avx => "avx",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Isa> {
+        match name {
+            "generic" => Some(Isa::Generic),
+            "sse" => Some(Isa::Sse),
+            "avx" => Some(Isa::Avx),
+            _ => None,
+        }
+    }
+
+    fn from_usize(value: usize) -> Option<Isa> {
+        match value {
+            1 => Some(Isa::Generic),
+            2 => Some(Isa::Sse),
+            3 => Some(Isa::Avx),
+            _ => None,
+        }
+    }
+}
+
+static DETECTED: AtomicUsize = AtomicUsize::new(0);
+
+pub fn detect() -> Isa {
+    match Isa::from_usize(DETECTED.load(Ordering::Relaxed)) {
+        Some(isa) => isa,
+        None => {
+            let isa = probe();
+            DETECTED.store(isa as usize, Ordering::Relaxed);
+            isa
+        }
+    }
+}
+
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+pub fn f16c() -> bool {
+    is_x86_feature_detected!("avx") && is_x86_feature_detected!("f16c")
+}
+
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+pub fn avx2() -> bool {
+    is_x86_feature_detected!("avx2")
+}
+
+pub fn supported(isa: Isa) -> bool {
+    isa as usize <= detect() as usize
+}
+
+// The kernel used by one precision. Unless it was set explicitly, the first
+// use picks the best supported one. `kernels` is ordered from the best to the
+// generic one.
+pub(crate) struct Selection(AtomicUsize);
+
+impl Selection {
+    pub(crate) const fn new() -> Self {
+        Selection(AtomicUsize::new(0))
+    }
+
+    pub(crate) fn get(&self, kernels: &[Isa]) -> Isa {
+        if let Some(isa) = Isa::from_usize(self.0.load(Ordering::Relaxed)) {
+            return isa;
+        }
+
+        let isa = kernels
+            .iter()
+            .copied()
+            .find(|isa| supported(*isa))
+            .unwrap_or(Isa::Generic);
+
+        // a kernel set meanwhile wins over the automatic choice
+        let _ = self
+            .0
+            .compare_exchange(0, isa as usize, Ordering::Relaxed, Ordering::Relaxed);
+
+        Isa::from_usize(self.0.load(Ordering::Relaxed)).unwrap_or(isa)
+    }
+
+    pub(crate) fn set(&self, isa: Isa, kernels: &[Isa]) -> Result<(), GemmError> {
+        if !kernels.contains(&isa) || !supported(isa) {
+            return Err(GemmError::UnsupportedIsa { isa });
+        }
+
+        self.0.store(isa as usize, Ordering::Relaxed);
+
+        Ok(())
+    }
+}
+
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+fn probe() -> Isa {
+    if is_x86_feature_detected!("avx") && is_x86_feature_detected!("fma") {
+        Isa::Avx
+    } else if is_x86_feature_detected!("sse2") {
+        Isa::Sse
+    } else {
+        Isa::Generic
+    }
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "x86")))]
+fn probe() -> Isa {
+    Isa::Generic
+}
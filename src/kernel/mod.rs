@@ -1,28 +1,316 @@
-#[cfg(all(
-    any(target_arch = "x86_64", target_arch = "x86"),
-    target_feature = "avx"
-))]
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
 pub mod avx;
+pub mod cache;
 pub mod generic;
+pub mod isa;
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
 pub mod sse;
 
 use crate::dim::Dim;
+use crate::epilogue::Epilogue;
+use crate::error::GemmError;
 use crate::matrix::{Matrix, MatrixMut, MutMatrix, Number};
+use std::sync::{Once, RwLock};
 
 pub mod params {
+    // MC, KC and NC are the defaults used when the cache sizes are unknown
     pub mod single {
+        use crate::error::GemmError;
+        use crate::kernel::isa::{Isa, Selection};
+        use crate::kernel::{AtomicBlocking, Blocking};
+
         pub const MC: usize = 128;
         pub const KC: usize = 256;
-        pub const NC: usize = 8 * 1024;
+        pub const NC: usize = 1638 * NR;
         pub const MR: usize = 16;
         pub const NR: usize = 5;
+
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        pub const KERNELS: &[Isa] = &[Isa::Avx, Isa::Sse, Isa::Generic];
+        #[cfg(not(any(target_arch = "x86_64", target_arch = "x86")))]
+        pub const KERNELS: &[Isa] = &[Isa::Generic];
+
+        static ISA: Selection = Selection::new();
+        static BLOCKING: AtomicBlocking = AtomicBlocking::new(MC, KC, NC);
+
+        pub fn isa() -> Isa {
+            ISA.get(KERNELS)
+        }
+
+        pub fn set_isa(isa: Isa) -> Result<(), GemmError> {
+            ISA.set(isa, KERNELS)
+        }
+
+        pub fn blocking() -> Blocking {
+            BLOCKING.load::<f32>(MR, NR)
+        }
+
+        pub fn set_blocking(blocking: Blocking) -> Result<(), GemmError> {
+            blocking.validate::<f32>(MR, NR)?;
+            BLOCKING.store(blocking);
+
+            Ok(())
+        }
     }
     pub mod double {
+        use crate::error::GemmError;
+        use crate::kernel::isa::{Isa, Selection};
+        use crate::kernel::{AtomicBlocking, Blocking};
+
         pub const MC: usize = 256;
         pub const KC: usize = 512;
         pub const NC: usize = 4096;
         pub const MR: usize = 8;
         pub const NR: usize = 4;
+
+        // There is no SSE kernel for f64, CPUs without AVX run the generic one
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        pub const KERNELS: &[Isa] = &[Isa::Avx, Isa::Generic];
+        #[cfg(not(any(target_arch = "x86_64", target_arch = "x86")))]
+        pub const KERNELS: &[Isa] = &[Isa::Generic];
+
+        static ISA: Selection = Selection::new();
+        static BLOCKING: AtomicBlocking = AtomicBlocking::new(MC, KC, NC);
+
+        pub fn isa() -> Isa {
+            ISA.get(KERNELS)
+        }
+
+        pub fn set_isa(isa: Isa) -> Result<(), GemmError> {
+            ISA.set(isa, KERNELS)
+        }
+
+        pub fn blocking() -> Blocking {
+            BLOCKING.load::<f64>(MR, NR)
+        }
+
+        pub fn set_blocking(blocking: Blocking) -> Result<(), GemmError> {
+            blocking.validate::<f64>(MR, NR)?;
+            BLOCKING.store(blocking);
+
+            Ok(())
+        }
+    }
+    pub mod complex_single {
+        use crate::complex::Complex;
+        use crate::error::GemmError;
+        use crate::kernel::isa::{Isa, Selection};
+        use crate::kernel::{AtomicBlocking, Blocking};
+
+        pub const MC: usize = 128;
+        pub const KC: usize = 256;
+        pub const NC: usize = 4096;
+        pub const MR: usize = 8;
+        pub const NR: usize = 4;
+
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        pub const KERNELS: &[Isa] = &[Isa::Avx, Isa::Generic];
+        #[cfg(not(any(target_arch = "x86_64", target_arch = "x86")))]
+        pub const KERNELS: &[Isa] = &[Isa::Generic];
+
+        static ISA: Selection = Selection::new();
+        static BLOCKING: AtomicBlocking = AtomicBlocking::new(MC, KC, NC);
+
+        pub fn isa() -> Isa {
+            ISA.get(KERNELS)
+        }
+
+        pub fn set_isa(isa: Isa) -> Result<(), GemmError> {
+            ISA.set(isa, KERNELS)
+        }
+
+        pub fn blocking() -> Blocking {
+            BLOCKING.load::<Complex<f32>>(MR, NR)
+        }
+
+        pub fn set_blocking(blocking: Blocking) -> Result<(), GemmError> {
+            blocking.validate::<Complex<f32>>(MR, NR)?;
+            BLOCKING.store(blocking);
+
+            Ok(())
+        }
+    }
+    pub mod complex_double {
+        use crate::complex::Complex;
+        use crate::error::GemmError;
+        use crate::kernel::isa::{Isa, Selection};
+        use crate::kernel::{AtomicBlocking, Blocking};
+
+        pub const MC: usize = 64;
+        pub const KC: usize = 256;
+        pub const NC: usize = 2048;
+        pub const MR: usize = 4;
+        pub const NR: usize = 4;
+
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        pub const KERNELS: &[Isa] = &[Isa::Avx, Isa::Generic];
+        #[cfg(not(any(target_arch = "x86_64", target_arch = "x86")))]
+        pub const KERNELS: &[Isa] = &[Isa::Generic];
+
+        static ISA: Selection = Selection::new();
+        static BLOCKING: AtomicBlocking = AtomicBlocking::new(MC, KC, NC);
+
+        pub fn isa() -> Isa {
+            ISA.get(KERNELS)
+        }
+
+        pub fn set_isa(isa: Isa) -> Result<(), GemmError> {
+            ISA.set(isa, KERNELS)
+        }
+
+        pub fn blocking() -> Blocking {
+            BLOCKING.load::<Complex<f64>>(MR, NR)
+        }
+
+        pub fn set_blocking(blocking: Blocking) -> Result<(), GemmError> {
+            blocking.validate::<Complex<f64>>(MR, NR)?;
+            BLOCKING.store(blocking);
+
+            Ok(())
+        }
+    }
+    // u8 x i8 products accumulated in i32
+    pub mod quantized {
+        use crate::error::GemmError;
+        use crate::kernel::isa::{Isa, Selection};
+        use crate::kernel::{AtomicBlocking, Blocking};
+
+        pub const MC: usize = 256;
+        pub const KC: usize = 512;
+        pub const NC: usize = 682 * NR;
+        pub const MR: usize = 8;
+        pub const NR: usize = 6;
+
+        // the vector kernel needs AVX2 besides the AVX of `Isa::Avx`
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        pub const KERNELS: &[Isa] = &[Isa::Avx, Isa::Generic];
+        #[cfg(not(any(target_arch = "x86_64", target_arch = "x86")))]
+        pub const KERNELS: &[Isa] = &[Isa::Generic];
+
+        static ISA: Selection = Selection::new();
+        static BLOCKING: AtomicBlocking = AtomicBlocking::new(MC, KC, NC);
+
+        // The kernels this CPU runs, without AVX2 `Isa::Avx` is neither
+        // picked nor accepted
+        fn kernels() -> &'static [Isa] {
+            #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+            if !crate::kernel::isa::avx2() {
+                return &[Isa::Generic];
+            }
+
+            KERNELS
+        }
+
+        pub fn isa() -> Isa {
+            ISA.get(kernels())
+        }
+
+        pub fn set_isa(isa: Isa) -> Result<(), GemmError> {
+            ISA.set(isa, kernels())
+        }
+
+        pub fn blocking() -> Blocking {
+            BLOCKING.load::<i32>(MR, NR)
+        }
+
+        pub fn set_blocking(blocking: Blocking) -> Result<(), GemmError> {
+            blocking.validate::<i32>(MR, NR)?;
+            BLOCKING.store(blocking);
+
+            Ok(())
+        }
+    }
+}
+
+/// Cache blocking of the gemm loops: `mc` rows of A and `nc` columns of B
+/// are packed `kc` elements deep.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Blocking {
+    pub mc: usize,
+    pub kc: usize,
+    pub nc: usize,
+}
+
+impl Blocking {
+    /// MC and NC have to be whole micro-panels and KC has to keep every
+    /// packed panel on a 32-byte boundary.
+    pub fn validate<F>(&self, mr: usize, nr: usize) -> Result<(), GemmError> {
+        let size = std::mem::size_of::<F>();
+        let kc_align = std::cmp::max(32 / size, 1);
+
+        let fits = self
+            .mc
+            .checked_mul(self.kc)
+            .and_then(|x| x.checked_mul(size))
+            .is_some()
+            && self
+                .kc
+                .checked_mul(self.nc)
+                .and_then(|x| x.checked_mul(size))
+                .is_some();
+
+        if self.mc == 0
+            || self.kc == 0
+            || self.nc == 0
+            || !self.mc.is_multiple_of(mr)
+            || !self.nc.is_multiple_of(nr)
+            || !self.kc.is_multiple_of(kc_align)
+            || !fits
+        {
+            return Err(GemmError::InvalidBlocking {
+                mc: self.mc,
+                kc: self.kc,
+                nc: self.nc,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+// The blocking in use for one precision. The first load replaces the
+// defaults with the one derived from the cache sizes, unless a blocking was
+// stored before. MC, KC and NC are read and written together, a gemm never
+// sees half of a blocking being stored.
+pub(crate) struct AtomicBlocking {
+    auto: Once,
+    blocking: RwLock<Blocking>,
+}
+
+impl AtomicBlocking {
+    pub(crate) const fn new(mc: usize, kc: usize, nc: usize) -> Self {
+        AtomicBlocking {
+            auto: Once::new(),
+            blocking: RwLock::new(Blocking { mc, kc, nc }),
+        }
+    }
+
+    pub(crate) fn load<F>(&self, mr: usize, nr: usize) -> Blocking {
+        self.auto.call_once(|| {
+            self.write(cache::blocking::<F>(
+                &cache::detect(),
+                mr,
+                nr,
+                self.current(),
+            ));
+        });
+
+        self.current()
+    }
+
+    pub(crate) fn store(&self, blocking: Blocking) {
+        self.auto.call_once(|| ());
+        self.write(blocking);
+    }
+
+    // Nothing panics while holding the lock, a poisoned one still holds a
+    // whole blocking
+    fn current(&self) -> Blocking {
+        *self.blocking.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self, blocking: Blocking) {
+        *self.blocking.write().unwrap_or_else(|e| e.into_inner()) = blocking;
     }
 }
 
@@ -39,42 +327,131 @@ pub mod params {
 // |BL  |    |    |    |BR|
 // +----------------------+
 
+// The kernels store `epilogue.apply(alpha * A * B + beta * C)` to C, its
+// rows and columns count from the top left element of the C they get. The
+// ones with an epilogue compute the block into a local tile first, which
+// `store_tile` merges into C.
 pub trait GemmKernelSup<F: Number> {
-    unsafe fn sup_br<A: Matrix<F>, B: Matrix<F>, C: MatrixMut<F>>(
+    unsafe fn sup_br<A: Matrix<F>, B: Matrix<F>, C: MatrixMut<F>, P: Epilogue<F>>(
         k: usize,
         alpha: F,
         a: A,
         b: B,
         beta: F,
         c: C,
+        epilogue: P,
     );
 }
 
 pub trait GemmKernelSupMr<F: Number, MR: Dim> {
-    unsafe fn sup_bl<B: Matrix<F>, C: MatrixMut<F>>(
+    unsafe fn sup_bl<B: Matrix<F>, C: MatrixMut<F>, P: Epilogue<F>>(
         alpha: F,
         pa: MutMatrix<F>,
         b: B,
         beta: F,
         c: C,
+        epilogue: P,
     );
 }
 
 pub trait GemmKernelSupNr<F: Number, NR: Dim> {
-    unsafe fn sup_tr<A: Matrix<F>, C: MatrixMut<F>>(
+    unsafe fn sup_tr<A: Matrix<F>, C: MatrixMut<F>, P: Epilogue<F>>(
         alpha: F,
         a: A,
         pb: MutMatrix<F>,
         beta: F,
         c: C,
+        epilogue: P,
     );
 }
 
 pub trait GemmKernel<F: Number, MR: Dim, NR: Dim>:
     GemmKernelSupMr<F, MR> + GemmKernelSupNr<F, NR> + GemmKernelSup<F>
 {
+    fn blocking() -> Blocking;
+
     unsafe fn pack_row_a<A: Matrix<F>>(a: A, pa: MutMatrix<F>);
     unsafe fn pack_row_b<B: Matrix<F>>(b: B, pb: MutMatrix<F>);
 
-    unsafe fn main_tl<C: MatrixMut<F>>(alpha: F, pa: MutMatrix<F>, pb: MutMatrix<F>, beta: F, c: C);
+    unsafe fn main_tl<C: MatrixMut<F>, P: Epilogue<F>>(
+        alpha: F,
+        pa: MutMatrix<F>,
+        pb: MutMatrix<F>,
+        beta: F,
+        c: C,
+        epilogue: P,
+    );
+}
+
+// Largest MR * NR of the kernels
+pub(crate) const MAX_TILE: usize = 128;
+
+// Vector kernels store columns of C with contiguous loads and stores and
+// apply `Linear` epilogues to their registers, other epilogues afterwards
+// with `apply_epilogue`. For C with a non-unit column stride they compute
+// into a local tile (with beta = 0) and the tile is merged into C element by
+// element.
+#[inline]
+pub(crate) unsafe fn store_tile<F: Number, C: MatrixMut<F>, P: Epilogue<F>>(
+    tile: MutMatrix<F>,
+    rows: usize,
+    cols: usize,
+    beta: F,
+    c: C,
+    epilogue: P,
+) {
+    for j in 0..cols {
+        for i in 0..rows {
+            let cij = c.index_mut(j, i);
+
+            let elem = if beta != F::ZERO {
+                tile.get(j, i) + beta * *cij
+            } else {
+                tile.get(j, i)
+            };
+
+            *cij = epilogue.apply(i, j, elem);
+        }
+    }
+}
+
+// Applies the epilogue to the final m x n block of C in place
+#[inline]
+pub(crate) unsafe fn apply_epilogue<F, C, P>(m: usize, n: usize, c: C, epilogue: P)
+where
+    F: Number,
+    C: MatrixMut<F>,
+    P: Epilogue<F>,
+{
+    if P::IDENTITY {
+        return;
+    }
+
+    for j in 0..n {
+        for i in 0..m {
+            c.set(j, i, epilogue.apply(i, j, c.get(j, i)));
+        }
+    }
+}
+
+// Runs `kernel` on a local tile of `rows` x `cols` and merges the tile into C
+#[inline]
+pub(crate) unsafe fn with_tile<F, C, P, K>(
+    rows: usize,
+    cols: usize,
+    beta: F,
+    c: C,
+    epilogue: P,
+    kernel: K,
+) where
+    F: Number,
+    C: MatrixMut<F>,
+    P: Epilogue<F>,
+    K: FnOnce(MutMatrix<F>),
+{
+    let mut tile = [F::ZERO; MAX_TILE];
+    let t = MutMatrix::new(tile.as_mut_ptr(), rows);
+
+    kernel(t);
+    store_tile(t, rows, cols, beta, c, epilogue);
 }
@@ -2,15 +2,46 @@
 
 use super::fma::fmadd_ps;
 use super::intrinsics::*;
-use crate::kernel::params::single::NR;
+use crate::epilogue::{Bias, Linear};
+use crate::kernel::params::single::{MR, NR};
 use crate::matrix::{Matrix, MatrixMut, MutMatrix};
 
+// `linear` of the 4 rows from `row` of column `col` of C
+#[inline]
+unsafe fn linear_ps(x: __m128, linear: &Linear<f32>, row: usize, col: usize) -> __m128 {
+    let mut x = x;
+
+    match linear.scale {
+        Bias::None => {}
+        Bias::Row(scale) => x = _mm_mul_ps(x, _mm_loadu_ps(scale.add(row))),
+        Bias::Col(scale) => x = _mm_mul_ps(x, _mm_set1_ps(*scale.add(col))),
+    }
+
+    match linear.bias {
+        Bias::None => {}
+        Bias::Row(bias) => x = _mm_add_ps(x, _mm_loadu_ps(bias.add(row))),
+        Bias::Col(bias) => x = _mm_add_ps(x, _mm_set1_ps(*bias.add(col))),
+    }
+
+    // max and min return their second operand for NaN, like f32::max and
+    // f32::min
+    if linear.relu {
+        x = _mm_max_ps(x, _mm_setzero_ps());
+    }
+
+    if let Some((min, max)) = linear.clamp {
+        x = _mm_min_ps(_mm_max_ps(x, _mm_set1_ps(min)), _mm_set1_ps(max));
+    }
+
+    x
+}
+
 pub(crate) unsafe fn sgemm_sup_1x8<A: Matrix<f32>, C: MatrixMut<f32>>(
     k: usize,
-    _alpha: f32,
+    alpha: f32,
     a: A,
     pb: MutMatrix<f32>,
-    _beta: f32,
+    beta: f32,
     c: C,
 ) {
     let mut c0_3 = _mm_setzero_ps();
@@ -23,8 +54,8 @@ pub(crate) unsafe fn sgemm_sup_1x8<A: Matrix<f32>, C: MatrixMut<f32>>(
     let mut pb = pb;
 
     for _ in 0..k {
-        let a0 = *a.ptr();
-        let a0_simd = _mm_broadcast_ss(&*a.ptr());
+        let a0 = a.get(0, 0);
+        let a0_simd = _mm_set1_ps(a0);
 
         c0_3 = fmadd_ps(_mm_loadu_ps(pb.ptr()), a0_simd, c0_3);
         c4 += *pb.col(4) * a0;
@@ -36,11 +67,10 @@ pub(crate) unsafe fn sgemm_sup_1x8<A: Matrix<f32>, C: MatrixMut<f32>>(
         pb.shift_col(NR);
     }
 
-    // c0 *= alpha;
-    // c1 *= alpha;
-    // c2 *= alpha;
-    // c3 *= alpha;
-    // c4 *= alpha;
+    let alpha_simd = _mm_set_ps1(alpha);
+
+    c0_3 = _mm_mul_ps(alpha_simd, c0_3);
+    c4 *= alpha;
     // c5 *= alpha;
     // c6 *= alpha;
     // c7 *= alpha;
@@ -51,18 +81,27 @@ pub(crate) unsafe fn sgemm_sup_1x8<A: Matrix<f32>, C: MatrixMut<f32>>(
     // let ccol6 = c.add(ldc * 6);
     // let ccol7 = c.add(ldc * 7);
 
-    // if beta != 0.0 {
-    //     c0 += beta * *ccol0;
-    //     c1 += beta * *ccol1;
-    //     c2 += beta * *ccol2;
-    //     c3 += beta * *ccol3;
-    //     c4 += beta * *ccol4;
-    //     // c5 += beta * *ccol5;
-    //     // c6 += beta * *ccol6;
-    //     // c7 += beta * *ccol7;
-    // }
+    if beta != 0.0 {
+        let beta_simd = _mm_set_ps1(beta);
+
+        c0_3 = fmadd_ps(
+            beta_simd,
+            _mm_set_ps(*c.row(3), *c.row(2), *c.row(1), *c.ptr()),
+            c0_3,
+        );
+        c4 += beta * *ccol4;
+        // c5 += beta * *ccol5;
+        // c6 += beta * *ccol6;
+        // c7 += beta * *ccol7;
+    }
+
+    let mut c0_3_arr = [0.0f32; 4];
+    _mm_storeu_ps(c0_3_arr.as_mut_ptr(), c0_3);
 
-    _mm_storeu_ps(ccol0_3, c0_3);
+    *ccol0_3 = c0_3_arr[0];
+    *c.row_mut(1) = c0_3_arr[1];
+    *c.row_mut(2) = c0_3_arr[2];
+    *c.row_mut(3) = c0_3_arr[3];
     *ccol4 = c4;
     // *ccol5 = c5;
     // *ccol6 = c6;
@@ -178,3 +217,257 @@ pub(crate) unsafe fn sgemm_pb_x8(k: usize, b: *const f32, ldb: usize, pb: *mut f
         pb = pb.add(NR);
     }
 }
+
+#[inline]
+unsafe fn sgemm_ukr_8x5<C: MatrixMut<f32>>(
+    k: usize,
+    alpha: f32,
+    pa: *const f32,
+    pb: *const f32,
+    beta: f32,
+    c: C,
+    linear: Option<Linear<f32>>,
+) {
+    let mut mt00 = _mm_setzero_ps();
+    let mut mt01 = _mm_setzero_ps();
+    let mut mt02 = _mm_setzero_ps();
+    let mut mt03 = _mm_setzero_ps();
+    let mut mt04 = _mm_setzero_ps();
+
+    let mut mt10 = _mm_setzero_ps();
+    let mut mt11 = _mm_setzero_ps();
+    let mut mt12 = _mm_setzero_ps();
+    let mut mt13 = _mm_setzero_ps();
+    let mut mt14 = _mm_setzero_ps();
+
+    let mut pa = pa;
+    let mut pb = pb;
+
+    for _ in 0..k {
+        let a0 = _mm_load_ps(pa);
+        let a1 = _mm_load_ps(pa.add(4));
+
+        let b0 = _mm_set1_ps(*pb);
+        mt00 = fmadd_ps(a0, b0, mt00);
+        mt10 = fmadd_ps(a1, b0, mt10);
+
+        let b1 = _mm_set1_ps(*pb.add(1));
+        mt01 = fmadd_ps(a0, b1, mt01);
+        mt11 = fmadd_ps(a1, b1, mt11);
+
+        let b2 = _mm_set1_ps(*pb.add(2));
+        mt02 = fmadd_ps(a0, b2, mt02);
+        mt12 = fmadd_ps(a1, b2, mt12);
+
+        let b3 = _mm_set1_ps(*pb.add(3));
+        mt03 = fmadd_ps(a0, b3, mt03);
+        mt13 = fmadd_ps(a1, b3, mt13);
+
+        let b4 = _mm_set1_ps(*pb.add(4));
+        mt04 = fmadd_ps(a0, b4, mt04);
+        mt14 = fmadd_ps(a1, b4, mt14);
+
+        pa = pa.add(MR);
+        pb = pb.add(NR);
+    }
+
+    let alpha = _mm_set1_ps(alpha);
+
+    mt00 = _mm_mul_ps(alpha, mt00);
+    mt01 = _mm_mul_ps(alpha, mt01);
+    mt02 = _mm_mul_ps(alpha, mt02);
+    mt03 = _mm_mul_ps(alpha, mt03);
+    mt04 = _mm_mul_ps(alpha, mt04);
+
+    mt10 = _mm_mul_ps(alpha, mt10);
+    mt11 = _mm_mul_ps(alpha, mt11);
+    mt12 = _mm_mul_ps(alpha, mt12);
+    mt13 = _mm_mul_ps(alpha, mt13);
+    mt14 = _mm_mul_ps(alpha, mt14);
+
+    let ccol0 = c.ptr_mut();
+    let ccol1 = c.row_mut(1);
+    let ccol2 = c.row_mut(2);
+    let ccol3 = c.row_mut(3);
+    let ccol4 = c.row_mut(4);
+
+    if beta != 0.0 {
+        let beta = _mm_set1_ps(beta);
+
+        mt00 = fmadd_ps(beta, _mm_loadu_ps(ccol0), mt00);
+        mt01 = fmadd_ps(beta, _mm_loadu_ps(ccol1), mt01);
+        mt02 = fmadd_ps(beta, _mm_loadu_ps(ccol2), mt02);
+        mt03 = fmadd_ps(beta, _mm_loadu_ps(ccol3), mt03);
+        mt04 = fmadd_ps(beta, _mm_loadu_ps(ccol4), mt04);
+
+        mt10 = fmadd_ps(beta, _mm_loadu_ps(ccol0.add(4)), mt10);
+        mt11 = fmadd_ps(beta, _mm_loadu_ps(ccol1.add(4)), mt11);
+        mt12 = fmadd_ps(beta, _mm_loadu_ps(ccol2.add(4)), mt12);
+        mt13 = fmadd_ps(beta, _mm_loadu_ps(ccol3.add(4)), mt13);
+        mt14 = fmadd_ps(beta, _mm_loadu_ps(ccol4.add(4)), mt14);
+    }
+
+    if let Some(linear) = linear {
+        mt00 = linear_ps(mt00, &linear, 0, 0);
+        mt01 = linear_ps(mt01, &linear, 0, 1);
+        mt02 = linear_ps(mt02, &linear, 0, 2);
+        mt03 = linear_ps(mt03, &linear, 0, 3);
+        mt04 = linear_ps(mt04, &linear, 0, 4);
+
+        mt10 = linear_ps(mt10, &linear, 4, 0);
+        mt11 = linear_ps(mt11, &linear, 4, 1);
+        mt12 = linear_ps(mt12, &linear, 4, 2);
+        mt13 = linear_ps(mt13, &linear, 4, 3);
+        mt14 = linear_ps(mt14, &linear, 4, 4);
+    }
+
+    _mm_storeu_ps(ccol0, mt00);
+    _mm_storeu_ps(ccol1, mt01);
+    _mm_storeu_ps(ccol2, mt02);
+    _mm_storeu_ps(ccol3, mt03);
+    _mm_storeu_ps(ccol4, mt04);
+
+    _mm_storeu_ps(ccol0.add(4), mt10);
+    _mm_storeu_ps(ccol1.add(4), mt11);
+    _mm_storeu_ps(ccol2.add(4), mt12);
+    _mm_storeu_ps(ccol3.add(4), mt13);
+    _mm_storeu_ps(ccol4.add(4), mt14);
+}
+
+// 16x5 tile does not fit in 16 xmm registers, so it is computed
+// as two 8x5 halves sharing the same packed panels
+pub(crate) unsafe fn sgemm_ukr_16x5<C: MatrixMut<f32>>(
+    k: usize,
+    alpha: f32,
+    pa: MutMatrix<f32>,
+    pb: MutMatrix<f32>,
+    beta: f32,
+    c: C,
+    linear: Option<Linear<f32>>,
+) {
+    let lower = linear.map(|linear| linear.sub(8, 0));
+
+    sgemm_ukr_8x5(k, alpha, pa.ptr(), pb.ptr(), beta, c, linear);
+    sgemm_ukr_8x5(
+        k,
+        alpha,
+        pa.ptr().add(8),
+        pb.ptr(),
+        beta,
+        c.sub_col(8),
+        lower,
+    );
+}
+
+pub(crate) unsafe fn sgemm_sup_16x1<B: Matrix<f32>, C: MatrixMut<f32>>(
+    k: usize,
+    alpha: f32,
+    pa: MutMatrix<f32>,
+    b: B,
+    beta: f32,
+    c: C,
+    linear: Option<Linear<f32>>,
+) {
+    let mut mt0 = _mm_setzero_ps();
+    let mut mt1 = _mm_setzero_ps();
+    let mut mt2 = _mm_setzero_ps();
+    let mut mt3 = _mm_setzero_ps();
+
+    let mut pa = pa.ptr();
+    let mut b = b;
+
+    for _ in 0..k {
+        let b0 = _mm_set1_ps(b.get(0, 0));
+
+        mt0 = fmadd_ps(_mm_load_ps(pa), b0, mt0);
+        mt1 = fmadd_ps(_mm_load_ps(pa.add(4)), b0, mt1);
+        mt2 = fmadd_ps(_mm_load_ps(pa.add(8)), b0, mt2);
+        mt3 = fmadd_ps(_mm_load_ps(pa.add(12)), b0, mt3);
+
+        pa = pa.add(MR);
+        b.inc_col();
+    }
+
+    let alpha = _mm_set1_ps(alpha);
+
+    mt0 = _mm_mul_ps(alpha, mt0);
+    mt1 = _mm_mul_ps(alpha, mt1);
+    mt2 = _mm_mul_ps(alpha, mt2);
+    mt3 = _mm_mul_ps(alpha, mt3);
+
+    let c = c.ptr_mut();
+
+    if beta != 0.0 {
+        let beta = _mm_set1_ps(beta);
+
+        mt0 = fmadd_ps(beta, _mm_loadu_ps(c), mt0);
+        mt1 = fmadd_ps(beta, _mm_loadu_ps(c.add(4)), mt1);
+        mt2 = fmadd_ps(beta, _mm_loadu_ps(c.add(8)), mt2);
+        mt3 = fmadd_ps(beta, _mm_loadu_ps(c.add(12)), mt3);
+    }
+
+    if let Some(linear) = linear {
+        mt0 = linear_ps(mt0, &linear, 0, 0);
+        mt1 = linear_ps(mt1, &linear, 4, 0);
+        mt2 = linear_ps(mt2, &linear, 8, 0);
+        mt3 = linear_ps(mt3, &linear, 12, 0);
+    }
+
+    _mm_storeu_ps(c, mt0);
+    _mm_storeu_ps(c.add(4), mt1);
+    _mm_storeu_ps(c.add(8), mt2);
+    _mm_storeu_ps(c.add(12), mt3);
+}
+
+pub(crate) unsafe fn sgemm_pa_16x(k: usize, a: *const f32, lda: usize, pa: *mut f32) {
+    let mut a = a;
+    let mut pa = pa;
+
+    for _ in 0..k {
+        _mm_store_ps(pa, _mm_loadu_ps(a));
+        _mm_store_ps(pa.add(4), _mm_loadu_ps(a.add(4)));
+        _mm_store_ps(pa.add(8), _mm_loadu_ps(a.add(8)));
+        _mm_store_ps(pa.add(12), _mm_loadu_ps(a.add(12)));
+
+        pa = pa.add(MR);
+        a = a.add(lda);
+    }
+}
+
+// Packs a block of A^T, the rows of the 16 x k block are contiguous and are
+// transposed four by four
+pub(crate) unsafe fn sgemm_pa_16x_t(k: usize, a: *const f32, lda: usize, pa: *mut f32) {
+    let mut a = a;
+    let mut pa = pa;
+
+    for _ in 0..k / 4 {
+        for j in (0..MR).step_by(4) {
+            let r0 = _mm_loadu_ps(a.add(j * lda));
+            let r1 = _mm_loadu_ps(a.add((j + 1) * lda));
+            let r2 = _mm_loadu_ps(a.add((j + 2) * lda));
+            let r3 = _mm_loadu_ps(a.add((j + 3) * lda));
+
+            let t0 = _mm_unpacklo_ps(r0, r1);
+            let t1 = _mm_unpacklo_ps(r2, r3);
+            let t2 = _mm_unpackhi_ps(r0, r1);
+            let t3 = _mm_unpackhi_ps(r2, r3);
+
+            _mm_store_ps(pa.add(j), _mm_movelh_ps(t0, t1));
+            _mm_store_ps(pa.add(MR + j), _mm_movehl_ps(t1, t0));
+            _mm_store_ps(pa.add(2 * MR + j), _mm_movelh_ps(t2, t3));
+            _mm_store_ps(pa.add(3 * MR + j), _mm_movehl_ps(t3, t2));
+        }
+
+        a = a.add(4);
+        pa = pa.add(4 * MR);
+    }
+
+    for _ in 0..k % 4 {
+        for j in 0..MR {
+            *pa.add(j) = *a.add(j * lda);
+        }
+
+        a = a.add(1);
+        pa = pa.add(MR);
+    }
+}
@@ -7,8 +7,12 @@ mod intrinsics;
 pub mod l3s;
 
 use crate::dim::*;
-use crate::kernel::{GemmKernel, GemmKernelSup, GemmKernelSupMr, GemmKernelSupNr};
-use crate::matrix::{Matrix, MatrixMut, MutMatrix, Number};
+use crate::epilogue::Epilogue;
+use crate::kernel::{
+    apply_epilogue, params, store_tile, with_tile, Blocking, GemmKernel, GemmKernelSup,
+    GemmKernelSupMr, GemmKernelSupNr,
+};
+use crate::matrix::{Matrix, MatrixMut, MutMatrix, Number, Storage};
 use core::marker::PhantomData;
 
 pub struct SseKernel<F: Number, I>(PhantomData<fn(F, I)>);
@@ -18,14 +22,23 @@ where
     I: GemmKernelSupNr<f32, A5>,
 {
     #[inline]
-    unsafe fn sup_tr<A: Matrix<f32>, C: MatrixMut<f32>>(
+    unsafe fn sup_tr<A: Matrix<f32>, C: MatrixMut<f32>, P: Epilogue<f32>>(
         alpha: f32,
         a: A,
         pb: MutMatrix<f32>,
         beta: f32,
         c: C,
+        epilogue: P,
     ) {
-        I::sup_tr(alpha, a, pb, beta, c);
+        use crate::kernel::params::single::NR;
+
+        if P::IDENTITY {
+            self::l3s::sgemm_sup_1x8(pb.stride, alpha, a, pb, beta, c);
+        } else {
+            with_tile(1, NR, beta, c, epilogue, |t| {
+                self::l3s::sgemm_sup_1x8(pb.stride, alpha, a, pb, 0.0, t)
+            });
+        }
     }
 }
 
@@ -34,14 +47,31 @@ where
     I: GemmKernelSupMr<f32, A16>,
 {
     #[inline]
-    unsafe fn sup_bl<B: Matrix<f32>, C: MatrixMut<f32>>(
+    unsafe fn sup_bl<B: Matrix<f32>, C: MatrixMut<f32>, P: Epilogue<f32>>(
         alpha: f32,
         pa: MutMatrix<f32>,
         b: B,
         beta: f32,
         c: C,
+        epilogue: P,
     ) {
-        I::sup_bl(alpha, pa, b, beta, c);
+        use crate::kernel::params::single::MR;
+
+        if c.col_stride() == 1 {
+            let linear = epilogue.linear();
+
+            self::l3s::sgemm_sup_16x1(pa.stride, alpha, pa, b, beta, c, linear);
+
+            if linear.is_none() {
+                apply_epilogue(MR, 1, c, epilogue);
+            }
+        } else {
+            let mut tile = [0.0; MR];
+            let t = MutMatrix::new(tile.as_mut_ptr(), MR);
+
+            self::l3s::sgemm_sup_16x1(pa.stride, alpha, pa, b, 0.0, t, None);
+            store_tile(t, MR, 1, beta, c, epilogue);
+        }
     }
 }
 
@@ -50,15 +80,16 @@ where
     I: GemmKernelSup<f32>,
 {
     #[inline]
-    unsafe fn sup_br<A: Matrix<f32>, B: Matrix<f32>, C: MatrixMut<f32>>(
+    unsafe fn sup_br<A: Matrix<f32>, B: Matrix<f32>, C: MatrixMut<f32>, P: Epilogue<f32>>(
         k: usize,
         alpha: f32,
         a: A,
         b: B,
         beta: f32,
         c: C,
+        epilogue: P,
     ) {
-        I::sup_br(k, alpha, a, b, beta, c);
+        I::sup_br(k, alpha, a, b, beta, c, epilogue);
     }
 }
 
@@ -66,24 +97,56 @@ impl<I> GemmKernel<f32, A16, A5> for SseKernel<f32, I>
 where
     I: GemmKernel<f32, A16, A5>,
 {
+    #[inline]
+    fn blocking() -> Blocking {
+        params::single::blocking()
+    }
+
     #[inline]
     unsafe fn pack_row_a<A: Matrix<f32>>(a: A, pa: MutMatrix<f32>) {
-        I::pack_row_a(a, pa);
+        if a.col_stride() == 1 && a.storage() == Storage::Native {
+            self::l3s::sgemm_pa_16x(pa.stride, a.ptr(), a.row_stride(), pa.ptr_mut());
+        } else if a.row_stride() == 1 && a.storage() == Storage::Native {
+            self::l3s::sgemm_pa_16x_t(pa.stride, a.ptr(), a.col_stride(), pa.ptr_mut());
+        } else {
+            I::pack_row_a(a, pa);
+        }
     }
 
     #[inline]
     unsafe fn pack_row_b<B: Matrix<f32>>(b: B, pb: MutMatrix<f32>) {
-        I::pack_row_b(b, pb);
+        if b.col_stride() == 1 && b.storage() == Storage::Native {
+            self::l3s::sgemm_pb_x8(pb.stride, b.ptr(), b.row_stride(), pb.ptr_mut());
+        } else {
+            I::pack_row_b(b, pb);
+        }
     }
 
     #[inline]
-    unsafe fn main_tl<C: MatrixMut<f32>>(
+    unsafe fn main_tl<C: MatrixMut<f32>, P: Epilogue<f32>>(
         alpha: f32,
         pa: MutMatrix<f32>,
         pb: MutMatrix<f32>,
         beta: f32,
         c: C,
+        epilogue: P,
     ) {
-        I::main_tl(alpha, pa, pb, beta, c);
+        use crate::kernel::params::single::{MR, NR};
+
+        if c.col_stride() == 1 {
+            let linear = epilogue.linear();
+
+            self::l3s::sgemm_ukr_16x5(pa.stride, alpha, pa, pb, beta, c, linear);
+
+            if linear.is_none() {
+                apply_epilogue(MR, NR, c, epilogue);
+            }
+        } else {
+            let mut tile = [0.0; MR * NR];
+            let t = MutMatrix::new(tile.as_mut_ptr(), MR);
+
+            self::l3s::sgemm_ukr_16x5(pa.stride, alpha, pa, pb, 0.0, t, None);
+            store_tile(t, MR, NR, beta, c, epilogue);
+        }
     }
 }
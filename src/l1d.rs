@@ -0,0 +1,72 @@
+// The level-1 routines have an AVX and a generic version only, CPUs with SSE
+// but no AVX run the generic one
+
+use crate::kernel::generic;
+use crate::kernel::isa::{self, Isa};
+
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+use crate::kernel::avx;
+
+pub unsafe fn drot(n: usize, x: *mut f64, incx: usize, y: *mut f64, incy: usize, c: f64, s: f64) {
+    match isa::detect() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => avx::l1d::drot(n, x, incx, y, incy, c, s),
+        _ => generic::l1d::drot(n, x, incx, y, incy, c, s),
+    }
+}
+
+pub unsafe fn dswap(n: usize, x: *mut f64, incx: usize, y: *mut f64, incy: usize) {
+    match isa::detect() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => avx::l1d::dswap(n, x, incx, y, incy),
+        _ => generic::l1d::dswap(n, x, incx, y, incy),
+    }
+}
+
+pub unsafe fn dscal(n: usize, a: f64, x: *mut f64, incx: usize) {
+    match isa::detect() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => avx::l1d::dscal(n, a, x, incx),
+        _ => generic::l1d::dscal(n, a, x, incx),
+    }
+}
+
+pub unsafe fn dcopy(n: usize, x: *const f64, incx: usize, y: *mut f64, incy: usize) {
+    match isa::detect() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => avx::l1d::dcopy(n, x, incx, y, incy),
+        _ => generic::l1d::dcopy(n, x, incx, y, incy),
+    }
+}
+
+pub unsafe fn daxpy(n: usize, a: f64, x: *const f64, incx: usize, y: *mut f64, incy: usize) {
+    match isa::detect() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => avx::l1d::daxpy(n, a, x, incx, y, incy),
+        _ => generic::l1d::daxpy(n, a, x, incx, y, incy),
+    }
+}
+
+pub unsafe fn ddot(n: usize, x: *const f64, incx: usize, y: *const f64, incy: usize) -> f64 {
+    match isa::detect() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => avx::l1d::ddot(n, x, incx, y, incy),
+        _ => generic::l1d::ddot(n, x, incx, y, incy),
+    }
+}
+
+pub unsafe fn dnrm2(n: usize, x: *const f64, incx: usize) -> f64 {
+    match isa::detect() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => avx::l1d::dnrm2(n, x, incx),
+        _ => generic::l1d::dnrm2(n, x, incx),
+    }
+}
+
+pub unsafe fn dasum(n: usize, x: *const f64, incx: usize) -> f64 {
+    match isa::detect() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => avx::l1d::dasum(n, x, incx),
+        _ => generic::l1d::dasum(n, x, incx),
+    }
+}
@@ -0,0 +1,72 @@
+// The level-1 routines have an AVX and a generic version only, CPUs with SSE
+// but no AVX run the generic one
+
+use crate::kernel::generic;
+use crate::kernel::isa::{self, Isa};
+
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+use crate::kernel::avx;
+
+pub unsafe fn srot(n: usize, x: *mut f32, incx: usize, y: *mut f32, incy: usize, c: f32, s: f32) {
+    match isa::detect() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => avx::l1s::srot(n, x, incx, y, incy, c, s),
+        _ => generic::l1s::srot(n, x, incx, y, incy, c, s),
+    }
+}
+
+pub unsafe fn sswap(n: usize, x: *mut f32, incx: usize, y: *mut f32, incy: usize) {
+    match isa::detect() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => avx::l1s::sswap(n, x, incx, y, incy),
+        _ => generic::l1s::sswap(n, x, incx, y, incy),
+    }
+}
+
+pub unsafe fn sscal(n: usize, a: f32, x: *mut f32, incx: usize) {
+    match isa::detect() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => avx::l1s::sscal(n, a, x, incx),
+        _ => generic::l1s::sscal(n, a, x, incx),
+    }
+}
+
+pub unsafe fn scopy(n: usize, x: *const f32, incx: usize, y: *mut f32, incy: usize) {
+    match isa::detect() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => avx::l1s::scopy(n, x, incx, y, incy),
+        _ => generic::l1s::scopy(n, x, incx, y, incy),
+    }
+}
+
+pub unsafe fn saxpy(n: usize, a: f32, x: *const f32, incx: usize, y: *mut f32, incy: usize) {
+    match isa::detect() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => avx::l1s::saxpy(n, a, x, incx, y, incy),
+        _ => generic::l1s::saxpy(n, a, x, incx, y, incy),
+    }
+}
+
+pub unsafe fn sdot(n: usize, x: *const f32, incx: usize, y: *const f32, incy: usize) -> f32 {
+    match isa::detect() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => avx::l1s::sdot(n, x, incx, y, incy),
+        _ => generic::l1s::sdot(n, x, incx, y, incy),
+    }
+}
+
+pub unsafe fn snrm2(n: usize, x: *const f32, incx: usize) -> f32 {
+    match isa::detect() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => avx::l1s::snrm2(n, x, incx),
+        _ => generic::l1s::snrm2(n, x, incx),
+    }
+}
+
+pub unsafe fn sasum(n: usize, x: *const f32, incx: usize) -> f32 {
+    match isa::detect() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => avx::l1s::sasum(n, x, incx),
+        _ => generic::l1s::sasum(n, x, incx),
+    }
+}
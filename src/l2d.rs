@@ -0,0 +1,324 @@
+use crate::executor::Executor;
+use crate::gemm::{Diag, Layout, Uplo};
+use crate::gemv::gemv;
+use crate::kernel::generic;
+use crate::kernel::isa::Isa;
+use crate::kernel::params;
+use crate::l1d::{daxpy, ddot};
+
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+use crate::kernel::avx;
+
+/// y = alpha * op(A) * x + beta * y of an m x n A, `trans` selects A^T
+pub unsafe fn dgemv<E: Executor>(
+    e: &E,
+    layout: Layout,
+    trans: bool,
+    m: usize,
+    n: usize,
+    alpha: f64,
+    a: *const f64,
+    lda: usize,
+    x: *const f64,
+    incx: usize,
+    beta: f64,
+    y: *mut f64,
+    incy: usize,
+) {
+    match params::double::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => gemv(
+            e,
+            layout,
+            trans,
+            m,
+            n,
+            alpha,
+            a,
+            lda,
+            x,
+            incx,
+            beta,
+            y,
+            incy,
+            avx::l2d::dgemv_n,
+            avx::l2d::dgemv_t,
+        ),
+        _ => gemv(
+            e,
+            layout,
+            trans,
+            m,
+            n,
+            alpha,
+            a,
+            lda,
+            x,
+            incx,
+            beta,
+            y,
+            incy,
+            generic::l2d::dgemv_n,
+            generic::l2d::dgemv_t,
+        ),
+    }
+}
+
+// Row-major A is stored as column-major A^T: a symmetric matrix keeps the
+// opposite triangle, a triangular one also swaps op(A).
+fn col_major(layout: Layout, uplo: Uplo) -> Uplo {
+    match layout {
+        Layout::ColMajor => uplo,
+        Layout::RowMajor => uplo.flip(),
+    }
+}
+
+/// A += alpha * x * y^T of an m x n A
+pub unsafe fn dger(
+    layout: Layout,
+    m: usize,
+    n: usize,
+    alpha: f64,
+    x: *const f64,
+    incx: usize,
+    y: *const f64,
+    incy: usize,
+    a: *mut f64,
+    lda: usize,
+) {
+    // row-major A is column-major A^T += alpha * y * x^T
+    let (m, n, x, incx, y, incy) = match layout {
+        Layout::ColMajor => (m, n, x, incx, y, incy),
+        Layout::RowMajor => (n, m, y, incy, x, incx),
+    };
+
+    for j in 0..n {
+        daxpy(m, alpha * *y.add(j * incy), x, incx, a.add(j * lda), 1);
+    }
+}
+
+/// A += alpha * x * x^T of a symmetric n x n A, only `uplo` is updated
+pub unsafe fn dsyr(
+    layout: Layout,
+    uplo: Uplo,
+    n: usize,
+    alpha: f64,
+    x: *const f64,
+    incx: usize,
+    a: *mut f64,
+    lda: usize,
+) {
+    for j in 0..n {
+        let xj = alpha * *x.add(j * incx);
+        let col = a.add(j * lda);
+
+        match col_major(layout, uplo) {
+            Uplo::Upper => daxpy(j + 1, xj, x, incx, col, 1),
+            Uplo::Lower => daxpy(n - j, xj, x.add(j * incx), incx, col.add(j), 1),
+        }
+    }
+}
+
+/// A += alpha * (x * y^T + y * x^T) of a symmetric n x n A, only `uplo` is
+/// updated
+pub unsafe fn dsyr2(
+    layout: Layout,
+    uplo: Uplo,
+    n: usize,
+    alpha: f64,
+    x: *const f64,
+    incx: usize,
+    y: *const f64,
+    incy: usize,
+    a: *mut f64,
+    lda: usize,
+) {
+    for j in 0..n {
+        let xj = alpha * *x.add(j * incx);
+        let yj = alpha * *y.add(j * incy);
+        let col = a.add(j * lda);
+
+        match col_major(layout, uplo) {
+            Uplo::Upper => {
+                daxpy(j + 1, yj, x, incx, col, 1);
+                daxpy(j + 1, xj, y, incy, col, 1);
+            }
+            Uplo::Lower => {
+                daxpy(n - j, yj, x.add(j * incx), incx, col.add(j), 1);
+                daxpy(n - j, xj, y.add(j * incy), incy, col.add(j), 1);
+            }
+        }
+    }
+}
+
+/// y = alpha * A * x + beta * y of a symmetric n x n A stored in `uplo`
+pub unsafe fn dsymv(
+    layout: Layout,
+    uplo: Uplo,
+    n: usize,
+    alpha: f64,
+    a: *const f64,
+    lda: usize,
+    x: *const f64,
+    incx: usize,
+    beta: f64,
+    y: *mut f64,
+    incy: usize,
+) {
+    for i in 0..n {
+        let yi = y.add(i * incy);
+        *yi = if beta == 0.0 { 0.0 } else { beta * *yi };
+    }
+
+    // column j of the triangle is also row j, it adds to y above (below) j
+    // and its dot product with x goes to y[j]
+    for j in 0..n {
+        let xj = alpha * *x.add(j * incx);
+        let col = a.add(j * lda);
+        let diag = *col.add(j) * *x.add(j * incx);
+
+        let dot = match col_major(layout, uplo) {
+            Uplo::Upper => {
+                daxpy(j, xj, col, 1, y, incy);
+                ddot(j, col, 1, x, incx)
+            }
+            Uplo::Lower => {
+                let len = n - j - 1;
+
+                daxpy(len, xj, col.add(j + 1), 1, y.add((j + 1) * incy), incy);
+                ddot(len, col.add(j + 1), 1, x.add((j + 1) * incx), incx)
+            }
+        };
+
+        *y.add(j * incy) += alpha * (diag + dot);
+    }
+}
+
+/// x = op(A) * x of a triangular n x n A stored in `uplo`
+pub unsafe fn dtrmv(
+    layout: Layout,
+    uplo: Uplo,
+    trans: bool,
+    diag: Diag,
+    n: usize,
+    a: *const f64,
+    lda: usize,
+    x: *mut f64,
+    incx: usize,
+) {
+    let trans = trans != (layout == Layout::RowMajor);
+    let unit = diag == Diag::Unit;
+
+    // every step reads the elements of x not yet overwritten
+    match (col_major(layout, uplo), trans) {
+        (Uplo::Upper, false) => {
+            for j in 0..n {
+                let col = a.add(j * lda);
+                let xj = x.add(j * incx);
+
+                daxpy(j, *xj, col, 1, x, incx);
+                if !unit {
+                    *xj *= *col.add(j);
+                }
+            }
+        }
+        (Uplo::Lower, false) => {
+            for j in (0..n).rev() {
+                let col = a.add(j * lda);
+                let xj = x.add(j * incx);
+
+                daxpy(n - j - 1, *xj, col.add(j + 1), 1, xj.add(incx), incx);
+                if !unit {
+                    *xj *= *col.add(j);
+                }
+            }
+        }
+        (Uplo::Upper, true) => {
+            for j in (0..n).rev() {
+                let col = a.add(j * lda);
+                let xj = x.add(j * incx);
+
+                if !unit {
+                    *xj *= *col.add(j);
+                }
+                *xj += ddot(j, col, 1, x, incx);
+            }
+        }
+        (Uplo::Lower, true) => {
+            for j in 0..n {
+                let col = a.add(j * lda);
+                let xj = x.add(j * incx);
+
+                if !unit {
+                    *xj *= *col.add(j);
+                }
+                *xj += ddot(n - j - 1, col.add(j + 1), 1, xj.add(incx), incx);
+            }
+        }
+    }
+}
+
+/// Solves op(A) * x = b of a triangular n x n A stored in `uplo`, x
+/// overwrites b
+pub unsafe fn dtrsv(
+    layout: Layout,
+    uplo: Uplo,
+    trans: bool,
+    diag: Diag,
+    n: usize,
+    a: *const f64,
+    lda: usize,
+    x: *mut f64,
+    incx: usize,
+) {
+    let trans = trans != (layout == Layout::RowMajor);
+    let unit = diag == Diag::Unit;
+
+    // substitution in the order of dtrmv reversed
+    match (col_major(layout, uplo), trans) {
+        (Uplo::Upper, false) => {
+            for j in (0..n).rev() {
+                let col = a.add(j * lda);
+                let xj = x.add(j * incx);
+
+                if !unit {
+                    *xj /= *col.add(j);
+                }
+                daxpy(j, -*xj, col, 1, x, incx);
+            }
+        }
+        (Uplo::Lower, false) => {
+            for j in 0..n {
+                let col = a.add(j * lda);
+                let xj = x.add(j * incx);
+
+                if !unit {
+                    *xj /= *col.add(j);
+                }
+                daxpy(n - j - 1, -*xj, col.add(j + 1), 1, xj.add(incx), incx);
+            }
+        }
+        (Uplo::Upper, true) => {
+            for j in 0..n {
+                let col = a.add(j * lda);
+                let xj = x.add(j * incx);
+
+                *xj -= ddot(j, col, 1, x, incx);
+                if !unit {
+                    *xj /= *col.add(j);
+                }
+            }
+        }
+        (Uplo::Lower, true) => {
+            for j in (0..n).rev() {
+                let col = a.add(j * lda);
+                let xj = x.add(j * incx);
+
+                *xj -= ddot(n - j - 1, col.add(j + 1), 1, xj.add(incx), incx);
+                if !unit {
+                    *xj /= *col.add(j);
+                }
+            }
+        }
+    }
+}
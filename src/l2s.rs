@@ -0,0 +1,324 @@
+use crate::executor::Executor;
+use crate::gemm::{Diag, Layout, Uplo};
+use crate::gemv::gemv;
+use crate::kernel::generic;
+use crate::kernel::isa::Isa;
+use crate::kernel::params;
+use crate::l1s::{saxpy, sdot};
+
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+use crate::kernel::avx;
+
+/// y = alpha * op(A) * x + beta * y of an m x n A, `trans` selects A^T
+pub unsafe fn sgemv<E: Executor>(
+    e: &E,
+    layout: Layout,
+    trans: bool,
+    m: usize,
+    n: usize,
+    alpha: f32,
+    a: *const f32,
+    lda: usize,
+    x: *const f32,
+    incx: usize,
+    beta: f32,
+    y: *mut f32,
+    incy: usize,
+) {
+    match params::single::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => gemv(
+            e,
+            layout,
+            trans,
+            m,
+            n,
+            alpha,
+            a,
+            lda,
+            x,
+            incx,
+            beta,
+            y,
+            incy,
+            avx::l2s::sgemv_n,
+            avx::l2s::sgemv_t,
+        ),
+        _ => gemv(
+            e,
+            layout,
+            trans,
+            m,
+            n,
+            alpha,
+            a,
+            lda,
+            x,
+            incx,
+            beta,
+            y,
+            incy,
+            generic::l2s::sgemv_n,
+            generic::l2s::sgemv_t,
+        ),
+    }
+}
+
+// Row-major A is stored as column-major A^T: a symmetric matrix keeps the
+// opposite triangle, a triangular one also swaps op(A).
+fn col_major(layout: Layout, uplo: Uplo) -> Uplo {
+    match layout {
+        Layout::ColMajor => uplo,
+        Layout::RowMajor => uplo.flip(),
+    }
+}
+
+/// A += alpha * x * y^T of an m x n A
+pub unsafe fn sger(
+    layout: Layout,
+    m: usize,
+    n: usize,
+    alpha: f32,
+    x: *const f32,
+    incx: usize,
+    y: *const f32,
+    incy: usize,
+    a: *mut f32,
+    lda: usize,
+) {
+    // row-major A is column-major A^T += alpha * y * x^T
+    let (m, n, x, incx, y, incy) = match layout {
+        Layout::ColMajor => (m, n, x, incx, y, incy),
+        Layout::RowMajor => (n, m, y, incy, x, incx),
+    };
+
+    for j in 0..n {
+        saxpy(m, alpha * *y.add(j * incy), x, incx, a.add(j * lda), 1);
+    }
+}
+
+/// A += alpha * x * x^T of a symmetric n x n A, only `uplo` is updated
+pub unsafe fn ssyr(
+    layout: Layout,
+    uplo: Uplo,
+    n: usize,
+    alpha: f32,
+    x: *const f32,
+    incx: usize,
+    a: *mut f32,
+    lda: usize,
+) {
+    for j in 0..n {
+        let xj = alpha * *x.add(j * incx);
+        let col = a.add(j * lda);
+
+        match col_major(layout, uplo) {
+            Uplo::Upper => saxpy(j + 1, xj, x, incx, col, 1),
+            Uplo::Lower => saxpy(n - j, xj, x.add(j * incx), incx, col.add(j), 1),
+        }
+    }
+}
+
+/// A += alpha * (x * y^T + y * x^T) of a symmetric n x n A, only `uplo` is
+/// updated
+pub unsafe fn ssyr2(
+    layout: Layout,
+    uplo: Uplo,
+    n: usize,
+    alpha: f32,
+    x: *const f32,
+    incx: usize,
+    y: *const f32,
+    incy: usize,
+    a: *mut f32,
+    lda: usize,
+) {
+    for j in 0..n {
+        let xj = alpha * *x.add(j * incx);
+        let yj = alpha * *y.add(j * incy);
+        let col = a.add(j * lda);
+
+        match col_major(layout, uplo) {
+            Uplo::Upper => {
+                saxpy(j + 1, yj, x, incx, col, 1);
+                saxpy(j + 1, xj, y, incy, col, 1);
+            }
+            Uplo::Lower => {
+                saxpy(n - j, yj, x.add(j * incx), incx, col.add(j), 1);
+                saxpy(n - j, xj, y.add(j * incy), incy, col.add(j), 1);
+            }
+        }
+    }
+}
+
+/// y = alpha * A * x + beta * y of a symmetric n x n A stored in `uplo`
+pub unsafe fn ssymv(
+    layout: Layout,
+    uplo: Uplo,
+    n: usize,
+    alpha: f32,
+    a: *const f32,
+    lda: usize,
+    x: *const f32,
+    incx: usize,
+    beta: f32,
+    y: *mut f32,
+    incy: usize,
+) {
+    for i in 0..n {
+        let yi = y.add(i * incy);
+        *yi = if beta == 0.0 { 0.0 } else { beta * *yi };
+    }
+
+    // column j of the triangle is also row j, it adds to y above (below) j
+    // and its dot product with x goes to y[j]
+    for j in 0..n {
+        let xj = alpha * *x.add(j * incx);
+        let col = a.add(j * lda);
+        let diag = *col.add(j) * *x.add(j * incx);
+
+        let dot = match col_major(layout, uplo) {
+            Uplo::Upper => {
+                saxpy(j, xj, col, 1, y, incy);
+                sdot(j, col, 1, x, incx)
+            }
+            Uplo::Lower => {
+                let len = n - j - 1;
+
+                saxpy(len, xj, col.add(j + 1), 1, y.add((j + 1) * incy), incy);
+                sdot(len, col.add(j + 1), 1, x.add((j + 1) * incx), incx)
+            }
+        };
+
+        *y.add(j * incy) += alpha * (diag + dot);
+    }
+}
+
+/// x = op(A) * x of a triangular n x n A stored in `uplo`
+pub unsafe fn strmv(
+    layout: Layout,
+    uplo: Uplo,
+    trans: bool,
+    diag: Diag,
+    n: usize,
+    a: *const f32,
+    lda: usize,
+    x: *mut f32,
+    incx: usize,
+) {
+    let trans = trans != (layout == Layout::RowMajor);
+    let unit = diag == Diag::Unit;
+
+    // every step reads the elements of x not yet overwritten
+    match (col_major(layout, uplo), trans) {
+        (Uplo::Upper, false) => {
+            for j in 0..n {
+                let col = a.add(j * lda);
+                let xj = x.add(j * incx);
+
+                saxpy(j, *xj, col, 1, x, incx);
+                if !unit {
+                    *xj *= *col.add(j);
+                }
+            }
+        }
+        (Uplo::Lower, false) => {
+            for j in (0..n).rev() {
+                let col = a.add(j * lda);
+                let xj = x.add(j * incx);
+
+                saxpy(n - j - 1, *xj, col.add(j + 1), 1, xj.add(incx), incx);
+                if !unit {
+                    *xj *= *col.add(j);
+                }
+            }
+        }
+        (Uplo::Upper, true) => {
+            for j in (0..n).rev() {
+                let col = a.add(j * lda);
+                let xj = x.add(j * incx);
+
+                if !unit {
+                    *xj *= *col.add(j);
+                }
+                *xj += sdot(j, col, 1, x, incx);
+            }
+        }
+        (Uplo::Lower, true) => {
+            for j in 0..n {
+                let col = a.add(j * lda);
+                let xj = x.add(j * incx);
+
+                if !unit {
+                    *xj *= *col.add(j);
+                }
+                *xj += sdot(n - j - 1, col.add(j + 1), 1, xj.add(incx), incx);
+            }
+        }
+    }
+}
+
+/// Solves op(A) * x = b of a triangular n x n A stored in `uplo`, x
+/// overwrites b
+pub unsafe fn strsv(
+    layout: Layout,
+    uplo: Uplo,
+    trans: bool,
+    diag: Diag,
+    n: usize,
+    a: *const f32,
+    lda: usize,
+    x: *mut f32,
+    incx: usize,
+) {
+    let trans = trans != (layout == Layout::RowMajor);
+    let unit = diag == Diag::Unit;
+
+    // substitution in the order of strmv reversed
+    match (col_major(layout, uplo), trans) {
+        (Uplo::Upper, false) => {
+            for j in (0..n).rev() {
+                let col = a.add(j * lda);
+                let xj = x.add(j * incx);
+
+                if !unit {
+                    *xj /= *col.add(j);
+                }
+                saxpy(j, -*xj, col, 1, x, incx);
+            }
+        }
+        (Uplo::Lower, false) => {
+            for j in 0..n {
+                let col = a.add(j * lda);
+                let xj = x.add(j * incx);
+
+                if !unit {
+                    *xj /= *col.add(j);
+                }
+                saxpy(n - j - 1, -*xj, col.add(j + 1), 1, xj.add(incx), incx);
+            }
+        }
+        (Uplo::Upper, true) => {
+            for j in 0..n {
+                let col = a.add(j * lda);
+                let xj = x.add(j * incx);
+
+                *xj -= sdot(j, col, 1, x, incx);
+                if !unit {
+                    *xj /= *col.add(j);
+                }
+            }
+        }
+        (Uplo::Lower, true) => {
+            for j in (0..n).rev() {
+                let col = a.add(j * lda);
+                let xj = x.add(j * incx);
+
+                *xj -= sdot(n - j - 1, col.add(j + 1), 1, xj.add(incx), incx);
+                if !unit {
+                    *xj /= *col.add(j);
+                }
+            }
+        }
+    }
+}
@@ -1,10 +1,34 @@
 mod aligned_alloc;
+#[cfg(feature = "cblas")]
+pub mod cblas;
+mod cgemm;
+pub mod complex;
+pub mod context;
+mod dgemm;
 pub mod dim;
+pub mod epilogue;
+mod error;
 pub mod executor;
+#[cfg(feature = "fortran")]
+pub mod fortran;
 pub mod gemm;
+mod gemv;
+pub mod half;
 pub mod kernel;
+mod l1d;
+mod l1s;
+mod l2d;
+mod l2s;
 pub mod matrix;
+pub mod packed;
+mod qgemm;
+pub mod quant;
+pub mod safe;
 mod sgemm;
+mod trmm;
+mod trsm;
+pub mod tune;
+mod zgemm;
 
 #[cfg(test)]
 extern crate blas;
@@ -13,4 +37,31 @@ extern crate openblas;
 #[cfg(test)]
 mod test;
 
-pub use crate::sgemm::sgemm;
+pub use crate::cgemm::{cgemm, cgemm_blocking, cgemm_isa, cgemm_set_blocking, cgemm_set_isa};
+pub use crate::complex::Complex;
+pub use crate::context::GemmContext;
+pub use crate::dgemm::{
+    dgemm, dgemm_batch_strided, dgemm_blocking, dgemm_epilogue, dgemm_grouped, dgemm_isa,
+    dgemm_pack_a, dgemm_pack_b, dgemm_packed_a, dgemm_packed_b, dgemm_set_blocking, dgemm_set_isa,
+    dgemm_strided, dgemm_with_context, dgemm_workspace_size, dtrmm, dtrsm,
+};
+pub use crate::epilogue::{Activation, Bias, Epilogue, Fused, Linear, NoEpilogue};
+pub use crate::error::{GemmError, Operand};
+pub use crate::gemm::{Diag, GemmProblem, Layout, Side, Transpose, Uplo};
+pub use crate::half::Input;
+pub use crate::kernel::Blocking;
+pub use crate::l1d::{dasum, daxpy, dcopy, ddot, dnrm2, drot, dscal, dswap};
+pub use crate::l1s::{sasum, saxpy, scopy, sdot, snrm2, srot, sscal, sswap};
+pub use crate::l2d::{dgemv, dger, dsymv, dsyr, dsyr2, dtrmv, dtrsv};
+pub use crate::l2s::{sgemv, sger, ssymv, ssyr, ssyr2, strmv, strsv};
+pub use crate::packed::{PackedA, PackedB};
+pub use crate::qgemm::{
+    qgemm, qgemm_blocking, qgemm_isa, qgemm_requantize, qgemm_set_blocking, qgemm_set_isa,
+};
+pub use crate::quant::Scale;
+pub use crate::sgemm::{
+    sgemm, sgemm_batch_strided, sgemm_blocking, sgemm_epilogue, sgemm_grouped, sgemm_isa,
+    sgemm_mixed, sgemm_pack_a, sgemm_pack_b, sgemm_packed_a, sgemm_packed_b, sgemm_set_blocking,
+    sgemm_set_isa, sgemm_strided, sgemm_with_context, sgemm_workspace_size, strmm, strsm,
+};
+pub use crate::zgemm::{zgemm, zgemm_blocking, zgemm_isa, zgemm_set_blocking, zgemm_set_isa};
@@ -1,14 +1,62 @@
-pub trait Number: core::fmt::Display + Copy + Send + Sync + 'static {}
+pub trait Number:
+    core::fmt::Display
+    + Copy
+    + Send
+    + Sync
+    + PartialEq
+    + core::ops::Add<Output = Self>
+    + core::ops::Mul<Output = Self>
+    + 'static
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    #[inline]
+    fn conj(self) -> Self {
+        self
+    }
+}
+
+/// Encoding of the stored elements. Matrices of 16-bit floats are read as
+/// f32, `get` widens the elements.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Storage {
+    Native,
+    F16,
+    Bf16,
+}
+
+/// Encoding of the elements of a [`ByteMatrix`], which are read as i32.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ByteStorage {
+    U8,
+    I8,
+}
+
+impl Number for f32 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+}
+
+impl Number for f64 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+}
 
-impl Number for f32 {}
-impl Number for f64 {}
+impl Number for i32 {
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+}
 
 pub trait Matrix<F: Number>: Copy + Send + Sync + 'static {
     unsafe fn sub(&self, row: usize, col: usize) -> Self;
     unsafe fn sub_col(&self, col: usize) -> Self;
     unsafe fn sub_row(&self, row: usize) -> Self;
+    unsafe fn offset(&self, count: usize) -> Self;
     unsafe fn is_transposed(&self) -> bool;
     unsafe fn stride(&self) -> usize;
+    unsafe fn row_stride(&self) -> usize;
+    unsafe fn col_stride(&self) -> usize;
     unsafe fn get(&self, row: usize, col: usize) -> F;
     unsafe fn index(&self, row: usize, col: usize) -> *const F;
     unsafe fn row(&self, row: usize) -> *const F;
@@ -18,6 +66,25 @@ pub trait Matrix<F: Number>: Copy + Send + Sync + 'static {
     unsafe fn inc_col(&mut self);
     unsafe fn shift_row(&mut self, rows: usize);
     unsafe fn shift_col(&mut self, cols: usize);
+
+    // Whether `get` conjugates the elements, the pointers always address the
+    // stored ones
+    #[inline]
+    unsafe fn is_conjugated(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    unsafe fn storage(&self) -> Storage {
+        Storage::Native
+    }
+
+    // Address of the stored elements whatever their encoding, for the
+    // packing routines that widen them
+    #[inline]
+    unsafe fn stored_ptr(&self) -> *const u8 {
+        self.ptr() as *const u8
+    }
 }
 
 pub trait MatrixMut<F: Number>: Matrix<F> {
@@ -68,6 +135,14 @@ impl<F: Number> Matrix<F> for ConstMatrix<F> {
         }
     }
 
+    #[inline]
+    unsafe fn offset(&self, count: usize) -> Self {
+        Self {
+            ptr: self.ptr.add(count),
+            ..*self
+        }
+    }
+
     #[inline]
     unsafe fn is_transposed(&self) -> bool {
         false
@@ -78,6 +153,16 @@ impl<F: Number> Matrix<F> for ConstMatrix<F> {
         self.stride
     }
 
+    #[inline]
+    unsafe fn row_stride(&self) -> usize {
+        self.stride
+    }
+
+    #[inline]
+    unsafe fn col_stride(&self) -> usize {
+        1
+    }
+
     #[inline]
     unsafe fn get(&self, row: usize, col: usize) -> F {
         *self.ptr.add(row * self.stride + col)
@@ -164,6 +249,14 @@ impl<F: Number> Matrix<F> for ConstTransposedMatrix<F> {
         }
     }
 
+    #[inline]
+    unsafe fn offset(&self, count: usize) -> Self {
+        Self {
+            ptr: self.ptr.add(count),
+            ..*self
+        }
+    }
+
     #[inline]
     unsafe fn is_transposed(&self) -> bool {
         true
@@ -174,6 +267,16 @@ impl<F: Number> Matrix<F> for ConstTransposedMatrix<F> {
         self.stride
     }
 
+    #[inline]
+    unsafe fn row_stride(&self) -> usize {
+        1
+    }
+
+    #[inline]
+    unsafe fn col_stride(&self) -> usize {
+        self.stride
+    }
+
     #[inline]
     unsafe fn get(&self, row: usize, col: usize) -> F {
         *self.ptr.add(col * self.stride + row)
@@ -260,6 +363,14 @@ impl<F: Number> Matrix<F> for MutMatrix<F> {
         }
     }
 
+    #[inline]
+    unsafe fn offset(&self, count: usize) -> Self {
+        Self {
+            ptr: self.ptr.add(count),
+            ..*self
+        }
+    }
+
     #[inline]
     unsafe fn is_transposed(&self) -> bool {
         false
@@ -270,6 +381,16 @@ impl<F: Number> Matrix<F> for MutMatrix<F> {
         self.stride
     }
 
+    #[inline]
+    unsafe fn row_stride(&self) -> usize {
+        self.stride
+    }
+
+    #[inline]
+    unsafe fn col_stride(&self) -> usize {
+        1
+    }
+
     #[inline]
     unsafe fn get(&self, row: usize, col: usize) -> F {
         *self.ptr.add(row * self.stride + col)
@@ -383,6 +504,14 @@ impl<F: Number> Matrix<F> for MutTransposedMatrix<F> {
         }
     }
 
+    #[inline]
+    unsafe fn offset(&self, count: usize) -> Self {
+        Self {
+            ptr: self.ptr.add(count),
+            ..*self
+        }
+    }
+
     #[inline]
     unsafe fn is_transposed(&self) -> bool {
         true
@@ -393,6 +522,16 @@ impl<F: Number> Matrix<F> for MutTransposedMatrix<F> {
         self.stride
     }
 
+    #[inline]
+    unsafe fn row_stride(&self) -> usize {
+        1
+    }
+
+    #[inline]
+    unsafe fn col_stride(&self) -> usize {
+        self.stride
+    }
+
     #[inline]
     unsafe fn get(&self, row: usize, col: usize) -> F {
         *self.ptr.add(col * self.stride + row)
@@ -465,3 +604,547 @@ impl<F: Number> MatrixMut<F> for MutTransposedMatrix<F> {
         self.ptr
     }
 }
+
+#[derive(Copy, Clone)]
+pub struct StridedMatrix<F: Number> {
+    pub row_stride: usize,
+    pub col_stride: usize,
+    pub ptr: *mut F,
+}
+
+unsafe impl<F: Number> Send for StridedMatrix<F> {}
+unsafe impl<F: Number> Sync for StridedMatrix<F> {}
+
+impl<F: Number> StridedMatrix<F> {
+    pub fn new(ptr: *mut F, row_stride: usize, col_stride: usize) -> Self {
+        Self {
+            ptr,
+            row_stride,
+            col_stride,
+        }
+    }
+}
+
+impl<F: Number> Matrix<F> for StridedMatrix<F> {
+    #[inline]
+    unsafe fn sub(&self, row: usize, col: usize) -> Self {
+        Self {
+            ptr: self.index_mut(row, col),
+            ..*self
+        }
+    }
+
+    #[inline]
+    unsafe fn sub_col(&self, col: usize) -> Self {
+        Self {
+            ptr: self.col_mut(col),
+            ..*self
+        }
+    }
+
+    #[inline]
+    unsafe fn sub_row(&self, row: usize) -> Self {
+        Self {
+            ptr: self.row_mut(row),
+            ..*self
+        }
+    }
+
+    #[inline]
+    unsafe fn offset(&self, count: usize) -> Self {
+        Self {
+            ptr: self.ptr.add(count),
+            ..*self
+        }
+    }
+
+    #[inline]
+    unsafe fn is_transposed(&self) -> bool {
+        self.row_stride == 1 && self.col_stride != 1
+    }
+
+    #[inline]
+    unsafe fn stride(&self) -> usize {
+        if self.is_transposed() {
+            self.col_stride
+        } else {
+            self.row_stride
+        }
+    }
+
+    #[inline]
+    unsafe fn row_stride(&self) -> usize {
+        self.row_stride
+    }
+
+    #[inline]
+    unsafe fn col_stride(&self) -> usize {
+        self.col_stride
+    }
+
+    #[inline]
+    unsafe fn get(&self, row: usize, col: usize) -> F {
+        *self.index(row, col)
+    }
+
+    #[inline]
+    unsafe fn index(&self, row: usize, col: usize) -> *const F {
+        self.ptr.add(row * self.row_stride + col * self.col_stride)
+    }
+
+    #[inline]
+    unsafe fn row(&self, row: usize) -> *const F {
+        self.ptr.add(row * self.row_stride)
+    }
+
+    #[inline]
+    unsafe fn col(&self, col: usize) -> *const F {
+        self.ptr.add(col * self.col_stride)
+    }
+
+    #[inline]
+    unsafe fn ptr(&self) -> *const F {
+        self.ptr
+    }
+
+    #[inline]
+    unsafe fn inc_row(&mut self) {
+        self.ptr = self.row_mut(1);
+    }
+
+    #[inline]
+    unsafe fn inc_col(&mut self) {
+        self.ptr = self.col_mut(1);
+    }
+
+    #[inline]
+    unsafe fn shift_row(&mut self, rows: usize) {
+        self.ptr = self.row_mut(rows);
+    }
+
+    #[inline]
+    unsafe fn shift_col(&mut self, cols: usize) {
+        self.ptr = self.col_mut(cols);
+    }
+}
+
+impl<F: Number> MatrixMut<F> for StridedMatrix<F> {
+    #[inline]
+    unsafe fn set(&self, row: usize, col: usize, value: F) {
+        *self.index_mut(row, col) = value;
+    }
+
+    #[inline]
+    unsafe fn index_mut(&self, row: usize, col: usize) -> *mut F {
+        self.ptr.add(row * self.row_stride + col * self.col_stride)
+    }
+
+    #[inline]
+    unsafe fn row_mut(&self, row: usize) -> *mut F {
+        self.ptr.add(row * self.row_stride)
+    }
+
+    #[inline]
+    unsafe fn col_mut(&self, col: usize) -> *mut F {
+        self.ptr.add(col * self.col_stride)
+    }
+
+    #[inline]
+    unsafe fn ptr_mut(&self) -> *mut F {
+        self.ptr
+    }
+}
+
+/// Conjugated view of a matrix, used for the conjugate-transpose operands.
+#[derive(Copy, Clone)]
+pub struct ConjMatrix<M> {
+    pub inner: M,
+}
+
+impl<M> ConjMatrix<M> {
+    pub fn new(inner: M) -> Self {
+        Self { inner }
+    }
+}
+
+impl<F: Number, M: Matrix<F>> Matrix<F> for ConjMatrix<M> {
+    #[inline]
+    unsafe fn sub(&self, row: usize, col: usize) -> Self {
+        Self::new(self.inner.sub(row, col))
+    }
+
+    #[inline]
+    unsafe fn sub_col(&self, col: usize) -> Self {
+        Self::new(self.inner.sub_col(col))
+    }
+
+    #[inline]
+    unsafe fn sub_row(&self, row: usize) -> Self {
+        Self::new(self.inner.sub_row(row))
+    }
+
+    #[inline]
+    unsafe fn offset(&self, count: usize) -> Self {
+        Self::new(self.inner.offset(count))
+    }
+
+    #[inline]
+    unsafe fn is_transposed(&self) -> bool {
+        self.inner.is_transposed()
+    }
+
+    #[inline]
+    unsafe fn stride(&self) -> usize {
+        self.inner.stride()
+    }
+
+    #[inline]
+    unsafe fn row_stride(&self) -> usize {
+        self.inner.row_stride()
+    }
+
+    #[inline]
+    unsafe fn col_stride(&self) -> usize {
+        self.inner.col_stride()
+    }
+
+    #[inline]
+    unsafe fn get(&self, row: usize, col: usize) -> F {
+        self.inner.get(row, col).conj()
+    }
+
+    #[inline]
+    unsafe fn index(&self, row: usize, col: usize) -> *const F {
+        self.inner.index(row, col)
+    }
+
+    #[inline]
+    unsafe fn row(&self, row: usize) -> *const F {
+        self.inner.row(row)
+    }
+
+    #[inline]
+    unsafe fn col(&self, col: usize) -> *const F {
+        self.inner.col(col)
+    }
+
+    #[inline]
+    unsafe fn ptr(&self) -> *const F {
+        self.inner.ptr()
+    }
+
+    #[inline]
+    unsafe fn inc_row(&mut self) {
+        self.inner.inc_row();
+    }
+
+    #[inline]
+    unsafe fn inc_col(&mut self) {
+        self.inner.inc_col();
+    }
+
+    #[inline]
+    unsafe fn shift_row(&mut self, rows: usize) {
+        self.inner.shift_row(rows);
+    }
+
+    #[inline]
+    unsafe fn shift_col(&mut self, cols: usize) {
+        self.inner.shift_col(cols);
+    }
+
+    #[inline]
+    unsafe fn is_conjugated(&self) -> bool {
+        !self.inner.is_conjugated()
+    }
+
+    #[inline]
+    unsafe fn storage(&self) -> Storage {
+        self.inner.storage()
+    }
+
+    #[inline]
+    unsafe fn stored_ptr(&self) -> *const u8 {
+        self.inner.stored_ptr()
+    }
+}
+
+#[cold]
+fn no_pointer<S: core::fmt::Debug>(storage: S) -> ! {
+    panic!(
+        "{:?} elements have no native pointer, read them with get",
+        storage
+    )
+}
+
+/// f16 or bf16 elements stored as `u16`, read as f32. There are no f32
+/// pointers to them, only `get` reads the elements.
+#[derive(Copy, Clone)]
+pub struct HalfMatrix {
+    pub storage: Storage,
+    pub row_stride: usize,
+    pub col_stride: usize,
+    pub ptr: *const u16,
+}
+
+unsafe impl Send for HalfMatrix {}
+unsafe impl Sync for HalfMatrix {}
+
+impl HalfMatrix {
+    pub fn new(ptr: *const u16, storage: Storage, row_stride: usize, col_stride: usize) -> Self {
+        Self {
+            storage,
+            row_stride,
+            col_stride,
+            ptr,
+        }
+    }
+}
+
+impl Matrix<f32> for HalfMatrix {
+    #[inline]
+    unsafe fn sub(&self, row: usize, col: usize) -> Self {
+        Self {
+            ptr: self.ptr.add(row * self.row_stride + col * self.col_stride),
+            ..*self
+        }
+    }
+
+    #[inline]
+    unsafe fn sub_col(&self, col: usize) -> Self {
+        Self {
+            ptr: self.ptr.add(col * self.col_stride),
+            ..*self
+        }
+    }
+
+    #[inline]
+    unsafe fn sub_row(&self, row: usize) -> Self {
+        Self {
+            ptr: self.ptr.add(row * self.row_stride),
+            ..*self
+        }
+    }
+
+    #[inline]
+    unsafe fn offset(&self, count: usize) -> Self {
+        Self {
+            ptr: self.ptr.add(count),
+            ..*self
+        }
+    }
+
+    #[inline]
+    unsafe fn is_transposed(&self) -> bool {
+        self.row_stride == 1 && self.col_stride != 1
+    }
+
+    #[inline]
+    unsafe fn stride(&self) -> usize {
+        if self.is_transposed() {
+            self.col_stride
+        } else {
+            self.row_stride
+        }
+    }
+
+    #[inline]
+    unsafe fn row_stride(&self) -> usize {
+        self.row_stride
+    }
+
+    #[inline]
+    unsafe fn col_stride(&self) -> usize {
+        self.col_stride
+    }
+
+    #[inline]
+    unsafe fn get(&self, row: usize, col: usize) -> f32 {
+        let bits = *self.ptr.add(row * self.row_stride + col * self.col_stride);
+
+        crate::half::widen(self.storage, bits)
+    }
+
+    // The elements are not stored as f32, the kernels check `storage` and
+    // read them through `get`
+    unsafe fn index(&self, _row: usize, _col: usize) -> *const f32 {
+        no_pointer(self.storage)
+    }
+
+    unsafe fn row(&self, _row: usize) -> *const f32 {
+        no_pointer(self.storage)
+    }
+
+    unsafe fn col(&self, _col: usize) -> *const f32 {
+        no_pointer(self.storage)
+    }
+
+    unsafe fn ptr(&self) -> *const f32 {
+        no_pointer(self.storage)
+    }
+
+    #[inline]
+    unsafe fn inc_row(&mut self) {
+        self.ptr = self.ptr.add(self.row_stride);
+    }
+
+    #[inline]
+    unsafe fn inc_col(&mut self) {
+        self.ptr = self.ptr.add(self.col_stride);
+    }
+
+    #[inline]
+    unsafe fn shift_row(&mut self, rows: usize) {
+        self.ptr = self.ptr.add(rows * self.row_stride);
+    }
+
+    #[inline]
+    unsafe fn shift_col(&mut self, cols: usize) {
+        self.ptr = self.ptr.add(cols * self.col_stride);
+    }
+
+    #[inline]
+    unsafe fn storage(&self) -> Storage {
+        self.storage
+    }
+
+    #[inline]
+    unsafe fn stored_ptr(&self) -> *const u8 {
+        self.ptr as *const u8
+    }
+}
+
+/// u8 or i8 elements, read as i32. There are no i32 pointers to them, only
+/// `get` reads the elements.
+#[derive(Copy, Clone)]
+pub struct ByteMatrix {
+    pub storage: ByteStorage,
+    pub row_stride: usize,
+    pub col_stride: usize,
+    pub ptr: *const u8,
+}
+
+unsafe impl Send for ByteMatrix {}
+unsafe impl Sync for ByteMatrix {}
+
+impl ByteMatrix {
+    pub fn new(ptr: *const u8, storage: ByteStorage, row_stride: usize, col_stride: usize) -> Self {
+        Self {
+            storage,
+            row_stride,
+            col_stride,
+            ptr,
+        }
+    }
+}
+
+impl Matrix<i32> for ByteMatrix {
+    #[inline]
+    unsafe fn sub(&self, row: usize, col: usize) -> Self {
+        Self {
+            ptr: self.ptr.add(row * self.row_stride + col * self.col_stride),
+            ..*self
+        }
+    }
+
+    #[inline]
+    unsafe fn sub_col(&self, col: usize) -> Self {
+        Self {
+            ptr: self.ptr.add(col * self.col_stride),
+            ..*self
+        }
+    }
+
+    #[inline]
+    unsafe fn sub_row(&self, row: usize) -> Self {
+        Self {
+            ptr: self.ptr.add(row * self.row_stride),
+            ..*self
+        }
+    }
+
+    #[inline]
+    unsafe fn offset(&self, count: usize) -> Self {
+        Self {
+            ptr: self.ptr.add(count),
+            ..*self
+        }
+    }
+
+    #[inline]
+    unsafe fn is_transposed(&self) -> bool {
+        self.row_stride == 1 && self.col_stride != 1
+    }
+
+    #[inline]
+    unsafe fn stride(&self) -> usize {
+        if self.is_transposed() {
+            self.col_stride
+        } else {
+            self.row_stride
+        }
+    }
+
+    #[inline]
+    unsafe fn row_stride(&self) -> usize {
+        self.row_stride
+    }
+
+    #[inline]
+    unsafe fn col_stride(&self) -> usize {
+        self.col_stride
+    }
+
+    #[inline]
+    unsafe fn get(&self, row: usize, col: usize) -> i32 {
+        let byte = *self.ptr.add(row * self.row_stride + col * self.col_stride);
+
+        match self.storage {
+            ByteStorage::U8 => byte as i32,
+            ByteStorage::I8 => byte as i8 as i32,
+        }
+    }
+
+    // The elements are not stored as i32, the kernels check `storage` and
+    // read them through `get`
+    unsafe fn index(&self, _row: usize, _col: usize) -> *const i32 {
+        no_pointer(self.storage)
+    }
+
+    unsafe fn row(&self, _row: usize) -> *const i32 {
+        no_pointer(self.storage)
+    }
+
+    unsafe fn col(&self, _col: usize) -> *const i32 {
+        no_pointer(self.storage)
+    }
+
+    unsafe fn ptr(&self) -> *const i32 {
+        no_pointer(self.storage)
+    }
+
+    #[inline]
+    unsafe fn inc_row(&mut self) {
+        self.ptr = self.ptr.add(self.row_stride);
+    }
+
+    #[inline]
+    unsafe fn inc_col(&mut self) {
+        self.ptr = self.ptr.add(self.col_stride);
+    }
+
+    #[inline]
+    unsafe fn shift_row(&mut self, rows: usize) {
+        self.ptr = self.ptr.add(rows * self.row_stride);
+    }
+
+    #[inline]
+    unsafe fn shift_col(&mut self, cols: usize) {
+        self.ptr = self.ptr.add(cols * self.col_stride);
+    }
+
+    #[inline]
+    unsafe fn stored_ptr(&self) -> *const u8 {
+        self.ptr
+    }
+}
@@ -0,0 +1,466 @@
+use crate::aligned_alloc;
+use crate::dim::Dim;
+use crate::epilogue::NoEpilogue;
+use crate::error::{GemmError, Operand};
+use crate::executor::Executor;
+use crate::gemm::{self, packing_lens, Layout, Raw};
+use crate::kernel::{Blocking, GemmKernel};
+use crate::matrix::{ConstMatrix, ConstTransposedMatrix, Matrix, MutMatrix, Number};
+use std::marker::PhantomData;
+
+// An operand packed once into the layout `pack_row_a`/`pack_row_b` produce.
+// Every KC block of k holds all panels of `width` rows, the last panel is
+// padded with zeros so edge tiles go through the main micro-kernel as well.
+// The gemm steps through k by the KC the panels were packed with, whatever
+// the blocking is set to by then.
+struct Panels<F: Number> {
+    buf: aligned_alloc::Alloc,
+    rows: usize,
+    padded: usize,
+    width: usize,
+    k: usize,
+    kc: usize,
+    _f: PhantomData<F>,
+}
+
+unsafe impl<F: Number> Send for Panels<F> {}
+unsafe impl<F: Number> Sync for Panels<F> {}
+
+impl<F: Number> Panels<F> {
+    fn new(rows: usize, width: usize, k: usize, kc: usize) -> Self {
+        let padded = rows + (width - rows % width) % width;
+        let len = std::cmp::max(padded * k, 1);
+
+        Panels {
+            buf: aligned_alloc::Alloc::new(len * std::mem::size_of::<F>()),
+            rows,
+            padded,
+            width,
+            k,
+            kc,
+            _f: PhantomData,
+        }
+    }
+
+    // The current blocking with the KC of the panels
+    fn blocking<K, MR, NR>(&self, width: usize, operand: Operand) -> Result<Blocking, GemmError>
+    where
+        MR: Dim,
+        NR: Dim,
+        K: GemmKernel<F, MR, NR>,
+    {
+        if self.width != width {
+            return Err(GemmError::PackedMismatch { operand });
+        }
+
+        let blocking = Blocking {
+            kc: self.kc,
+            ..K::blocking()
+        };
+        blocking.validate::<F>(MR::DIM, NR::DIM)?;
+
+        Ok(blocking)
+    }
+
+    unsafe fn block(&self, p: usize) -> MutMatrix<F> {
+        let p_b = std::cmp::min(self.k - p, self.kc);
+
+        MutMatrix::new(self.buf.ptr::<F>().add(p * self.padded), p_b)
+    }
+
+    fn operand(&self) -> PackedOperand<F> {
+        PackedOperand {
+            ptr: self.buf.ptr(),
+            padded: self.padded,
+        }
+    }
+}
+
+// Panels as an operand of `gemm::gemm_operands`. The loops step through k by
+// the KC the panels were packed with, so every block starts at `p * padded`.
+#[derive(Copy, Clone)]
+struct PackedOperand<F: Number> {
+    ptr: *mut F,
+    padded: usize,
+}
+
+unsafe impl<F: Number> Send for PackedOperand<F> {}
+unsafe impl<F: Number> Sync for PackedOperand<F> {}
+
+impl<F: Number> gemm::Operand<F> for PackedOperand<F> {
+    type Matrix = ConstMatrix<F>;
+
+    #[inline]
+    fn matrix(self) -> Option<ConstMatrix<F>> {
+        None
+    }
+
+    #[inline]
+    unsafe fn panels(self, p: usize, index: usize, p_b: usize) -> MutMatrix<F> {
+        MutMatrix::new(self.ptr.add(p * self.padded + index * p_b), p_b)
+    }
+}
+
+/// `A` packed for repeated use with [`gemm_packed_a`].
+pub struct PackedA<F: Number> {
+    layout: Layout,
+    panels: Panels<F>,
+}
+
+impl<F: Number> PackedA<F> {
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    pub fn rows(&self) -> usize {
+        self.panels.rows
+    }
+
+    pub fn depth(&self) -> usize {
+        self.panels.k
+    }
+}
+
+/// `B` packed for repeated use with [`gemm_packed_b`].
+pub struct PackedB<F: Number> {
+    layout: Layout,
+    panels: Panels<F>,
+}
+
+impl<F: Number> PackedB<F> {
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    pub fn cols(&self) -> usize {
+        self.panels.rows
+    }
+
+    pub fn depth(&self) -> usize {
+        self.panels.k
+    }
+}
+
+/// Packs the m x k matrix `op(A)`.
+pub unsafe fn pack_a<E, F, K, MR, NR>(
+    e: &E,
+    layout: Layout,
+    transa: bool,
+    m: usize,
+    k: usize,
+    a: *const F,
+    lda: usize,
+) -> PackedA<F>
+where
+    E: Executor,
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+{
+    // row-major C is column-major C^T = op(B)^T * op(A)^T, so there A is
+    // the right-hand operand
+    let panels = match (layout, transa) {
+        (Layout::ColMajor, false) => {
+            pack_left::<E, F, K, MR, NR, _>(e, m, k, ConstMatrix::new(a, lda))
+        }
+        (Layout::ColMajor, true) => {
+            pack_left::<E, F, K, MR, NR, _>(e, m, k, ConstTransposedMatrix::new(a, lda))
+        }
+        (Layout::RowMajor, false) => {
+            pack_right::<E, F, K, MR, NR, _>(e, m, k, ConstMatrix::new(a, lda))
+        }
+        (Layout::RowMajor, true) => {
+            pack_right::<E, F, K, MR, NR, _>(e, m, k, ConstTransposedMatrix::new(a, lda))
+        }
+    };
+
+    PackedA { layout, panels }
+}
+
+/// Packs the k x n matrix `op(B)`.
+pub unsafe fn pack_b<E, F, K, MR, NR>(
+    e: &E,
+    layout: Layout,
+    transb: bool,
+    k: usize,
+    n: usize,
+    b: *const F,
+    ldb: usize,
+) -> PackedB<F>
+where
+    E: Executor,
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+{
+    let panels = match (layout, transb) {
+        (Layout::ColMajor, false) => {
+            pack_right::<E, F, K, MR, NR, _>(e, n, k, ConstMatrix::new(b, ldb))
+        }
+        (Layout::ColMajor, true) => {
+            pack_right::<E, F, K, MR, NR, _>(e, n, k, ConstTransposedMatrix::new(b, ldb))
+        }
+        (Layout::RowMajor, false) => {
+            pack_left::<E, F, K, MR, NR, _>(e, n, k, ConstMatrix::new(b, ldb))
+        }
+        (Layout::RowMajor, true) => {
+            pack_left::<E, F, K, MR, NR, _>(e, n, k, ConstTransposedMatrix::new(b, ldb))
+        }
+    };
+
+    PackedB { layout, panels }
+}
+
+/// `C = alpha * A * op(B) + beta * C` with a pre-packed `A`, in the layout `a`
+/// was packed for and with the KC it was packed with.
+pub unsafe fn gemm_packed_a<E, F, K, MR, NR>(
+    e: &E,
+    transb: bool,
+    n: usize,
+    alpha: F,
+    a: &PackedA<F>,
+    b: *const F,
+    ldb: usize,
+    beta: F,
+    c: *mut F,
+    ldc: usize,
+) -> Result<(), GemmError>
+where
+    E: Executor,
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+{
+    let (m, k) = (a.rows(), a.depth());
+
+    // row-major C is column-major C^T = op(B)^T * A^T, so there A is the
+    // right-hand operand
+    let (width, m, n) = match a.layout {
+        Layout::ColMajor => (MR::DIM, m, n),
+        Layout::RowMajor => (NR::DIM, n, m),
+    };
+    let blocking = a.panels.blocking::<K, MR, NR>(width, Operand::A)?;
+
+    let packed = a.panels.operand();
+
+    macro_rules! run {
+        ($a:expr, $b:expr) => {
+            gemm_packed::<E, F, K, MR, NR, _, _>(e, m, n, k, alpha, $a, $b, beta, c, ldc, blocking)
+        };
+    }
+
+    match (a.layout, transb) {
+        (Layout::ColMajor, false) => run!(packed, Raw(ConstMatrix::new(b, ldb))),
+        (Layout::ColMajor, true) => run!(packed, Raw(ConstTransposedMatrix::new(b, ldb))),
+        (Layout::RowMajor, false) => run!(Raw(ConstMatrix::new(b, ldb)), packed),
+        (Layout::RowMajor, true) => run!(Raw(ConstTransposedMatrix::new(b, ldb)), packed),
+    }
+
+    Ok(())
+}
+
+/// `C = alpha * op(A) * B + beta * C` with a pre-packed `B`, in the layout `b`
+/// was packed for and with the KC it was packed with.
+pub unsafe fn gemm_packed_b<E, F, K, MR, NR>(
+    e: &E,
+    transa: bool,
+    m: usize,
+    alpha: F,
+    a: *const F,
+    lda: usize,
+    b: &PackedB<F>,
+    beta: F,
+    c: *mut F,
+    ldc: usize,
+) -> Result<(), GemmError>
+where
+    E: Executor,
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+{
+    let (n, k) = (b.cols(), b.depth());
+
+    let (width, m, n) = match b.layout {
+        Layout::ColMajor => (NR::DIM, m, n),
+        Layout::RowMajor => (MR::DIM, n, m),
+    };
+    let blocking = b.panels.blocking::<K, MR, NR>(width, Operand::B)?;
+
+    let packed = b.panels.operand();
+
+    macro_rules! run {
+        ($a:expr, $b:expr) => {
+            gemm_packed::<E, F, K, MR, NR, _, _>(e, m, n, k, alpha, $a, $b, beta, c, ldc, blocking)
+        };
+    }
+
+    match (b.layout, transa) {
+        (Layout::ColMajor, false) => run!(Raw(ConstMatrix::new(a, lda)), packed),
+        (Layout::ColMajor, true) => run!(Raw(ConstTransposedMatrix::new(a, lda)), packed),
+        (Layout::RowMajor, false) => run!(packed, Raw(ConstMatrix::new(a, lda))),
+        (Layout::RowMajor, true) => run!(packed, Raw(ConstTransposedMatrix::new(a, lda))),
+    }
+
+    Ok(())
+}
+
+// Column-major m x n product with one operand packed beforehand, only the
+// other one needs a packing buffer
+unsafe fn gemm_packed<E, F, K, MR, NR, A, B>(
+    e: &E,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: F,
+    a: A,
+    b: B,
+    beta: F,
+    c: *mut F,
+    ldc: usize,
+    blocking: Blocking,
+) where
+    E: Executor,
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+    A: gemm::Operand<F>,
+    B: gemm::Operand<F>,
+{
+    if m == 0 || n == 0 {
+        return;
+    }
+
+    let (pa_len, pb_len) = packing_lens::<F>(blocking, m, n, k);
+    let len = if a.matrix().is_some() { pa_len } else { pb_len };
+    let buf = aligned_alloc::Alloc::new(std::cmp::max(len, 1) * std::mem::size_of::<F>());
+
+    gemm::gemm_operands::<E, F, K, MR, NR, _, _, _, _>(
+        e,
+        m,
+        n,
+        k,
+        alpha,
+        a,
+        b,
+        beta,
+        MutMatrix::new(c, ldc),
+        blocking,
+        buf.ptr(),
+        buf.ptr(),
+        NoEpilogue,
+    );
+}
+
+unsafe fn pack_left<E, F, K, MR, NR, A>(e: &E, m: usize, k: usize, a: A) -> Panels<F>
+where
+    E: Executor,
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+    A: Matrix<F>,
+{
+    let kc = K::blocking().kc;
+    let panels = Panels::new(m, MR::DIM, k, kc);
+
+    for p in (0..k).step_by(kc) {
+        pack_block_a::<E, F, K, MR, NR, _>(e, m, a.sub_row(p), panels.block(p));
+    }
+
+    e.synchronize();
+
+    panels
+}
+
+unsafe fn pack_right<E, F, K, MR, NR, B>(e: &E, n: usize, k: usize, b: B) -> Panels<F>
+where
+    E: Executor,
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+    B: Matrix<F>,
+{
+    let kc = K::blocking().kc;
+    let panels = Panels::new(n, NR::DIM, k, kc);
+
+    for p in (0..k).step_by(kc) {
+        pack_block_b::<E, F, K, MR, NR, _>(e, n, b.sub_col(p), panels.block(p));
+    }
+
+    e.synchronize();
+
+    panels
+}
+
+// Packs `m` columns of `a` into `pa.stride` deep panels, zero padding the
+// last one. The caller synchronizes the executor.
+unsafe fn pack_block_a<E, F, K, MR, NR, A>(e: &E, m: usize, a: A, pa: MutMatrix<F>)
+where
+    E: Executor,
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+    A: Matrix<F>,
+{
+    let m_main = m - m % MR::DIM;
+
+    e.execute(0, m_main, MR::DIM, move |i| {
+        K::pack_row_a(a.sub_col(i), pa.sub_row(i))
+    });
+
+    // the kernel decides the panel layout, so the leftover columns are
+    // packed from a zero padded copy MR wide
+    if m_main < m {
+        let a = a.sub_col(m_main);
+        let mut tail = vec![F::ZERO; pa.stride * MR::DIM];
+
+        for p in 0..pa.stride {
+            for i in 0..m - m_main {
+                tail[p * MR::DIM + i] = a.get(p, i);
+            }
+        }
+
+        K::pack_row_a(ConstMatrix::new(tail.as_ptr(), MR::DIM), pa.sub_row(m_main));
+    }
+}
+
+// Same as `pack_block_a` for `n` rows of `b`.
+unsafe fn pack_block_b<E, F, K, MR, NR, B>(e: &E, n: usize, b: B, pb: MutMatrix<F>)
+where
+    E: Executor,
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+    B: Matrix<F>,
+{
+    let n_main = n - n % NR::DIM;
+
+    e.execute(0, n_main, NR::DIM, move |j| {
+        K::pack_row_b(b.sub_row(j), pb.sub_row(j))
+    });
+
+    if n_main < n {
+        let b = b.sub_row(n_main);
+        let mut tail = vec![F::ZERO; pb.stride * NR::DIM];
+
+        for p in 0..pb.stride {
+            for j in 0..n - n_main {
+                tail[p * NR::DIM + j] = b.get(j, p);
+            }
+        }
+
+        K::pack_row_b(
+            ConstTransposedMatrix::new(tail.as_ptr(), NR::DIM),
+            pb.sub_row(n_main),
+        );
+    }
+}
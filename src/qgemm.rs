@@ -0,0 +1,118 @@
+use crate::dim::*;
+use crate::error::GemmError;
+use crate::executor::Executor;
+use crate::gemm::Layout;
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+use crate::kernel::avx::AvxKernel;
+use crate::kernel::generic::GenericKernel;
+use crate::kernel::isa::Isa;
+use crate::kernel::{params, Blocking};
+use crate::quant::{requantize, Scale};
+
+/// `C = (A - a_zero) * (B - b_zero)` of u8 A and i8 B, accumulated in i32
+pub unsafe fn qgemm<E: Executor>(
+    e: &E,
+    layout: Layout,
+    transa: bool,
+    transb: bool,
+    m: usize,
+    n: usize,
+    k: usize,
+    a: *const u8,
+    lda: usize,
+    a_zero: u8,
+    b: *const i8,
+    ldb: usize,
+    b_zero: i8,
+    c: *mut i32,
+    ldc: usize,
+) {
+    match params::quantized::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => crate::gemm::gemm_quantized::<E, AvxKernel<i32, GenericKernel>, A8, A6>(
+            e, layout, transa, transb, m, n, k, a, lda, a_zero, b, ldb, b_zero, c, ldc,
+        ),
+
+        _ => crate::gemm::gemm_quantized::<E, GenericKernel, A8, A6>(
+            e, layout, transa, transb, m, n, k, a, lda, a_zero, b, ldb, b_zero, c, ldc,
+        ),
+    }
+}
+
+/// qgemm requantizing the accumulators to u8 with `c_zero` as the zero-point
+/// of C
+pub unsafe fn qgemm_requantize<E: Executor>(
+    e: &E,
+    layout: Layout,
+    transa: bool,
+    transb: bool,
+    m: usize,
+    n: usize,
+    k: usize,
+    a: *const u8,
+    lda: usize,
+    a_zero: u8,
+    b: *const i8,
+    ldb: usize,
+    b_zero: i8,
+    scale: Scale,
+    c_zero: u8,
+    c: *mut u8,
+    ldc: usize,
+) {
+    if let Scale::Channel(scales) = scale {
+        assert_eq!(scales.len(), n, "one scale per column of C");
+    }
+
+    let ld = match layout {
+        Layout::ColMajor => m,
+        Layout::RowMajor => n,
+    };
+
+    let mut acc = vec![0i32; m * n];
+
+    qgemm(
+        e,
+        layout,
+        transa,
+        transb,
+        m,
+        n,
+        k,
+        a,
+        lda,
+        a_zero,
+        b,
+        ldb,
+        b_zero,
+        acc.as_mut_ptr(),
+        std::cmp::max(ld, 1),
+    );
+
+    for j in 0..n {
+        for i in 0..m {
+            let (idx, cidx) = match layout {
+                Layout::ColMajor => (i + j * m, i + j * ldc),
+                Layout::RowMajor => (i * n + j, i * ldc + j),
+            };
+
+            *c.add(cidx) = requantize(acc[idx], scale.get(j), c_zero);
+        }
+    }
+}
+
+pub fn qgemm_blocking() -> Blocking {
+    params::quantized::blocking()
+}
+
+pub fn qgemm_set_blocking(blocking: Blocking) -> Result<(), GemmError> {
+    params::quantized::set_blocking(blocking)
+}
+
+pub fn qgemm_isa() -> Isa {
+    params::quantized::isa()
+}
+
+pub fn qgemm_set_isa(isa: Isa) -> Result<(), GemmError> {
+    params::quantized::set_isa(isa)
+}
@@ -0,0 +1,29 @@
+//! Output stage of the quantized gemm, which maps the i32 accumulators back
+//! to u8.
+
+/// Scale of the requantized output, one for all of C or one per column of C,
+/// i.e. per output channel when B holds the weights.
+#[derive(Copy, Clone, Debug)]
+pub enum Scale<'a> {
+    Tensor(f32),
+    Channel(&'a [f32]),
+}
+
+impl Scale<'_> {
+    #[inline]
+    pub fn get(&self, col: usize) -> f32 {
+        match self {
+            Scale::Tensor(scale) => *scale,
+            Scale::Channel(scales) => scales[col],
+        }
+    }
+}
+
+/// `acc * scale` rounded to the nearest integer, ties away from zero, plus
+/// `zero_point` and saturated to u8
+#[inline]
+pub fn requantize(acc: i32, scale: f32, zero_point: u8) -> u8 {
+    let q = (acc as f32 * scale).round() as i32;
+
+    q.saturating_add(zero_point as i32).clamp(0, 255) as u8
+}
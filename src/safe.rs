@@ -0,0 +1,168 @@
+use crate::error::{GemmError, Operand};
+use crate::executor::Executor;
+use crate::gemm::Layout;
+
+// Checks a column-major `rows x cols` view with leading dimension `ld`,
+// i.e. `cols` vectors of `rows` contiguous elements placed `ld` apart
+pub(crate) fn check_operand(
+    operand: Operand,
+    rows: usize,
+    cols: usize,
+    ld: usize,
+    len: usize,
+) -> Result<(), GemmError> {
+    let min = std::cmp::max(1, rows);
+    if ld < min {
+        return Err(GemmError::LeadingDimension { operand, ld, min });
+    }
+
+    let required = if rows == 0 || cols == 0 {
+        0
+    } else {
+        (cols - 1)
+            .checked_mul(ld)
+            .and_then(|x| x.checked_add(rows))
+            .ok_or(GemmError::Overflow { operand })?
+    };
+
+    if len < required {
+        return Err(GemmError::BufferTooSmall {
+            operand,
+            len,
+            required,
+        });
+    }
+
+    Ok(())
+}
+
+pub(crate) fn check_gemm(
+    layout: Layout,
+    transa: bool,
+    transb: bool,
+    m: usize,
+    n: usize,
+    k: usize,
+    (lda, a_len): (usize, usize),
+    (ldb, b_len): (usize, usize),
+    (ldc, c_len): (usize, usize),
+) -> Result<(), GemmError> {
+    // shape of the stored (not transposed) operand, column-major
+    let shape = |trans: bool, rows: usize, cols: usize| match (layout, trans) {
+        (Layout::ColMajor, false) | (Layout::RowMajor, true) => (rows, cols),
+        (Layout::ColMajor, true) | (Layout::RowMajor, false) => (cols, rows),
+    };
+
+    let (rows, cols) = shape(transa, m, k);
+    check_operand(Operand::A, rows, cols, lda, a_len)?;
+
+    let (rows, cols) = shape(transb, k, n);
+    check_operand(Operand::B, rows, cols, ldb, b_len)?;
+
+    let (rows, cols) = shape(false, m, n);
+    check_operand(Operand::C, rows, cols, ldc, c_len)
+}
+
+pub fn sgemm<E: Executor>(
+    e: &E,
+    layout: Layout,
+    transa: bool,
+    transb: bool,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: f32,
+    a: &[f32],
+    lda: usize,
+    b: &[f32],
+    ldb: usize,
+    beta: f32,
+    c: &mut [f32],
+    ldc: usize,
+) -> Result<(), GemmError> {
+    check_gemm(
+        layout,
+        transa,
+        transb,
+        m,
+        n,
+        k,
+        (lda, a.len()),
+        (ldb, b.len()),
+        (ldc, c.len()),
+    )?;
+
+    unsafe {
+        crate::sgemm(
+            e,
+            layout,
+            transa,
+            transb,
+            m,
+            n,
+            k,
+            alpha,
+            a.as_ptr(),
+            lda,
+            b.as_ptr(),
+            ldb,
+            beta,
+            c.as_mut_ptr(),
+            ldc,
+        );
+    }
+
+    Ok(())
+}
+
+pub fn dgemm<E: Executor>(
+    e: &E,
+    layout: Layout,
+    transa: bool,
+    transb: bool,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: f64,
+    a: &[f64],
+    lda: usize,
+    b: &[f64],
+    ldb: usize,
+    beta: f64,
+    c: &mut [f64],
+    ldc: usize,
+) -> Result<(), GemmError> {
+    check_gemm(
+        layout,
+        transa,
+        transb,
+        m,
+        n,
+        k,
+        (lda, a.len()),
+        (ldb, b.len()),
+        (ldc, c.len()),
+    )?;
+
+    unsafe {
+        crate::dgemm(
+            e,
+            layout,
+            transa,
+            transb,
+            m,
+            n,
+            k,
+            alpha,
+            a.as_ptr(),
+            lda,
+            b.as_ptr(),
+            ldb,
+            beta,
+            c.as_mut_ptr(),
+            ldc,
+        );
+    }
+
+    Ok(())
+}
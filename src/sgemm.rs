@@ -1,13 +1,24 @@
+use crate::context::GemmContext;
 use crate::dim::*;
+use crate::epilogue::Epilogue;
+use crate::error::GemmError;
 use crate::executor::Executor;
+use crate::gemm::{Diag, GemmProblem, Layout, Side, Uplo};
+use crate::half::Input;
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
 use crate::kernel::avx::AvxKernel;
 use crate::kernel::generic::GenericKernel;
+use crate::kernel::isa::Isa;
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+use crate::kernel::sse::SseKernel;
+use crate::kernel::{params, Blocking};
+use crate::packed::{PackedA, PackedB};
 
 pub unsafe fn sgemm<E: Executor>(
     e: &E,
+    layout: Layout,
     transa: bool,
     transb: bool,
-    transc: bool,
     m: usize,
     n: usize,
     k: usize,
@@ -20,7 +31,427 @@ pub unsafe fn sgemm<E: Executor>(
     c: *mut f32,
     ldc: usize,
 ) {
-    crate::gemm::gemm::<E, f32, AvxKernel<f32, GenericKernel>, A16, A5>(
-        e, transa, transb, transc, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc,
-    );
+    match params::single::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => crate::gemm::gemm::<E, f32, AvxKernel<f32, GenericKernel>, A16, A5>(
+            e, layout, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc,
+        ),
+
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Sse => crate::gemm::gemm::<E, f32, SseKernel<f32, GenericKernel>, A16, A5>(
+            e, layout, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc,
+        ),
+
+        _ => crate::gemm::gemm::<E, f32, GenericKernel, A16, A5>(
+            e, layout, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc,
+        ),
+    }
+}
+
+/// sgemm with `epilogue` applied to every element of C, see
+/// [`Fused`](crate::epilogue::Fused)
+pub unsafe fn sgemm_epilogue<E: Executor, P: Epilogue<f32>>(
+    e: &E,
+    layout: Layout,
+    transa: bool,
+    transb: bool,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: f32,
+    a: *const f32,
+    lda: usize,
+    b: *const f32,
+    ldb: usize,
+    beta: f32,
+    c: *mut f32,
+    ldc: usize,
+    epilogue: P,
+) {
+    match params::single::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => {
+            crate::gemm::gemm_epilogue::<E, f32, AvxKernel<f32, GenericKernel>, A16, A5, P>(
+                e, layout, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc, epilogue,
+            )
+        }
+
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Sse => {
+            crate::gemm::gemm_epilogue::<E, f32, SseKernel<f32, GenericKernel>, A16, A5, P>(
+                e, layout, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc, epilogue,
+            )
+        }
+
+        _ => crate::gemm::gemm_epilogue::<E, f32, GenericKernel, A16, A5, P>(
+            e, layout, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc, epilogue,
+        ),
+    }
+}
+
+/// Solves op(A) * X = alpha * B or X * op(A) = alpha * B of a triangular A,
+/// X overwrites B
+pub unsafe fn strsm<E: Executor>(
+    e: &E,
+    layout: Layout,
+    side: Side,
+    uplo: Uplo,
+    trans: bool,
+    diag: Diag,
+    m: usize,
+    n: usize,
+    alpha: f32,
+    a: *const f32,
+    lda: usize,
+    b: *mut f32,
+    ldb: usize,
+) {
+    match params::single::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => crate::trsm::trsm::<E, f32, AvxKernel<f32, GenericKernel>, A16, A5>(
+            e, layout, side, uplo, trans, diag, m, n, alpha, a, lda, b, ldb,
+        ),
+
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Sse => crate::trsm::trsm::<E, f32, SseKernel<f32, GenericKernel>, A16, A5>(
+            e, layout, side, uplo, trans, diag, m, n, alpha, a, lda, b, ldb,
+        ),
+
+        _ => crate::trsm::trsm::<E, f32, GenericKernel, A16, A5>(
+            e, layout, side, uplo, trans, diag, m, n, alpha, a, lda, b, ldb,
+        ),
+    }
+}
+
+/// B = alpha * op(A) * B or B = alpha * B * op(A) of a triangular A
+pub unsafe fn strmm<E: Executor>(
+    e: &E,
+    layout: Layout,
+    side: Side,
+    uplo: Uplo,
+    trans: bool,
+    diag: Diag,
+    m: usize,
+    n: usize,
+    alpha: f32,
+    a: *const f32,
+    lda: usize,
+    b: *mut f32,
+    ldb: usize,
+) {
+    match params::single::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => crate::trmm::trmm::<E, f32, AvxKernel<f32, GenericKernel>, A16, A5>(
+            e, layout, side, uplo, trans, diag, m, n, alpha, a, lda, b, ldb,
+        ),
+
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Sse => crate::trmm::trmm::<E, f32, SseKernel<f32, GenericKernel>, A16, A5>(
+            e, layout, side, uplo, trans, diag, m, n, alpha, a, lda, b, ldb,
+        ),
+
+        _ => crate::trmm::trmm::<E, f32, GenericKernel, A16, A5>(
+            e, layout, side, uplo, trans, diag, m, n, alpha, a, lda, b, ldb,
+        ),
+    }
+}
+
+/// sgemm with f16 or bf16 elements in A and/or B
+pub unsafe fn sgemm_mixed<E: Executor>(
+    e: &E,
+    layout: Layout,
+    transa: bool,
+    transb: bool,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: f32,
+    a: Input,
+    lda: usize,
+    b: Input,
+    ldb: usize,
+    beta: f32,
+    c: *mut f32,
+    ldc: usize,
+) {
+    match params::single::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => crate::gemm::gemm_mixed::<E, AvxKernel<f32, GenericKernel>, A16, A5>(
+            e, layout, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc,
+        ),
+
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Sse => crate::gemm::gemm_mixed::<E, SseKernel<f32, GenericKernel>, A16, A5>(
+            e, layout, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc,
+        ),
+
+        _ => crate::gemm::gemm_mixed::<E, GenericKernel, A16, A5>(
+            e, layout, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc,
+        ),
+    }
+}
+
+pub unsafe fn sgemm_strided<E: Executor>(
+    e: &E,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: f32,
+    a: *const f32,
+    rsa: usize,
+    csa: usize,
+    b: *const f32,
+    rsb: usize,
+    csb: usize,
+    beta: f32,
+    c: *mut f32,
+    rsc: usize,
+    csc: usize,
+) {
+    match params::single::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => crate::gemm::gemm_strided::<E, f32, AvxKernel<f32, GenericKernel>, A16, A5>(
+            e, m, n, k, alpha, a, rsa, csa, b, rsb, csb, beta, c, rsc, csc,
+        ),
+
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Sse => crate::gemm::gemm_strided::<E, f32, SseKernel<f32, GenericKernel>, A16, A5>(
+            e, m, n, k, alpha, a, rsa, csa, b, rsb, csb, beta, c, rsc, csc,
+        ),
+
+        _ => crate::gemm::gemm_strided::<E, f32, GenericKernel, A16, A5>(
+            e, m, n, k, alpha, a, rsa, csa, b, rsb, csb, beta, c, rsc, csc,
+        ),
+    }
+}
+
+pub unsafe fn sgemm_batch_strided<E: Executor>(
+    e: &E,
+    layout: Layout,
+    transa: bool,
+    transb: bool,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: f32,
+    a: *const f32,
+    lda: usize,
+    stride_a: usize,
+    b: *const f32,
+    ldb: usize,
+    stride_b: usize,
+    beta: f32,
+    c: *mut f32,
+    ldc: usize,
+    stride_c: usize,
+    batch: usize,
+) {
+    match params::single::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => {
+            crate::gemm::gemm_batch_strided::<E, f32, AvxKernel<f32, GenericKernel>, A16, A5>(
+                e, layout, transa, transb, m, n, k, alpha, a, lda, stride_a, b, ldb, stride_b,
+                beta, c, ldc, stride_c, batch,
+            )
+        }
+
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Sse => {
+            crate::gemm::gemm_batch_strided::<E, f32, SseKernel<f32, GenericKernel>, A16, A5>(
+                e, layout, transa, transb, m, n, k, alpha, a, lda, stride_a, b, ldb, stride_b,
+                beta, c, ldc, stride_c, batch,
+            )
+        }
+
+        _ => crate::gemm::gemm_batch_strided::<E, f32, GenericKernel, A16, A5>(
+            e, layout, transa, transb, m, n, k, alpha, a, lda, stride_a, b, ldb, stride_b, beta, c,
+            ldc, stride_c, batch,
+        ),
+    }
+}
+
+pub unsafe fn sgemm_grouped<E: Executor>(e: &E, problems: &[GemmProblem<f32>]) {
+    match params::single::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => {
+            crate::gemm::gemm_grouped::<E, f32, AvxKernel<f32, GenericKernel>, A16, A5>(e, problems)
+        }
+
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Sse => {
+            crate::gemm::gemm_grouped::<E, f32, SseKernel<f32, GenericKernel>, A16, A5>(e, problems)
+        }
+
+        _ => crate::gemm::gemm_grouped::<E, f32, GenericKernel, A16, A5>(e, problems),
+    }
+}
+
+pub unsafe fn sgemm_pack_a<E: Executor>(
+    e: &E,
+    layout: Layout,
+    transa: bool,
+    m: usize,
+    k: usize,
+    a: *const f32,
+    lda: usize,
+) -> PackedA<f32> {
+    match params::single::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => crate::packed::pack_a::<E, f32, AvxKernel<f32, GenericKernel>, A16, A5>(
+            e, layout, transa, m, k, a, lda,
+        ),
+
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Sse => crate::packed::pack_a::<E, f32, SseKernel<f32, GenericKernel>, A16, A5>(
+            e, layout, transa, m, k, a, lda,
+        ),
+
+        _ => {
+            crate::packed::pack_a::<E, f32, GenericKernel, A16, A5>(e, layout, transa, m, k, a, lda)
+        }
+    }
+}
+
+pub unsafe fn sgemm_pack_b<E: Executor>(
+    e: &E,
+    layout: Layout,
+    transb: bool,
+    k: usize,
+    n: usize,
+    b: *const f32,
+    ldb: usize,
+) -> PackedB<f32> {
+    match params::single::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => crate::packed::pack_b::<E, f32, AvxKernel<f32, GenericKernel>, A16, A5>(
+            e, layout, transb, k, n, b, ldb,
+        ),
+
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Sse => crate::packed::pack_b::<E, f32, SseKernel<f32, GenericKernel>, A16, A5>(
+            e, layout, transb, k, n, b, ldb,
+        ),
+
+        _ => {
+            crate::packed::pack_b::<E, f32, GenericKernel, A16, A5>(e, layout, transb, k, n, b, ldb)
+        }
+    }
+}
+
+pub unsafe fn sgemm_packed_a<E: Executor>(
+    e: &E,
+    transb: bool,
+    n: usize,
+    alpha: f32,
+    a: &PackedA<f32>,
+    b: *const f32,
+    ldb: usize,
+    beta: f32,
+    c: *mut f32,
+    ldc: usize,
+) -> Result<(), GemmError> {
+    match params::single::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => crate::packed::gemm_packed_a::<E, f32, AvxKernel<f32, GenericKernel>, A16, A5>(
+            e, transb, n, alpha, a, b, ldb, beta, c, ldc,
+        ),
+
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Sse => crate::packed::gemm_packed_a::<E, f32, SseKernel<f32, GenericKernel>, A16, A5>(
+            e, transb, n, alpha, a, b, ldb, beta, c, ldc,
+        ),
+
+        _ => crate::packed::gemm_packed_a::<E, f32, GenericKernel, A16, A5>(
+            e, transb, n, alpha, a, b, ldb, beta, c, ldc,
+        ),
+    }
+}
+
+pub unsafe fn sgemm_packed_b<E: Executor>(
+    e: &E,
+    transa: bool,
+    m: usize,
+    alpha: f32,
+    a: *const f32,
+    lda: usize,
+    b: &PackedB<f32>,
+    beta: f32,
+    c: *mut f32,
+    ldc: usize,
+) -> Result<(), GemmError> {
+    match params::single::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => crate::packed::gemm_packed_b::<E, f32, AvxKernel<f32, GenericKernel>, A16, A5>(
+            e, transa, m, alpha, a, lda, b, beta, c, ldc,
+        ),
+
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Sse => crate::packed::gemm_packed_b::<E, f32, SseKernel<f32, GenericKernel>, A16, A5>(
+            e, transa, m, alpha, a, lda, b, beta, c, ldc,
+        ),
+
+        _ => crate::packed::gemm_packed_b::<E, f32, GenericKernel, A16, A5>(
+            e, transa, m, alpha, a, lda, b, beta, c, ldc,
+        ),
+    }
+}
+
+// All f32 kernels share the blocking parameters, so the size holds for any of them
+pub fn sgemm_workspace_size(layout: Layout, m: usize, n: usize, k: usize) -> usize {
+    crate::context::workspace_size::<f32, GenericKernel, A16, A5>(layout, m, n, k)
+}
+
+pub unsafe fn sgemm_with_context<E: Executor>(
+    ctx: &mut GemmContext<f32>,
+    e: &E,
+    layout: Layout,
+    transa: bool,
+    transb: bool,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: f32,
+    a: *const f32,
+    lda: usize,
+    b: *const f32,
+    ldb: usize,
+    beta: f32,
+    c: *mut f32,
+    ldc: usize,
+) -> Result<(), GemmError> {
+    match params::single::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => {
+            crate::context::gemm_with_context::<E, f32, AvxKernel<f32, GenericKernel>, A16, A5>(
+                ctx, e, layout, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc,
+            )
+        }
+
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Sse => {
+            crate::context::gemm_with_context::<E, f32, SseKernel<f32, GenericKernel>, A16, A5>(
+                ctx, e, layout, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc,
+            )
+        }
+
+        _ => crate::context::gemm_with_context::<E, f32, GenericKernel, A16, A5>(
+            ctx, e, layout, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc,
+        ),
+    }
+}
+
+pub fn sgemm_blocking() -> Blocking {
+    params::single::blocking()
+}
+
+pub fn sgemm_set_blocking(blocking: Blocking) -> Result<(), GemmError> {
+    params::single::set_blocking(blocking)
+}
+
+pub fn sgemm_isa() -> Isa {
+    params::single::isa()
+}
+
+pub fn sgemm_set_isa(isa: Isa) -> Result<(), GemmError> {
+    params::single::set_isa(isa)
 }
@@ -0,0 +1,108 @@
+use crate::kernel::cache::{self, CacheLevel, Caches};
+use crate::kernel::{AtomicBlocking, Blocking};
+
+const DEFAULT: Blocking = Blocking {
+    mc: 64,
+    kc: 256,
+    nc: 8190,
+};
+
+fn skylake() -> Caches {
+    Caches {
+        l1d: Some(CacheLevel {
+            size: 32 << 10,
+            ways: 8,
+            line: 64,
+        }),
+        l2: Some(CacheLevel {
+            size: 1 << 20,
+            ways: 16,
+            line: 64,
+        }),
+        l3: Some(CacheLevel {
+            size: 8 << 20,
+            ways: 16,
+            line: 64,
+        }),
+    }
+}
+
+#[test]
+fn test_cache_blocking() {
+    let blocking = cache::blocking::<f32>(&skylake(), 16, 5, DEFAULT);
+    assert_eq!(
+        blocking,
+        Blocking {
+            mc: 704,
+            kc: 320,
+            nc: 5320
+        }
+    );
+
+    let blocking = cache::blocking::<f32>(&Caches::default(), 16, 5, DEFAULT);
+    assert_eq!(blocking, DEFAULT);
+
+    let detected = cache::blocking::<f64>(&cache::detect(), 8, 4, DEFAULT);
+    assert!(detected.validate::<f64>(8, 4).is_ok());
+}
+
+// AMD K10, whose L1d has too few ways for the model
+fn k10(l1_ways: usize) -> Caches {
+    Caches {
+        l1d: Some(CacheLevel {
+            size: 64 << 10,
+            ways: l1_ways,
+            line: 64,
+        }),
+        l2: Some(CacheLevel {
+            size: 512 << 10,
+            ways: 16,
+            line: 64,
+        }),
+        l3: Some(CacheLevel {
+            size: 6 << 20,
+            ways: 48,
+            line: 64,
+        }),
+    }
+}
+
+#[test]
+fn test_cache_blocking_few_ways() {
+    assert_eq!(cache::blocking::<f32>(&k10(2), 16, 5, DEFAULT), DEFAULT);
+    assert_eq!(cache::blocking::<f32>(&k10(1), 16, 5, DEFAULT), DEFAULT);
+    assert_eq!(cache::blocking::<f64>(&k10(2), 8, 4, DEFAULT), DEFAULT);
+    assert_eq!(cache::blocking::<f64>(&k10(0), 8, 4, DEFAULT), DEFAULT);
+}
+
+#[test]
+fn test_parse_size() {
+    assert_eq!(cache::parse_size("48K"), Some(48 << 10));
+    assert_eq!(cache::parse_size("30M"), Some(30 << 20));
+    assert_eq!(cache::parse_size("512"), Some(512));
+    assert_eq!(cache::parse_size("1X"), None);
+}
+
+#[test]
+fn test_blocking_store_is_whole() {
+    let small = Blocking {
+        mc: 32,
+        kc: 128,
+        nc: 1020,
+    };
+    let blocking = AtomicBlocking::new(DEFAULT.mc, DEFAULT.kc, DEFAULT.nc);
+    blocking.store(small);
+
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            for i in 0..10000 {
+                blocking.store(if i % 2 == 0 { DEFAULT } else { small });
+            }
+        });
+
+        for _ in 0..10000 {
+            let current = blocking.load::<f32>(16, 5);
+            assert!(current == DEFAULT || current == small, "{:?}", current);
+        }
+    });
+}
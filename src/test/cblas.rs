@@ -0,0 +1,129 @@
+use crate::cblas::*;
+
+#[test]
+fn test_cblas_sgemm_row_major() {
+    let (m, n, k) = (19, 7, 11);
+
+    let a: Vec<f32> = (0..m * k).map(|x| (x % 7) as f32 - 3.0).collect();
+    let b: Vec<f32> = (0..k * n).map(|x| (x % 5) as f32 - 2.0).collect();
+
+    for &(ta, tb) in &[(false, false), (false, true), (true, false), (true, true)] {
+        // row-major: A is m x k (k x m when transposed), B is k x n (n x k)
+        let lda = if ta { m } else { k };
+        let ldb = if tb { k } else { n };
+
+        let a_at = |i: usize, p: usize| if ta { a[p * lda + i] } else { a[i * lda + p] };
+        let b_at = |p: usize, j: usize| if tb { b[j * ldb + p] } else { b[p * ldb + j] };
+
+        let mut c = vec![1.0f32; m * n];
+
+        unsafe {
+            cblas_sgemm(
+                CBLAS_ROW_MAJOR,
+                if ta { CBLAS_TRANS } else { CBLAS_NO_TRANS },
+                if tb { CBLAS_TRANS } else { CBLAS_NO_TRANS },
+                m as i32,
+                n as i32,
+                k as i32,
+                2.0,
+                a.as_ptr(),
+                lda as i32,
+                b.as_ptr(),
+                ldb as i32,
+                0.5,
+                c.as_mut_ptr(),
+                n as i32,
+            );
+        }
+
+        for i in 0..m {
+            for j in 0..n {
+                let expected = 0.5 + 2.0 * (0..k).map(|p| a_at(i, p) * b_at(p, j)).sum::<f32>();
+                assert_eq!(c[i * n + j], expected);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_cblas_gemm_info() {
+    use crate::cblas::gemm_info;
+
+    let (m, n, k) = (5, 3, 4);
+    let (col, row, nt, t) = (
+        CBLAS_COL_MAJOR,
+        CBLAS_ROW_MAJOR,
+        CBLAS_NO_TRANS,
+        CBLAS_TRANS,
+    );
+
+    assert_eq!(gemm_info(col, nt, nt, m, n, k, m, k, m), 0);
+    assert_eq!(gemm_info(col, t, t, m, n, k, k, n, m), 0);
+    assert_eq!(gemm_info(row, nt, nt, m, n, k, k, n, n), 0);
+    assert_eq!(gemm_info(row, t, t, m, n, k, m, k, n), 0);
+    assert_eq!(gemm_info(col, nt, nt, 0, 0, 0, 1, 1, 1), 0);
+
+    assert_eq!(gemm_info(0, nt, nt, m, n, k, m, k, m), 1);
+    assert_eq!(gemm_info(0, 0, 0, -1, n, k, 0, 0, 0), 1);
+    assert_eq!(gemm_info(col, 0, nt, m, n, k, m, k, m), 2);
+    assert_eq!(gemm_info(col, nt, 0, m, n, k, m, k, m), 3);
+    assert_eq!(gemm_info(col, nt, nt, -1, n, k, m, k, m), 4);
+    assert_eq!(gemm_info(col, nt, nt, m, -1, k, m, k, m), 5);
+    assert_eq!(gemm_info(col, nt, nt, m, n, -1, m, k, m), 6);
+
+    // each leading dimension one short of the stored rows (columns)
+    assert_eq!(gemm_info(col, nt, nt, m, n, k, m - 1, k, m), 9);
+    assert_eq!(gemm_info(col, t, nt, m, n, k, k - 1, k, m), 9);
+    assert_eq!(gemm_info(col, nt, nt, m, n, k, m, k - 1, m), 11);
+    assert_eq!(gemm_info(col, nt, t, m, n, k, m, n - 1, m), 11);
+    assert_eq!(gemm_info(col, nt, nt, m, n, k, m, k, m - 1), 14);
+    assert_eq!(gemm_info(row, nt, nt, m, n, k, k - 1, n, n), 9);
+    assert_eq!(gemm_info(row, t, nt, m, n, k, m - 1, n, n), 9);
+    assert_eq!(gemm_info(row, nt, nt, m, n, k, k, n - 1, n), 11);
+    assert_eq!(gemm_info(row, nt, t, m, n, k, k, k - 1, n), 11);
+    assert_eq!(gemm_info(row, nt, nt, m, n, k, k, n, n - 1), 14);
+    assert_eq!(gemm_info(col, nt, nt, 0, n, 0, 0, 1, 1), 9);
+}
+
+#[test]
+fn test_cblas_sgemm_rejects_short_lda() {
+    let (m, n, k) = (4, 3, 2);
+
+    let a = [1.0f32; 8];
+    let b = [1.0f32; 6];
+    let mut c = [7.0f32; 12];
+
+    unsafe {
+        cblas_sgemm(
+            CBLAS_COL_MAJOR,
+            CBLAS_NO_TRANS,
+            CBLAS_NO_TRANS,
+            m,
+            n,
+            k,
+            1.0,
+            a.as_ptr(),
+            m - 1,
+            b.as_ptr(),
+            k,
+            0.0,
+            c.as_mut_ptr(),
+            m,
+        );
+    }
+
+    assert_eq!(c, [7.0; 12]);
+}
+
+#[test]
+fn test_cblas_l1_negative_inc() {
+    let x = [1.0f32, 2.0, 3.0];
+    let mut y = [0.0f32; 3];
+
+    unsafe {
+        cblas_saxpy(3, 1.0, x.as_ptr(), 1, y.as_mut_ptr(), -1);
+        assert_eq!(y, [3.0, 2.0, 1.0]);
+        assert_eq!(cblas_sdot(3, x.as_ptr(), -1, y.as_ptr(), 1), 14.0);
+        assert_eq!(cblas_sasum(3, x.as_ptr(), 1), 6.0);
+    }
+}
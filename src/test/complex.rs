@@ -0,0 +1,522 @@
+use super::random_matrix;
+use crate::complex::Complex;
+use crate::dim::*;
+use crate::executor::{DefaultExecutor, RayonExecutor};
+use crate::gemm::{Layout, Transpose};
+use crate::kernel::generic::GenericKernel;
+use crate::matrix::Number;
+
+const OPS: [Transpose; 3] = [Transpose::NoTrans, Transpose::Trans, Transpose::ConjTrans];
+
+// Column-major reference
+fn zgemm_ref(
+    transa: Transpose,
+    transb: Transpose,
+    (m, n, k): (usize, usize, usize),
+    alpha: Complex<f64>,
+    a: &[Complex<f64>],
+    lda: usize,
+    b: &[Complex<f64>],
+    ldb: usize,
+    beta: Complex<f64>,
+    c: &mut [Complex<f64>],
+    ldc: usize,
+) {
+    let op = |t: Transpose, x: &[Complex<f64>], ld: usize, i: usize, j: usize| match t {
+        Transpose::NoTrans => x[i + j * ld],
+        Transpose::Trans => x[j + i * ld],
+        Transpose::ConjTrans => x[j + i * ld].conj(),
+    };
+
+    for j in 0..n {
+        for i in 0..m {
+            let mut acc = Complex::ZERO;
+            for p in 0..k {
+                acc = acc + op(transa, a, lda, i, p) * op(transb, b, ldb, p, j);
+            }
+
+            let cij = &mut c[i + j * ldc];
+            *cij = if beta == Complex::ZERO {
+                alpha * acc
+            } else {
+                alpha * acc + beta * *cij
+            };
+        }
+    }
+}
+
+// Computes in f64 and runs `gemm` on the elements converted by `from`
+fn check_gemm<T: Number>(
+    gemm: impl Fn(
+        Layout,
+        Transpose,
+        Transpose,
+        usize,
+        usize,
+        usize,
+        T,
+        &[T],
+        usize,
+        &[T],
+        usize,
+        T,
+        &mut [T],
+        usize,
+    ),
+    from: fn(Complex<f64>) -> T,
+    to: fn(T) -> Complex<f64>,
+    eps: f64,
+    layout: Layout,
+    transa: Transpose,
+    transb: Transpose,
+    (m, n, k): (usize, usize, usize),
+    alpha: Complex<f64>,
+    beta: Complex<f64>,
+) {
+    let a: Vec<_> = random_matrix(m * k).into_iter().map(from).map(to).collect();
+    let b: Vec<_> = random_matrix(n * k).into_iter().map(from).map(to).collect();
+    let c: Vec<_> = random_matrix(m * n).into_iter().map(from).map(to).collect();
+
+    // dimensions of the stored operands, as column-major for the reference
+    let (ar, ac) = if transa == Transpose::NoTrans {
+        (m, k)
+    } else {
+        (k, m)
+    };
+    let (br, bc) = if transb == Transpose::NoTrans {
+        (k, n)
+    } else {
+        (n, k)
+    };
+
+    let (lda, ldb, ldc) = match layout {
+        Layout::ColMajor => (ar, br, m),
+        Layout::RowMajor => (ac, bc, n),
+    };
+
+    // row-major storage read as column-major is the transpose
+    let transpose = |x: &[Complex<f64>], rows: usize, cols: usize| -> Vec<Complex<f64>> {
+        let mut t = vec![Complex::ZERO; rows * cols];
+        for j in 0..cols {
+            for i in 0..rows {
+                t[j + i * cols] = x[i + j * rows];
+            }
+        }
+        t
+    };
+
+    let mut cref = c.clone();
+    match layout {
+        Layout::ColMajor => zgemm_ref(
+            transa,
+            transb,
+            (m, n, k),
+            alpha,
+            &a,
+            lda,
+            &b,
+            ldb,
+            beta,
+            &mut cref,
+            ldc,
+        ),
+        Layout::RowMajor => {
+            let (ta, tb) = (transpose(&a, ac, ar), transpose(&b, bc, br));
+            let mut tc = transpose(&c, n, m);
+            zgemm_ref(
+                transa,
+                transb,
+                (m, n, k),
+                alpha,
+                &ta,
+                ar,
+                &tb,
+                br,
+                beta,
+                &mut tc,
+                m,
+            );
+            cref = transpose(&tc, m, n);
+        }
+    }
+
+    let a: Vec<T> = a.into_iter().map(from).collect();
+    let b: Vec<T> = b.into_iter().map(from).collect();
+    let mut c: Vec<T> = c.into_iter().map(from).collect();
+
+    gemm(
+        layout,
+        transa,
+        transb,
+        m,
+        n,
+        k,
+        from(alpha),
+        &a,
+        lda,
+        &b,
+        ldb,
+        from(beta),
+        &mut c,
+        ldc,
+    );
+
+    for (index, (x, y)) in c.into_iter().map(to).zip(cref).enumerate() {
+        assert!(
+            (x - y).norm_sqr().sqrt() <= eps * (1.0 + y.norm_sqr().sqrt()),
+            "{:?} {:?} {:?}: c[{}]={}, cref[{}]={}",
+            layout,
+            transa,
+            transb,
+            index,
+            x,
+            index,
+            y
+        );
+    }
+}
+
+fn check_zgemm<G>(gemm: G, shape: (usize, usize, usize))
+where
+    G: Fn(
+            Layout,
+            Transpose,
+            Transpose,
+            usize,
+            usize,
+            usize,
+            Complex<f64>,
+            &[Complex<f64>],
+            usize,
+            &[Complex<f64>],
+            usize,
+            Complex<f64>,
+            &mut [Complex<f64>],
+            usize,
+        ) + Copy,
+{
+    for &layout in &[Layout::ColMajor, Layout::RowMajor] {
+        for &transa in &OPS {
+            for &transb in &OPS {
+                let (alpha, beta) = (Complex::new(0.5, -1.5), Complex::new(2.0, 0.25));
+                check_gemm(
+                    gemm,
+                    |x| x,
+                    |x| x,
+                    1e-10,
+                    layout,
+                    transa,
+                    transb,
+                    shape,
+                    alpha,
+                    beta,
+                );
+                check_gemm(
+                    gemm,
+                    |x| x,
+                    |x| x,
+                    1e-10,
+                    layout,
+                    transa,
+                    transb,
+                    shape,
+                    Complex::ONE,
+                    Complex::ZERO,
+                );
+            }
+        }
+    }
+}
+
+fn check_cgemm<G>(gemm: G, shape: (usize, usize, usize))
+where
+    G: Fn(
+            Layout,
+            Transpose,
+            Transpose,
+            usize,
+            usize,
+            usize,
+            Complex<f32>,
+            &[Complex<f32>],
+            usize,
+            &[Complex<f32>],
+            usize,
+            Complex<f32>,
+            &mut [Complex<f32>],
+            usize,
+        ) + Copy,
+{
+    let from = |x: Complex<f64>| Complex::new(x.re as f32, x.im as f32);
+    let to = |x: Complex<f32>| Complex::new(x.re as f64, x.im as f64);
+
+    for &layout in &[Layout::ColMajor, Layout::RowMajor] {
+        for &transa in &OPS {
+            for &transb in &OPS {
+                let (alpha, beta) = (Complex::new(0.5, -1.5), Complex::new(2.0, 0.25));
+                check_gemm(
+                    gemm, from, to, 1e-4, layout, transa, transb, shape, alpha, beta,
+                );
+                check_gemm(
+                    gemm,
+                    from,
+                    to,
+                    1e-4,
+                    layout,
+                    transa,
+                    transb,
+                    shape,
+                    Complex::ONE,
+                    Complex::ZERO,
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_cgemm() {
+    let cgemm = |layout,
+                 transa,
+                 transb,
+                 m,
+                 n,
+                 k,
+                 alpha,
+                 a: &[_],
+                 lda,
+                 b: &[_],
+                 ldb,
+                 beta,
+                 c: &mut [_],
+                 ldc| unsafe {
+        crate::cgemm(
+            &RayonExecutor,
+            layout,
+            transa,
+            transb,
+            m,
+            n,
+            k,
+            alpha,
+            a.as_ptr(),
+            lda,
+            b.as_ptr(),
+            ldb,
+            beta,
+            c.as_mut_ptr(),
+            ldc,
+        )
+    };
+
+    check_cgemm(cgemm, (19, 13, 37));
+    check_cgemm(cgemm, (8, 4, 300));
+}
+
+#[test]
+fn test_zgemm() {
+    let zgemm = |layout,
+                 transa,
+                 transb,
+                 m,
+                 n,
+                 k,
+                 alpha,
+                 a: &[_],
+                 lda,
+                 b: &[_],
+                 ldb,
+                 beta,
+                 c: &mut [_],
+                 ldc| unsafe {
+        crate::zgemm(
+            &RayonExecutor,
+            layout,
+            transa,
+            transb,
+            m,
+            n,
+            k,
+            alpha,
+            a.as_ptr(),
+            lda,
+            b.as_ptr(),
+            ldb,
+            beta,
+            c.as_mut_ptr(),
+            ldc,
+        )
+    };
+
+    check_zgemm(zgemm, (19, 13, 37));
+    check_zgemm(zgemm, (4, 4, 300));
+}
+
+#[test]
+fn test_complex_generic_kernel() {
+    let cgemm = |layout,
+                 transa,
+                 transb,
+                 m,
+                 n,
+                 k,
+                 alpha,
+                 a: &[_],
+                 lda,
+                 b: &[_],
+                 ldb,
+                 beta,
+                 c: &mut [_],
+                 ldc| unsafe {
+        crate::gemm::gemm_conj::<_, Complex<f32>, GenericKernel, A8, A4>(
+            &DefaultExecutor,
+            layout,
+            transa,
+            transb,
+            m,
+            n,
+            k,
+            alpha,
+            a.as_ptr(),
+            lda,
+            b.as_ptr(),
+            ldb,
+            beta,
+            c.as_mut_ptr(),
+            ldc,
+        )
+    };
+    let zgemm = |layout,
+                 transa,
+                 transb,
+                 m,
+                 n,
+                 k,
+                 alpha,
+                 a: &[_],
+                 lda,
+                 b: &[_],
+                 ldb,
+                 beta,
+                 c: &mut [_],
+                 ldc| unsafe {
+        crate::gemm::gemm_conj::<_, Complex<f64>, GenericKernel, A4, A4>(
+            &DefaultExecutor,
+            layout,
+            transa,
+            transb,
+            m,
+            n,
+            k,
+            alpha,
+            a.as_ptr(),
+            lda,
+            b.as_ptr(),
+            ldb,
+            beta,
+            c.as_mut_ptr(),
+            ldc,
+        )
+    };
+
+    check_cgemm(cgemm, (19, 13, 37));
+    check_zgemm(zgemm, (19, 13, 37));
+}
+
+// The packed operands take their panel layout from the kernel, the split
+// real and imaginary parts here, tail panels included
+#[test]
+fn test_zgemm_packed_generic_kernel() {
+    use crate::packed::{gemm_packed_a, gemm_packed_b, pack_a, pack_b};
+
+    let packed_a = |layout,
+                    transa: Transpose,
+                    transb: Transpose,
+                    m,
+                    n,
+                    k,
+                    alpha,
+                    a: &[_],
+                    lda,
+                    b: &[_],
+                    ldb,
+                    beta,
+                    c: &mut [_],
+                    ldc| unsafe {
+        let e = &RayonExecutor;
+        let (ta, tb) = (transa != Transpose::NoTrans, transb != Transpose::NoTrans);
+        let a =
+            pack_a::<_, Complex<f64>, GenericKernel, A4, A4>(e, layout, ta, m, k, a.as_ptr(), lda);
+
+        gemm_packed_a::<_, _, GenericKernel, A4, A4>(
+            e,
+            tb,
+            n,
+            alpha,
+            &a,
+            b.as_ptr(),
+            ldb,
+            beta,
+            c.as_mut_ptr(),
+            ldc,
+        )
+        .unwrap()
+    };
+    let packed_b = |layout,
+                    transa: Transpose,
+                    transb: Transpose,
+                    m,
+                    n,
+                    k,
+                    alpha,
+                    a: &[_],
+                    lda,
+                    b: &[_],
+                    ldb,
+                    beta,
+                    c: &mut [_],
+                    ldc| unsafe {
+        let e = &RayonExecutor;
+        let (ta, tb) = (transa != Transpose::NoTrans, transb != Transpose::NoTrans);
+        let b =
+            pack_b::<_, Complex<f64>, GenericKernel, A4, A4>(e, layout, tb, k, n, b.as_ptr(), ldb);
+
+        gemm_packed_b::<_, _, GenericKernel, A4, A4>(
+            e,
+            ta,
+            m,
+            alpha,
+            a.as_ptr(),
+            lda,
+            &b,
+            beta,
+            c.as_mut_ptr(),
+            ldc,
+        )
+        .unwrap()
+    };
+
+    // m and n leave partial panels of MR = NR = 4
+    for &layout in &[Layout::ColMajor, Layout::RowMajor] {
+        for &transa in &OPS[..2] {
+            for &transb in &OPS[..2] {
+                let (alpha, beta) = (Complex::new(0.5, -1.5), Complex::new(2.0, 0.25));
+
+                for &gemm in &[packed_a, packed_b] {
+                    check_gemm(
+                        gemm,
+                        |x| x,
+                        |x| x,
+                        1e-10,
+                        layout,
+                        transa,
+                        transb,
+                        (19, 13, 37),
+                        alpha,
+                        beta,
+                    );
+                }
+            }
+        }
+    }
+}
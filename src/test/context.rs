@@ -0,0 +1,112 @@
+use super::random_matrix;
+use crate::executor::DefaultExecutor;
+use crate::{GemmContext, GemmError, Layout};
+
+fn sgemm_with_context(ctx: &mut GemmContext<f32>, (m, n, k): (usize, usize, usize)) {
+    let a = random_matrix(m * k);
+    let b = random_matrix(n * k);
+    let mut c = random_matrix(m * n);
+    let mut cref = c.clone();
+
+    unsafe {
+        crate::sgemm(
+            &DefaultExecutor,
+            Layout::RowMajor,
+            false,
+            true,
+            m,
+            n,
+            k,
+            0.5,
+            a.as_ptr(),
+            k,
+            b.as_ptr(),
+            k,
+            2.0,
+            cref.as_mut_ptr(),
+            n,
+        );
+
+        crate::sgemm_with_context(
+            ctx,
+            &DefaultExecutor,
+            Layout::RowMajor,
+            false,
+            true,
+            m,
+            n,
+            k,
+            0.5,
+            a.as_ptr(),
+            k,
+            b.as_ptr(),
+            k,
+            2.0,
+            c.as_mut_ptr(),
+            n,
+        )
+        .unwrap();
+    }
+
+    assert_eq!(c, cref);
+}
+
+#[test]
+fn test_context_owned() {
+    let mut ctx = GemmContext::new(crate::sgemm_workspace_size(Layout::RowMajor, 35, 13, 300));
+
+    // smaller shapes fit into the same workspace
+    sgemm_with_context(&mut ctx, (35, 13, 300));
+    sgemm_with_context(&mut ctx, (7, 13, 30));
+    sgemm_with_context(&mut ctx, (35, 0, 30));
+}
+
+#[test]
+fn test_context_borrowed() {
+    let (m, n, k) = (35, 13, 300);
+    let required = crate::sgemm_workspace_size(Layout::RowMajor, m, n, k);
+
+    let mut buf = vec![0.0f32; required + 1];
+
+    // any start of the buffer works, aligned or not
+    for offset in 0..2 {
+        let mut ctx = GemmContext::from_buffer(&mut buf[offset..offset + required]);
+        sgemm_with_context(&mut ctx, (m, n, k));
+    }
+
+    let mut ctx = GemmContext::from_buffer(&mut buf[..required - 1]);
+    let mut c = vec![0.0f32; m * n];
+
+    let err = unsafe {
+        crate::sgemm_with_context(
+            &mut ctx,
+            &DefaultExecutor,
+            Layout::RowMajor,
+            false,
+            false,
+            m,
+            n,
+            k,
+            1.0,
+            c.as_ptr(),
+            k,
+            c.as_ptr(),
+            n,
+            0.0,
+            c.as_mut_ptr(),
+            n,
+        )
+    };
+
+    assert_eq!(
+        err,
+        Err(GemmError::WorkspaceTooSmall {
+            len: required - 1,
+            required
+        })
+    );
+
+    // nothing to pack, no workspace needed
+    let mut ctx = GemmContext::from_buffer(&mut buf[..0]);
+    sgemm_with_context(&mut ctx, (m, n, 0));
+}
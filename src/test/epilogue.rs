@@ -0,0 +1,207 @@
+use super::{index, random_matrix};
+use crate::dim::*;
+use crate::epilogue::{Activation, Bias, Epilogue, Fused};
+use crate::executor::{DefaultExecutor, RayonExecutor};
+use crate::gemm::Layout;
+use crate::kernel::generic::GenericKernel;
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+use crate::kernel::sse::SseKernel;
+
+// The last one runs the k == 0 path, the one before it spans several KC blocks
+const SHAPES: [(usize, usize, usize); 5] = [
+    (19, 13, 37),
+    (16, 10, 8),
+    (300, 7, 3),
+    (33, 20, 1100),
+    (9, 11, 0),
+];
+
+const ACTIVATIONS: [Activation; 5] = [
+    Activation::Identity,
+    Activation::Relu,
+    Activation::Gelu,
+    Activation::Sigmoid,
+    Activation::Tanh,
+];
+
+// Fused epilogues over m x n covering every option once
+fn epilogues<F: Copy>(scale: &[F], row: &[F], col: &[F], min: F, max: F) -> Vec<Fused<F>> {
+    let mut epilogues: Vec<_> = ACTIVATIONS
+        .iter()
+        .map(|&activation| Fused {
+            activation,
+            bias: Bias::Col(col.as_ptr()),
+            ..Fused::default()
+        })
+        .collect();
+
+    epilogues.push(Fused {
+        scale: Some(scale.as_ptr()),
+        bias: Bias::Row(row.as_ptr()),
+        activation: Activation::Relu,
+        clamp: Some((min, max)),
+    });
+
+    epilogues.push(Fused {
+        scale: Some(scale.as_ptr()),
+        ..Fused::default()
+    });
+
+    epilogues
+}
+
+macro_rules! test_epilogue {
+    ($name: ident, $t: ident, $gemm: path, $gemm_epilogue: path, $generic: path, $eps: expr) => {
+        #[test]
+        fn $name() {
+            let e = RayonExecutor;
+
+            for &layout in &[Layout::ColMajor, Layout::RowMajor] {
+                for &(transa, transb) in
+                    &[(false, false), (false, true), (true, false), (true, true)]
+                {
+                    for &(m, n, k) in &SHAPES {
+                        let ld = m.max(n).max(k) + 3;
+                        let cast =
+                            |x: Vec<f64>| -> Vec<$t> { x.into_iter().map(|x| x as $t).collect() };
+
+                        let a = cast(random_matrix(ld * ld));
+                        let b = cast(random_matrix(ld * ld));
+                        let c = cast(random_matrix(ld * ld));
+                        let (alpha, beta) = (0.75, 0.5);
+
+                        let scale = cast(random_matrix(n));
+                        let row = cast(random_matrix(m));
+                        let col = cast(random_matrix(n));
+
+                        let mut expected = c.clone();
+                        unsafe {
+                            $gemm(
+                                &e,
+                                layout,
+                                transa,
+                                transb,
+                                m,
+                                n,
+                                k,
+                                alpha,
+                                a.as_ptr(),
+                                ld,
+                                b.as_ptr(),
+                                ld,
+                                beta,
+                                expected.as_mut_ptr(),
+                                ld,
+                            );
+                        }
+
+                        for epilogue in epilogues(&scale, &row, &col, -0.25, 0.5) {
+                            let mut reference = expected.clone();
+                            for i in 0..m {
+                                for j in 0..n {
+                                    let x = &mut reference[index(layout, ld, i, j)];
+                                    *x = unsafe { epilogue.apply(i, j, *x) };
+                                }
+                            }
+
+                            let mut fused = c.clone();
+                            let mut generic = c.clone();
+                            unsafe {
+                                $gemm_epilogue(
+                                    &e,
+                                    layout,
+                                    transa,
+                                    transb,
+                                    m,
+                                    n,
+                                    k,
+                                    alpha,
+                                    a.as_ptr(),
+                                    ld,
+                                    b.as_ptr(),
+                                    ld,
+                                    beta,
+                                    fused.as_mut_ptr(),
+                                    ld,
+                                    epilogue,
+                                );
+
+                                $generic(
+                                    &DefaultExecutor,
+                                    layout,
+                                    transa,
+                                    transb,
+                                    m,
+                                    n,
+                                    k,
+                                    alpha,
+                                    a.as_ptr(),
+                                    ld,
+                                    b.as_ptr(),
+                                    ld,
+                                    beta,
+                                    generic.as_mut_ptr(),
+                                    ld,
+                                    epilogue,
+                                );
+                            }
+
+                            for (idx, &x) in reference.iter().enumerate() {
+                                let tol = $eps * (1.0 + x.abs());
+
+                                assert!(
+                                    (fused[idx] - x).abs() <= tol,
+                                    "{:?} {:?} {:?} at {}: {} != {}",
+                                    layout,
+                                    (transa, transb),
+                                    (m, n, k),
+                                    idx,
+                                    fused[idx],
+                                    x
+                                );
+                                assert!(
+                                    (generic[idx] - x).abs() <= tol,
+                                    "{:?} {:?} {:?} at {}: {} != {}",
+                                    layout,
+                                    (transa, transb),
+                                    (m, n, k),
+                                    idx,
+                                    generic[idx],
+                                    x
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+}
+
+test_epilogue!(
+    test_sgemm_epilogue,
+    f32,
+    crate::sgemm,
+    crate::sgemm_epilogue,
+    crate::gemm::gemm_epilogue::<_, f32, GenericKernel, A16, A5, _>,
+    1e-4
+);
+
+test_epilogue!(
+    test_dgemm_epilogue,
+    f64,
+    crate::dgemm,
+    crate::dgemm_epilogue,
+    crate::gemm::gemm_epilogue::<_, f64, GenericKernel, A8, A4, _>,
+    1e-10
+);
+
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+test_epilogue!(
+    test_sgemm_epilogue_sse,
+    f32,
+    crate::sgemm,
+    crate::gemm::gemm_epilogue::<_, f32, SseKernel<f32, GenericKernel>, A16, A5, _>,
+    crate::gemm::gemm_epilogue::<_, f32, GenericKernel, A16, A5, _>,
+    1e-4
+);
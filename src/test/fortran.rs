@@ -0,0 +1,38 @@
+use crate::fortran::*;
+
+#[test]
+fn test_fortran_sgemm_and_sdot() {
+    let (m, n, k) = (3i32, 2i32, 2i32);
+
+    // column-major A (3x2) and B (2x2), B is used transposed
+    let a = [1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0];
+    let b = [1.0f32, 0.0, 1.0, 1.0];
+    let mut c = [0.0f32; 6];
+
+    unsafe {
+        sgemm_(
+            b"N".as_ptr() as *const _,
+            b"t".as_ptr() as *const _,
+            &m,
+            &n,
+            &k,
+            &1.0,
+            a.as_ptr(),
+            &m,
+            b.as_ptr(),
+            &k,
+            &0.0,
+            c.as_mut_ptr(),
+            &m,
+        );
+    }
+
+    // op(B) = [[1, 0], [1, 1]]
+    assert_eq!(c, [5.0, 7.0, 9.0, 4.0, 5.0, 6.0]);
+
+    let inc = 1;
+    assert_eq!(
+        unsafe { sdot_(&3, a.as_ptr(), &inc, c.as_ptr(), &inc) },
+        46.0
+    );
+}
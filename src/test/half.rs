@@ -0,0 +1,171 @@
+use super::random_matrix;
+use crate::executor::RayonExecutor;
+use crate::half::{self, Input};
+use crate::matrix::Storage;
+use crate::Layout;
+
+#[test]
+fn test_half_conversion() {
+    assert_eq!(half::f16_to_f32(0x3c00), 1.0);
+    assert_eq!(half::f16_to_f32(0xc000), -2.0);
+    assert_eq!(half::f16_to_f32(0x7bff), 65504.0);
+    assert_eq!(half::f16_to_f32(0x0001), 2f32.powi(-24));
+    assert_eq!(half::f16_to_f32(0x7c00), std::f32::INFINITY);
+    assert!(half::f16_to_f32(0x7e00).is_nan());
+
+    assert_eq!(half::f32_to_f16(1.0 + 2f32.powi(-11)), 0x3c00);
+    assert_eq!(half::f32_to_f16(1.0 + 3.0 * 2f32.powi(-11)), 0x3c02);
+    assert_eq!(half::f32_to_f16(65520.0), 0x7c00);
+    assert_eq!(half::f32_to_f16(2f32.powi(-25)), 0x0000);
+    assert_eq!(half::f32_to_f16(3.0 * 2f32.powi(-26)), 0x0001);
+
+    assert_eq!(half::bf16_to_f32(0x3f80), 1.0);
+    assert_eq!(half::f32_to_bf16(1.0 + 2f32.powi(-8)), 0x3f80);
+    assert_eq!(half::f32_to_bf16(1.0 + 3.0 * 2f32.powi(-8)), 0x3f82);
+
+    let bits: Vec<u16> = (0..=u16::MAX).collect();
+    let mut wide = vec![0.0f32; bits.len()];
+    unsafe { half::widen_slice(Storage::F16, bits.as_ptr(), wide.as_mut_ptr(), bits.len()) };
+
+    for (&h, &x) in bits.iter().zip(wide.iter()) {
+        let y = half::f16_to_f32(h);
+
+        if y.is_nan() {
+            assert!(x.is_nan());
+        } else {
+            assert_eq!(x.to_bits(), y.to_bits(), "{:#06x}", h);
+            assert_eq!(half::f32_to_f16(y), h);
+            assert_eq!(half::f32_to_bf16(half::bf16_to_f32(h)), h);
+        }
+    }
+}
+
+// Operand of the given storage and the f32 values it holds
+fn operand(storage: Storage, len: usize) -> (Vec<u16>, Vec<f32>) {
+    let x = random_matrix(len);
+
+    match storage {
+        Storage::F16 => {
+            let h: Vec<u16> = x.iter().map(|&x| half::f32_to_f16(x)).collect();
+            let x = h.iter().map(|&h| half::f16_to_f32(h)).collect();
+            (h, x)
+        }
+        Storage::Bf16 => {
+            let h: Vec<u16> = x.iter().map(|&x| half::f32_to_bf16(x)).collect();
+            let x = h.iter().map(|&h| half::bf16_to_f32(h)).collect();
+            (h, x)
+        }
+        Storage::Native => (Vec::new(), x),
+    }
+}
+
+fn input(storage: Storage, h: &[u16], x: &[f32]) -> Input {
+    match storage {
+        Storage::F16 => Input::F16(h.as_ptr()),
+        Storage::Bf16 => Input::Bf16(h.as_ptr()),
+        Storage::Native => Input::F32(x.as_ptr()),
+    }
+}
+
+#[test]
+fn test_sgemm_mixed() {
+    let (m, n, k) = (37, 23, 150);
+
+    for &(sa, sb) in &[
+        (Storage::F16, Storage::Native),
+        (Storage::Native, Storage::Bf16),
+        (Storage::F16, Storage::Bf16),
+        (Storage::Bf16, Storage::F16),
+    ] {
+        for &layout in &[Layout::ColMajor, Layout::RowMajor] {
+            for &(transa, transb) in &[(false, false), (false, true), (true, false), (true, true)] {
+                let (ha, xa) = operand(sa, m * k);
+                let (hb, xb) = operand(sb, k * n);
+
+                let lda = match (layout, transa) {
+                    (Layout::ColMajor, false) | (Layout::RowMajor, true) => m,
+                    _ => k,
+                };
+                let ldb = match (layout, transb) {
+                    (Layout::ColMajor, false) | (Layout::RowMajor, true) => k,
+                    _ => n,
+                };
+                let ldc = match layout {
+                    Layout::ColMajor => m,
+                    Layout::RowMajor => n,
+                };
+
+                let mut c = random_matrix(m * n);
+                let mut cref = c.clone();
+
+                unsafe {
+                    crate::sgemm(
+                        &RayonExecutor,
+                        layout,
+                        transa,
+                        transb,
+                        m,
+                        n,
+                        k,
+                        0.5,
+                        xa.as_ptr(),
+                        lda,
+                        xb.as_ptr(),
+                        ldb,
+                        2.0,
+                        cref.as_mut_ptr(),
+                        ldc,
+                    );
+
+                    crate::sgemm_mixed(
+                        &RayonExecutor,
+                        layout,
+                        transa,
+                        transb,
+                        m,
+                        n,
+                        k,
+                        0.5,
+                        input(sa, &ha, &xa),
+                        lda,
+                        input(sb, &hb, &xb),
+                        ldb,
+                        2.0,
+                        c.as_mut_ptr(),
+                        ldc,
+                    );
+                }
+
+                for (index, (a, b)) in c.iter().zip(cref.iter()).enumerate() {
+                    assert!(
+                        (a - b).abs() <= 1e-5 * (1.0 + b.abs()),
+                        "{:?} {:?} {:?} {} {}: c[{}]={}, cref[{}]={}",
+                        sa,
+                        sb,
+                        layout,
+                        transa,
+                        transb,
+                        index,
+                        a,
+                        index,
+                        b
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "F16 elements have no native pointer")]
+fn test_half_matrix_has_no_f32_pointer() {
+    use crate::matrix::{HalfMatrix, Matrix};
+
+    let bits = [0x3c00u16; 4];
+    let a = HalfMatrix::new(bits.as_ptr(), Storage::F16, 2, 1);
+
+    unsafe {
+        assert_eq!(a.get(1, 1), 1.0);
+        a.index(1, 1);
+    }
+}
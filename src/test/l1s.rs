@@ -0,0 +1,42 @@
+use super::random_matrix;
+use crate::kernel::generic;
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() <= 1e-4 * (1.0 + b.abs())
+}
+
+#[test]
+fn test_l1s_matches_generic() {
+    for &(n, inc) in &[(0, 1), (7, 1), (131, 1), (50, 3)] {
+        let x = random_matrix::<f32>(n * inc);
+        let y = random_matrix::<f32>(n * inc);
+
+        unsafe {
+            assert!(close(
+                crate::sdot(n, x.as_ptr(), inc, y.as_ptr(), inc),
+                generic::l1s::sdot(n, x.as_ptr(), inc, y.as_ptr(), inc)
+            ));
+            assert!(close(
+                crate::snrm2(n, x.as_ptr(), inc),
+                generic::l1s::snrm2(n, x.as_ptr(), inc)
+            ));
+            assert!(close(
+                crate::sasum(n, x.as_ptr(), inc),
+                generic::l1s::sasum(n, x.as_ptr(), inc)
+            ));
+
+            let mut y0 = y.clone();
+            let mut y1 = y.clone();
+            crate::saxpy(n, 1.5, x.as_ptr(), inc, y0.as_mut_ptr(), inc);
+            generic::l1s::saxpy(n, 1.5, x.as_ptr(), inc, y1.as_mut_ptr(), inc);
+            assert!(y0.iter().zip(y1.iter()).all(|(&a, &b)| close(a, b)));
+
+            let (mut x0, mut y0) = (x.clone(), y.clone());
+            let (mut x1, mut y1) = (x.clone(), y.clone());
+            crate::srot(n, x0.as_mut_ptr(), inc, y0.as_mut_ptr(), inc, 0.6, 0.8);
+            generic::l1s::srot(n, x1.as_mut_ptr(), inc, y1.as_mut_ptr(), inc, 0.6, 0.8);
+            assert!(x0.iter().zip(x1.iter()).all(|(&a, &b)| close(a, b)));
+            assert!(y0.iter().zip(y1.iter()).all(|(&a, &b)| close(a, b)));
+        }
+    }
+}
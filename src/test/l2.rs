@@ -0,0 +1,478 @@
+use super::{index, random_matrix};
+use crate::executor::{DefaultExecutor, Executor, RayonExecutor, ThreadPoolExecutor};
+use crate::gemm::{Diag, Layout, Uplo};
+
+const SHAPES: [(usize, usize); 6] = [(0, 5), (7, 0), (1, 1), (37, 13), (131, 70), (600, 300)];
+
+macro_rules! test_gemv {
+    ($name: ident, $t: ident, $gemv: path, $eps: expr) => {
+        fn $name<E: Executor>(e: &E) {
+            for &layout in &[Layout::ColMajor, Layout::RowMajor] {
+                for &trans in &[false, true] {
+                    for &(m, n) in &SHAPES {
+                        for &(incx, incy) in &[(1, 1), (2, 3)] {
+                            for &beta in &[0.0, 0.5] {
+                                let lda = match layout {
+                                    Layout::ColMajor => m + 3,
+                                    Layout::RowMajor => n + 3,
+                                };
+                                let (xlen, ylen) = if trans { (m, n) } else { (n, m) };
+
+                                let cast = |x: Vec<f64>| -> Vec<$t> {
+                                    x.into_iter().map(|x| x as $t).collect()
+                                };
+                                let a = cast(random_matrix(lda * (m + n)));
+                                let x = cast(random_matrix(xlen * incx));
+                                let mut y = cast(random_matrix(ylen * incy));
+
+                                // beta = 0 overwrites y without reading it
+                                if beta == 0.0 {
+                                    y.iter_mut().for_each(|y| *y = <$t>::NAN);
+                                }
+
+                                let alpha = 1.5;
+                                let mut expected = y.clone();
+                                for r in 0..ylen {
+                                    let mut acc = 0.0;
+                                    for c in 0..xlen {
+                                        let (i, j) = if trans { (c, r) } else { (r, c) };
+                                        acc += a[index(layout, lda, i, j)] * x[c * incx];
+                                    }
+
+                                    let yr = &mut expected[r * incy];
+                                    *yr = if beta == 0.0 {
+                                        alpha * acc
+                                    } else {
+                                        alpha * acc + beta * *yr
+                                    };
+                                }
+
+                                unsafe {
+                                    $gemv(
+                                        e,
+                                        layout,
+                                        trans,
+                                        m,
+                                        n,
+                                        alpha,
+                                        a.as_ptr(),
+                                        lda,
+                                        x.as_ptr(),
+                                        incx,
+                                        beta,
+                                        y.as_mut_ptr(),
+                                        incy,
+                                    );
+                                }
+
+                                for (idx, pair) in y.iter().zip(&expected).enumerate() {
+                                    let (&y, &expected) = pair;
+
+                                    // elements between the strides of y stay untouched
+                                    if idx % incy != 0 && beta == 0.0 {
+                                        assert!(y.is_nan());
+                                        continue;
+                                    }
+
+                                    assert!(
+                                        (y - expected).abs() <= $eps * (1.0 + expected.abs()),
+                                        "{:?} {} {:?} {:?} at {}: {} != {}",
+                                        layout,
+                                        trans,
+                                        (m, n),
+                                        (incx, incy),
+                                        idx,
+                                        y,
+                                        expected
+                                    );
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+}
+
+test_gemv!(check_sgemv, f32, crate::sgemv, 1e-4);
+test_gemv!(check_dgemv, f64, crate::dgemv, 1e-10);
+
+#[test]
+fn test_sgemv() {
+    check_sgemv(&DefaultExecutor);
+    check_sgemv(&RayonExecutor);
+    check_sgemv(&ThreadPoolExecutor::new());
+}
+
+#[test]
+fn test_dgemv() {
+    check_dgemv(&DefaultExecutor);
+    check_dgemv(&RayonExecutor);
+    check_dgemv(&ThreadPoolExecutor::new());
+}
+
+#[test]
+fn test_gemv_matches_generic() {
+    use crate::kernel::generic;
+
+    let (m, n) = (45, 29);
+    let a = random_matrix(m * n);
+    let x = random_matrix(m.max(n));
+    let y = random_matrix(m.max(n));
+
+    unsafe {
+        let mut y0 = y.clone();
+        let mut y1 = y.clone();
+        crate::dgemv(
+            &DefaultExecutor,
+            Layout::ColMajor,
+            false,
+            m,
+            n,
+            0.5,
+            a.as_ptr(),
+            m,
+            x.as_ptr(),
+            1,
+            2.0,
+            y0.as_mut_ptr(),
+            1,
+        );
+        generic::l2d::dgemv_n(
+            m,
+            n,
+            0.5,
+            a.as_ptr(),
+            m,
+            x.as_ptr(),
+            1,
+            2.0,
+            y1.as_mut_ptr(),
+        );
+        assert!(y0.iter().zip(&y1).all(|(&a, &b)| (a - b).abs() <= 1e-12));
+
+        let mut y0 = y.clone();
+        let mut y1 = y.clone();
+        crate::dgemv(
+            &DefaultExecutor,
+            Layout::ColMajor,
+            true,
+            m,
+            n,
+            0.5,
+            a.as_ptr(),
+            m,
+            x.as_ptr(),
+            1,
+            2.0,
+            y0.as_mut_ptr(),
+            1,
+        );
+        generic::l2d::dgemv_t(
+            m,
+            n,
+            0.5,
+            a.as_ptr(),
+            m,
+            x.as_ptr(),
+            2.0,
+            y1.as_mut_ptr(),
+            1,
+        );
+        assert!(y0.iter().zip(&y1).all(|(&a, &b)| (a - b).abs() <= 1e-12));
+    }
+}
+
+// Dense n x n matrix of the triangle `uplo` of `a`, the other triangle is
+// taken from the symmetric counterpart or left zero
+fn triangle(a: &[f64], n: usize, uplo: Uplo, symmetric: bool, unit: bool) -> Vec<f64> {
+    let mut dense = vec![0.0; n * n];
+
+    for i in 0..n {
+        for j in 0..n {
+            let stored = match uplo {
+                Uplo::Upper => i <= j,
+                Uplo::Lower => i >= j,
+            };
+
+            dense[i * n + j] = if i == j && unit {
+                1.0
+            } else if stored {
+                a[i * n + j]
+            } else if symmetric {
+                a[j * n + i]
+            } else {
+                0.0
+            };
+        }
+    }
+
+    dense
+}
+
+macro_rules! test_l2 {
+    (
+        $name: ident,
+        $t: ident,
+        $ger: path,
+        $syr: path,
+        $syr2: path,
+        $symv: path,
+        $trmv: path,
+        $trsv: path,
+        $eps: expr
+    ) => {
+        #[test]
+        fn $name() {
+            let cast = |x: &[f64]| -> Vec<$t> { x.iter().map(|&x| x as $t).collect() };
+            let close = |a: &[$t], b: &[f64]| {
+                a.iter()
+                    .zip(b)
+                    .all(|(&a, &b)| (a as f64 - b).abs() <= $eps * (1.0 + b.abs()))
+            };
+
+            for &layout in &[Layout::ColMajor, Layout::RowMajor] {
+                for &(n, inc) in &[(0, 1), (1, 1), (9, 2), (70, 1), (45, 3)] {
+                    let lda = n + 2;
+
+                    // stored with lda, read back as a dense row-wise n x n
+                    let store = |dense: &[f64]| {
+                        let mut a = vec![-7.0; lda * n];
+                        for i in 0..n {
+                            for j in 0..n {
+                                a[index(layout, lda, i, j)] = dense[i * n + j];
+                            }
+                        }
+                        a
+                    };
+                    let load = |a: &[$t]| {
+                        let mut dense = vec![0.0; n * n];
+                        for i in 0..n {
+                            for j in 0..n {
+                                dense[i * n + j] = a[index(layout, lda, i, j)] as f64;
+                            }
+                        }
+                        dense
+                    };
+                    let strided = |v: &[f64]| {
+                        let mut x = vec![0.0; n * inc];
+                        for (i, &v) in v.iter().enumerate() {
+                            x[i * inc] = v;
+                        }
+                        cast(&x)
+                    };
+                    let dense_of =
+                        |x: &[$t]| -> Vec<f64> { (0..n).map(|i| x[i * inc] as f64).collect() };
+
+                    // well conditioned for the solves
+                    let mut dense: Vec<f64> =
+                        random_matrix(n * n).iter().map(|x| x / n as f64).collect();
+                    for i in 0..n {
+                        dense[i * n + i] = 1.0 + random_matrix::<f64>(1)[0].abs();
+                    }
+
+                    let a = cast(&store(&dense));
+                    let x = random_matrix(n);
+                    let y = random_matrix(n);
+                    let (alpha, beta) = (0.75, 0.5);
+
+                    let product = |m: &[f64], v: &[f64]| -> Vec<f64> {
+                        (0..n)
+                            .map(|i| (0..n).map(|j| m[i * n + j] * v[j]).sum())
+                            .collect()
+                    };
+
+                    unsafe {
+                        let mut a_ger = a.clone();
+                        $ger(
+                            layout,
+                            n,
+                            n,
+                            alpha as $t,
+                            strided(&x).as_ptr(),
+                            inc,
+                            strided(&y).as_ptr(),
+                            inc,
+                            a_ger.as_mut_ptr(),
+                            lda,
+                        );
+                        let expected: Vec<f64> = (0..n * n)
+                            .map(|ij| dense[ij] + alpha * x[ij / n] * y[ij % n])
+                            .collect();
+                        assert!(
+                            close(&cast(&load(&a_ger)), &expected),
+                            "ger {:?} {}",
+                            layout,
+                            n
+                        );
+
+                        for &uplo in &[Uplo::Upper, Uplo::Lower] {
+                            let mut a_syr = a.clone();
+                            $syr(
+                                layout,
+                                uplo,
+                                n,
+                                alpha as $t,
+                                strided(&x).as_ptr(),
+                                inc,
+                                a_syr.as_mut_ptr(),
+                                lda,
+                            );
+                            let expected: Vec<f64> = (0..n * n)
+                                .map(|ij| dense[ij] + alpha * x[ij / n] * x[ij % n])
+                                .collect();
+                            assert!(
+                                close(
+                                    &cast(&triangle(&load(&a_syr), n, uplo, true, false)),
+                                    &triangle(&expected, n, uplo, true, false)
+                                ),
+                                "syr {:?} {:?} {}",
+                                layout,
+                                uplo,
+                                n
+                            );
+
+                            let mut a_syr2 = a.clone();
+                            $syr2(
+                                layout,
+                                uplo,
+                                n,
+                                alpha as $t,
+                                strided(&x).as_ptr(),
+                                inc,
+                                strided(&y).as_ptr(),
+                                inc,
+                                a_syr2.as_mut_ptr(),
+                                lda,
+                            );
+                            let expected: Vec<f64> = (0..n * n)
+                                .map(|ij| {
+                                    let (i, j) = (ij / n, ij % n);
+                                    dense[ij] + alpha * (x[i] * y[j] + y[i] * x[j])
+                                })
+                                .collect();
+                            assert!(
+                                close(
+                                    &cast(&triangle(&load(&a_syr2), n, uplo, true, false)),
+                                    &triangle(&expected, n, uplo, true, false)
+                                ),
+                                "syr2 {:?} {:?} {}",
+                                layout,
+                                uplo,
+                                n
+                            );
+
+                            let mut y_symv = strided(&y);
+                            $symv(
+                                layout,
+                                uplo,
+                                n,
+                                alpha as $t,
+                                a.as_ptr(),
+                                lda,
+                                strided(&x).as_ptr(),
+                                inc,
+                                beta as $t,
+                                y_symv.as_mut_ptr(),
+                                inc,
+                            );
+                            let expected: Vec<f64> =
+                                product(&triangle(&dense, n, uplo, true, false), &x)
+                                    .iter()
+                                    .zip(&y)
+                                    .map(|(ax, y)| alpha * ax + beta * y)
+                                    .collect();
+                            assert!(
+                                close(&cast(&dense_of(&y_symv)), &expected),
+                                "symv {:?} {:?} {}",
+                                layout,
+                                uplo,
+                                n
+                            );
+
+                            for &trans in &[false, true] {
+                                for &diag in &[Diag::NonUnit, Diag::Unit] {
+                                    let mut t =
+                                        triangle(&dense, n, uplo, false, diag == Diag::Unit);
+                                    if trans {
+                                        t = (0..n * n).map(|ij| t[(ij % n) * n + ij / n]).collect();
+                                    }
+
+                                    let mut x_trmv = strided(&x);
+                                    $trmv(
+                                        layout,
+                                        uplo,
+                                        trans,
+                                        diag,
+                                        n,
+                                        a.as_ptr(),
+                                        lda,
+                                        x_trmv.as_mut_ptr(),
+                                        inc,
+                                    );
+                                    assert!(
+                                        close(&cast(&dense_of(&x_trmv)), &product(&t, &x)),
+                                        "trmv {:?} {:?} {} {:?} {}",
+                                        layout,
+                                        uplo,
+                                        trans,
+                                        diag,
+                                        n
+                                    );
+
+                                    let b = product(&t, &x);
+                                    let mut x_trsv = strided(&b);
+                                    $trsv(
+                                        layout,
+                                        uplo,
+                                        trans,
+                                        diag,
+                                        n,
+                                        a.as_ptr(),
+                                        lda,
+                                        x_trsv.as_mut_ptr(),
+                                        inc,
+                                    );
+                                    assert!(
+                                        close(&cast(&dense_of(&x_trsv)), &x),
+                                        "trsv {:?} {:?} {} {:?} {}",
+                                        layout,
+                                        uplo,
+                                        trans,
+                                        diag,
+                                        n
+                                    );
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+}
+
+test_l2!(
+    test_l2s,
+    f32,
+    crate::sger,
+    crate::ssyr,
+    crate::ssyr2,
+    crate::ssymv,
+    crate::strmv,
+    crate::strsv,
+    1e-4
+);
+
+test_l2!(
+    test_l2d,
+    f64,
+    crate::dger,
+    crate::dsyr,
+    crate::dsyr2,
+    crate::dsymv,
+    crate::dtrmv,
+    crate::dtrsv,
+    1e-10
+);
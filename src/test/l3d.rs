@@ -0,0 +1,163 @@
+use super::random_matrix;
+use crate::dim::*;
+use crate::executor::DefaultExecutor;
+use crate::kernel::generic::GenericKernel;
+
+type GemmFn = unsafe fn(
+    &DefaultExecutor,
+    crate::Layout,
+    bool,
+    bool,
+    usize,
+    usize,
+    usize,
+    f64,
+    *const f64,
+    usize,
+    *const f64,
+    usize,
+    f64,
+    *mut f64,
+    usize,
+);
+
+fn check_dgemm(
+    gemm: GemmFn,
+    transa: bool,
+    transb: bool,
+    (m, n, k): (usize, usize, usize),
+    alpha: f64,
+    beta: f64,
+) {
+    let a = random_matrix(m * k);
+    let b = random_matrix(n * k);
+
+    let lda = if transa { k } else { m };
+    let ldb = if transb { n } else { k };
+
+    let mut c = random_matrix(m * n);
+    if beta == 0.0 {
+        c.iter_mut().for_each(|x| *x = std::f64::NAN);
+    }
+    let mut cref = c.clone();
+
+    unsafe {
+        blas::dgemm(
+            if transa { b'T' } else { b'N' },
+            if transb { b'T' } else { b'N' },
+            m as i32,
+            n as i32,
+            k as i32,
+            alpha,
+            a.as_slice(),
+            lda as i32,
+            b.as_slice(),
+            ldb as i32,
+            beta,
+            cref.as_mut_slice(),
+            m as i32,
+        )
+    }
+
+    unsafe {
+        gemm(
+            &DefaultExecutor,
+            crate::Layout::ColMajor,
+            transa,
+            transb,
+            m,
+            n,
+            k,
+            alpha,
+            a.as_ptr(),
+            lda,
+            b.as_ptr(),
+            ldb,
+            beta,
+            c.as_mut_ptr(),
+            m,
+        );
+    }
+
+    for (index, (a, b)) in c.iter().zip(cref.iter()).enumerate() {
+        assert!(
+            (a - b).abs() <= 1e-10 * (1.0 + b.abs()),
+            "a != b, c[{}]={}, cref[{}]={}",
+            index,
+            a,
+            index,
+            b
+        );
+    }
+}
+
+#[test]
+fn test_dgemm_nn() {
+    check_dgemm(crate::dgemm, false, false, (19, 10, 7), 1.0, 0.0);
+    check_dgemm(crate::dgemm, false, false, (19, 10, 600), 0.5, 2.0);
+}
+
+#[test]
+fn test_dgemm_nt() {
+    check_dgemm(crate::dgemm, false, true, (19, 10, 7), 1.0, 0.0);
+    check_dgemm(crate::dgemm, false, true, (19, 10, 600), 0.5, 2.0);
+}
+
+#[test]
+fn test_dgemm_tn() {
+    check_dgemm(crate::dgemm, true, false, (19, 10, 7), 1.0, 0.0);
+    check_dgemm(crate::dgemm, true, false, (19, 10, 600), 0.5, 2.0);
+}
+
+#[test]
+fn test_dgemm_tt() {
+    check_dgemm(crate::dgemm, true, true, (19, 10, 7), 1.0, 0.0);
+    check_dgemm(crate::dgemm, true, true, (19, 10, 600), 0.5, 2.0);
+}
+
+#[test]
+fn test_dgemm_generic_kernel() {
+    let gemm = crate::gemm::gemm::<DefaultExecutor, f64, GenericKernel, A8, A4>;
+
+    for &(transa, transb) in &[(false, false), (false, true), (true, false), (true, true)] {
+        check_dgemm(gemm, transa, transb, (19, 10, 7), 1.0, 0.0);
+        check_dgemm(gemm, transa, transb, (19, 10, 600), 0.5, 2.0);
+    }
+}
+
+#[test]
+fn test_dgemm_blocking() {
+    use crate::{Blocking, GemmError};
+
+    let default = crate::dgemm_blocking();
+
+    for &(mc, kc, nc) in &[
+        (0, 8, 8),
+        (12, 8, 8),
+        (16, 6, 8),
+        (16, 8, 10),
+        (8, usize::MAX, 4),
+    ] {
+        assert_eq!(
+            crate::dgemm_set_blocking(Blocking { mc, kc, nc }),
+            Err(GemmError::InvalidBlocking { mc, kc, nc })
+        );
+    }
+    assert_eq!(crate::dgemm_blocking(), default);
+
+    // blocks much smaller than the matrices
+    let small = Blocking {
+        mc: 16,
+        kc: 8,
+        nc: 12,
+    };
+    crate::dgemm_set_blocking(small).unwrap();
+    assert_eq!(crate::dgemm_blocking(), small);
+
+    for &(transa, transb) in &[(false, false), (false, true), (true, false), (true, true)] {
+        check_dgemm(crate::dgemm, transa, transb, (35, 29, 61), 0.5, 2.0);
+        check_dgemm(crate::dgemm, transa, transb, (35, 29, 61), 1.0, 0.0);
+    }
+
+    crate::dgemm_set_blocking(default).unwrap();
+}
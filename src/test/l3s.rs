@@ -1,3 +1,26 @@
+use super::random_matrix;
+use crate::dim::*;
+use crate::executor::DefaultExecutor;
+use crate::kernel::generic::GenericKernel;
+
+type GemmFn = unsafe fn(
+    &DefaultExecutor,
+    crate::Layout,
+    bool,
+    bool,
+    usize,
+    usize,
+    usize,
+    f32,
+    *const f32,
+    usize,
+    *const f32,
+    usize,
+    f32,
+    *mut f32,
+    usize,
+);
+
 const M_LEN: usize = 17;
 const N_LEN: usize = 6;
 const K_LEN: usize = 4;
@@ -112,7 +135,7 @@ fn test_sgemm_nn() {
     unsafe {
         crate::sgemm(
             &crate::executor::DefaultExecutor,
-            false,
+            crate::Layout::ColMajor,
             false,
             false,
             m,
@@ -198,9 +221,9 @@ fn test_sgemm_nt() {
     unsafe {
         crate::sgemm(
             &crate::executor::DefaultExecutor,
+            crate::Layout::ColMajor,
             false,
             true,
-            false,
             m,
             n,
             k,
@@ -262,9 +285,9 @@ fn test_sgemm_tn() {
     unsafe {
         crate::sgemm(
             &crate::executor::DefaultExecutor,
+            crate::Layout::ColMajor,
             true,
             false,
-            false,
             m,
             n,
             k,
@@ -326,9 +349,9 @@ fn test_sgemm_tt() {
     unsafe {
         crate::sgemm(
             &crate::executor::DefaultExecutor,
+            crate::Layout::ColMajor,
             true,
             true,
-            false,
             m,
             n,
             k,
@@ -370,3 +393,645 @@ fn feq(a: f32, b: f32) -> bool {
         (a - b).abs() / (a.abs() + b.abs()) < std::f32::EPSILON * 10.0
     }
 }
+
+fn check_sgemm_alpha_beta(
+    gemm: GemmFn,
+    transa: bool,
+    transb: bool,
+    (m, n, k): (usize, usize, usize),
+    alpha: f32,
+    beta: f32,
+    c_init: f32,
+) {
+    let a = random_matrix(m * k);
+    let b = random_matrix(n * k);
+
+    let (lda, ldb) = (if transa { k } else { m }, if transb { n } else { k });
+
+    let mut c = random_matrix(m * n);
+    if beta == 0.0 {
+        c.iter_mut().for_each(|x| *x = c_init);
+    }
+    let mut cref = c.clone();
+
+    unsafe {
+        blas::sgemm(
+            if transa { b'T' } else { b'N' },
+            if transb { b'T' } else { b'N' },
+            m as i32,
+            n as i32,
+            k as i32,
+            alpha,
+            a.as_slice(),
+            lda as i32,
+            b.as_slice(),
+            ldb as i32,
+            beta,
+            cref.as_mut_slice(),
+            m as i32,
+        )
+    }
+
+    unsafe {
+        gemm(
+            &DefaultExecutor,
+            crate::Layout::ColMajor,
+            transa,
+            transb,
+            m,
+            n,
+            k,
+            alpha,
+            a.as_ptr(),
+            lda,
+            b.as_ptr(),
+            ldb,
+            beta,
+            c.as_mut_ptr(),
+            m,
+        );
+    }
+
+    for (index, (a, b)) in c.iter().zip(cref.iter()).enumerate() {
+        assert!(
+            (a - b).abs() <= 1e-4 * (1.0 + b.abs()),
+            "a != b, c[{}]={}, cref[{}]={}",
+            index,
+            a,
+            index,
+            b
+        );
+    }
+}
+
+#[test]
+fn test_sgemm_alpha_beta() {
+    for &(transa, transb) in &[(false, false), (false, true), (true, false), (true, true)] {
+        check_sgemm_alpha_beta(crate::sgemm, transa, transb, (35, 13, 300), 0.5, 2.0, 0.0);
+        check_sgemm_alpha_beta(crate::sgemm, transa, transb, (35, 13, 300), -1.5, 1.0, 0.0);
+        check_sgemm_alpha_beta(crate::sgemm, transa, transb, (35, 13, 300), 2.0, -0.5, 0.0);
+    }
+}
+
+#[test]
+fn test_sgemm_beta_zero_ignores_c() {
+    for &(transa, transb) in &[(false, false), (false, true), (true, false), (true, true)] {
+        check_sgemm_alpha_beta(
+            crate::sgemm,
+            transa,
+            transb,
+            (35, 13, 300),
+            1.5,
+            0.0,
+            std::f32::NAN,
+        );
+        check_sgemm_alpha_beta(
+            crate::sgemm,
+            transa,
+            transb,
+            (35, 13, 0),
+            1.5,
+            0.0,
+            std::f32::NAN,
+        );
+    }
+}
+
+#[test]
+fn test_sgemm_generic_kernel() {
+    let gemm = crate::gemm::gemm::<DefaultExecutor, f32, GenericKernel, A16, A5>;
+
+    for &(transa, transb) in &[(false, false), (false, true), (true, false), (true, true)] {
+        check_sgemm_alpha_beta(gemm, transa, transb, (35, 13, 300), 0.5, 2.0, 0.0);
+        check_sgemm_alpha_beta(gemm, transa, transb, (35, 13, 300), 1.5, 0.0, std::f32::NAN);
+    }
+}
+
+#[test]
+fn test_sgemm_sse_kernel() {
+    use crate::kernel::sse::SseKernel;
+
+    let gemm = crate::gemm::gemm::<DefaultExecutor, f32, SseKernel<f32, GenericKernel>, A16, A5>;
+
+    for &(transa, transb) in &[(false, false), (false, true), (true, false), (true, true)] {
+        check_sgemm_alpha_beta(gemm, transa, transb, (35, 13, 300), 0.5, 2.0, 0.0);
+        check_sgemm_alpha_beta(gemm, transa, transb, (35, 13, 300), 1.5, 0.0, std::f32::NAN);
+    }
+}
+
+#[test]
+fn test_sgemm_sse_kernel_without_fma() {
+    use crate::kernel::sse::SseKernel;
+
+    let gemm = crate::gemm::gemm::<DefaultExecutor, f32, SseKernel<f32, GenericKernel>, A16, A5>;
+
+    // -1 * 1 + x * x with x = 1 + 2^-13 is 2^-12 when x * x is rounded before
+    // the add, a fused multiply-add keeps its 2^-26 as well
+    let x = 1.0 + 2f32.powi(-13);
+    let (m, n, k) = (35, 13, 2);
+
+    for &(transa, transb) in &[(false, false), (false, true), (true, false), (true, true)] {
+        let a: Vec<f32> = (0..m * k)
+            .map(|i| {
+                let p = if transa { i % k } else { i / m };
+                if p == 0 {
+                    -1.0
+                } else {
+                    x
+                }
+            })
+            .collect();
+        let b: Vec<f32> = (0..k * n)
+            .map(|i| {
+                let p = if transb { i / n } else { i % k };
+                if p == 0 {
+                    1.0
+                } else {
+                    x
+                }
+            })
+            .collect();
+        let mut c = vec![std::f32::NAN; m * n];
+
+        unsafe {
+            gemm(
+                &DefaultExecutor,
+                crate::Layout::ColMajor,
+                transa,
+                transb,
+                m,
+                n,
+                k,
+                1.0,
+                a.as_ptr(),
+                if transa { k } else { m },
+                b.as_ptr(),
+                if transb { n } else { k },
+                0.0,
+                c.as_mut_ptr(),
+                m,
+            );
+        }
+
+        for (index, &c) in c.iter().enumerate() {
+            assert_eq!(
+                c,
+                2f32.powi(-12),
+                "c[{}] with {:?}",
+                index,
+                (transa, transb)
+            );
+        }
+    }
+}
+
+#[test]
+fn test_sgemm_row_major() {
+    let (m, n, k) = (35, 13, 300);
+
+    let a = random_matrix(m * k);
+    let b = random_matrix(n * k);
+    let mut c = random_matrix(m * n);
+
+    // row-major C is column-major C^T, which is B^T * A^T
+    let mut cref = c.clone();
+    unsafe {
+        blas::sgemm(
+            b'T',
+            b'T',
+            n as i32,
+            m as i32,
+            k as i32,
+            0.5,
+            b.as_slice(),
+            k as i32,
+            a.as_slice(),
+            m as i32,
+            2.0,
+            cref.as_mut_slice(),
+            n as i32,
+        )
+    }
+
+    // A is k x m and B is n x k, both row-major and transposed
+    unsafe {
+        crate::sgemm(
+            &DefaultExecutor,
+            crate::Layout::RowMajor,
+            true,
+            true,
+            m,
+            n,
+            k,
+            0.5,
+            a.as_ptr(),
+            m,
+            b.as_ptr(),
+            k,
+            2.0,
+            c.as_mut_ptr(),
+            n,
+        );
+    }
+
+    for (index, (a, b)) in c.iter().zip(cref.iter()).enumerate() {
+        assert!(
+            (a - b).abs() <= 1e-4 * (1.0 + b.abs()),
+            "a != b, c[{}]={}, cref[{}]={}",
+            index,
+            a,
+            index,
+            b
+        );
+    }
+}
+
+type StridedGemmFn = unsafe fn(
+    &DefaultExecutor,
+    usize,
+    usize,
+    usize,
+    f32,
+    *const f32,
+    usize,
+    usize,
+    *const f32,
+    usize,
+    usize,
+    f32,
+    *mut f32,
+    usize,
+    usize,
+);
+
+fn check_sgemm_strided(
+    gemm: StridedGemmFn,
+    (m, n, k): (usize, usize, usize),
+    (rsa, csa): (usize, usize),
+    (rsb, csb): (usize, usize),
+    (rsc, csc): (usize, usize),
+) {
+    let a = random_matrix((m - 1) * rsa + (k - 1) * csa + 1);
+    let b = random_matrix((k - 1) * rsb + (n - 1) * csb + 1);
+    let mut c = random_matrix((m - 1) * rsc + (n - 1) * csc + 1);
+    let c_orig = c.clone();
+
+    unsafe {
+        gemm(
+            &DefaultExecutor,
+            m,
+            n,
+            k,
+            0.5,
+            a.as_ptr(),
+            rsa,
+            csa,
+            b.as_ptr(),
+            rsb,
+            csb,
+            2.0,
+            c.as_mut_ptr(),
+            rsc,
+            csc,
+        );
+    }
+
+    for i in 0..m {
+        for j in 0..n {
+            let ab: f32 = (0..k)
+                .map(|p| a[i * rsa + p * csa] * b[p * rsb + j * csb])
+                .sum();
+            let expected = 0.5 * ab + 2.0 * c_orig[i * rsc + j * csc];
+            let actual = c[i * rsc + j * csc];
+
+            assert!(
+                (actual - expected).abs() <= 1e-4 * (1.0 + expected.abs()),
+                "c[{}, {}]={}, expected {}",
+                i,
+                j,
+                actual,
+                expected
+            );
+        }
+    }
+}
+
+#[test]
+fn test_sgemm_strided() {
+    let (m, n, k) = (35, 13, 300);
+
+    let gemms: [StridedGemmFn; 3] = [
+        crate::sgemm_strided,
+        crate::gemm::gemm_strided::<DefaultExecutor, f32, GenericKernel, A16, A5>,
+        crate::gemm::gemm_strided::<
+            DefaultExecutor,
+            f32,
+            crate::kernel::sse::SseKernel<f32, GenericKernel>,
+            A16,
+            A5,
+        >,
+    ];
+
+    for &gemm in &gemms {
+        // column-major, row-major and every other column/row of a larger matrix
+        check_sgemm_strided(gemm, (m, n, k), (1, m), (1, k), (1, m));
+        check_sgemm_strided(gemm, (m, n, k), (k, 1), (n, 1), (n, 1));
+        check_sgemm_strided(gemm, (m, n, k), (2, 2 * m), (3, 3 * k), (2, 4 * m));
+        check_sgemm_strided(gemm, (m, n, k), (2 * k, 2), (3 * n, 3), (2 * n, 2));
+        check_sgemm_strided(gemm, (m, n, k), (1, 2 * m), (2 * n, 1), (1, 2 * m));
+    }
+}
+
+fn check_sgemm_batch_strided<E: crate::executor::Executor>(
+    e: &E,
+    layout: crate::Layout,
+    transa: bool,
+    transb: bool,
+    (m, n, k): (usize, usize, usize),
+    batch: usize,
+) {
+    let (lda, ldb, ldc) = match layout {
+        crate::Layout::ColMajor => (if transa { k } else { m }, if transb { n } else { k }, m),
+        crate::Layout::RowMajor => (if transa { m } else { k }, if transb { k } else { n }, n),
+    };
+
+    // leave a gap between consecutive matrices
+    let (stride_a, stride_b, stride_c) = (m * k + 3, n * k + 1, m * n + 5);
+
+    let a = random_matrix(stride_a * batch);
+    let b = random_matrix(stride_b * batch);
+    let mut c = random_matrix(stride_c * batch);
+    let mut cref = c.clone();
+
+    unsafe {
+        crate::sgemm_batch_strided(
+            e,
+            layout,
+            transa,
+            transb,
+            m,
+            n,
+            k,
+            0.5,
+            a.as_ptr(),
+            lda,
+            stride_a,
+            b.as_ptr(),
+            ldb,
+            stride_b,
+            2.0,
+            c.as_mut_ptr(),
+            ldc,
+            stride_c,
+            batch,
+        );
+
+        for idx in 0..batch {
+            crate::sgemm(
+                &DefaultExecutor,
+                layout,
+                transa,
+                transb,
+                m,
+                n,
+                k,
+                0.5,
+                a.as_ptr().add(idx * stride_a),
+                lda,
+                b.as_ptr().add(idx * stride_b),
+                ldb,
+                2.0,
+                cref.as_mut_ptr().add(idx * stride_c),
+                ldc,
+            );
+        }
+    }
+
+    for (index, (a, b)) in c.iter().zip(cref.iter()).enumerate() {
+        assert!(
+            (a - b).abs() <= 1e-4 * (1.0 + b.abs()),
+            "a != b, c[{}]={}, cref[{}]={}",
+            index,
+            a,
+            index,
+            b
+        );
+    }
+}
+
+#[test]
+fn test_sgemm_batch_strided() {
+    use crate::executor::RayonExecutor;
+    use crate::Layout::{ColMajor, RowMajor};
+
+    for &(transa, transb) in &[(false, false), (false, true), (true, false), (true, true)] {
+        check_sgemm_batch_strided(&DefaultExecutor, ColMajor, transa, transb, (19, 7, 33), 13);
+        check_sgemm_batch_strided(&DefaultExecutor, RowMajor, transa, transb, (19, 7, 33), 13);
+        check_sgemm_batch_strided(&RayonExecutor, ColMajor, transa, transb, (35, 13, 300), 1);
+        check_sgemm_batch_strided(&RayonExecutor, ColMajor, transa, transb, (8, 8, 8), 100);
+        check_sgemm_batch_strided(&RayonExecutor, RowMajor, transa, transb, (8, 8, 0), 100);
+    }
+}
+
+fn check_sgemm_grouped<E: crate::executor::Executor>(e: &E) {
+    use crate::Layout::{ColMajor, RowMajor};
+
+    let shapes = [
+        (ColMajor, false, false, (35, 13, 300), 0.5, 2.0),
+        (ColMajor, true, false, (1, 1, 1), 1.0, 0.0),
+        (RowMajor, false, true, (19, 7, 33), -1.5, 1.0),
+        (ColMajor, true, true, (8, 8, 0), 1.0, 0.5),
+        (RowMajor, true, false, (64, 3, 17), 2.0, -0.5),
+        (ColMajor, false, true, (0, 5, 5), 1.0, 1.0),
+        (RowMajor, false, false, (5, 16, 9), 1.0, 0.0),
+    ];
+
+    let mut data = Vec::new();
+    for &(layout, transa, transb, (m, n, k), _, _) in &shapes {
+        let (lda, ldb, ldc) = match layout {
+            ColMajor => (if transa { k } else { m }, if transb { n } else { k }, m),
+            RowMajor => (if transa { m } else { k }, if transb { k } else { n }, n),
+        };
+
+        let c = random_matrix(m * n);
+        data.push((
+            random_matrix(m * k),
+            random_matrix(n * k),
+            c.clone(),
+            c,
+            lda,
+            ldb,
+            ldc,
+        ));
+    }
+
+    let problems: Vec<_> = shapes
+        .iter()
+        .zip(data.iter_mut())
+        .map(
+            |(&(layout, transa, transb, (m, n, k), alpha, beta), (a, b, c, _, lda, ldb, ldc))| {
+                crate::GemmProblem {
+                    layout,
+                    transa,
+                    transb,
+                    m,
+                    n,
+                    k,
+                    alpha,
+                    a: a.as_ptr(),
+                    lda: *lda,
+                    b: b.as_ptr(),
+                    ldb: *ldb,
+                    beta,
+                    c: c.as_mut_ptr(),
+                    ldc: *ldc,
+                }
+            },
+        )
+        .collect();
+
+    unsafe {
+        crate::sgemm_grouped(e, &problems);
+    }
+
+    for (p, (a, b, c, cref, lda, ldb, ldc)) in problems.iter().zip(data.iter_mut()) {
+        unsafe {
+            crate::sgemm(
+                &DefaultExecutor,
+                p.layout,
+                p.transa,
+                p.transb,
+                p.m,
+                p.n,
+                p.k,
+                p.alpha,
+                a.as_ptr(),
+                *lda,
+                b.as_ptr(),
+                *ldb,
+                p.beta,
+                cref.as_mut_ptr(),
+                *ldc,
+            );
+        }
+
+        for (index, (a, b)) in c.iter().zip(cref.iter()).enumerate() {
+            assert!(
+                (a - b).abs() <= 1e-4 * (1.0 + b.abs()),
+                "a != b, c[{}]={}, cref[{}]={}",
+                index,
+                a,
+                index,
+                b
+            );
+        }
+    }
+}
+
+#[test]
+fn test_sgemm_grouped() {
+    check_sgemm_grouped(&DefaultExecutor);
+    check_sgemm_grouped(&crate::executor::RayonExecutor);
+}
+
+fn check_sgemm_packed(
+    layout: crate::Layout,
+    transa: bool,
+    transb: bool,
+    (m, n, k): (usize, usize, usize),
+) {
+    let (lda, ldb, ldc) = match layout {
+        crate::Layout::ColMajor => (if transa { k } else { m }, if transb { n } else { k }, m),
+        crate::Layout::RowMajor => (if transa { m } else { k }, if transb { k } else { n }, n),
+    };
+
+    let a = random_matrix(m * k);
+    let b = random_matrix(n * k);
+    let c = random_matrix(m * n);
+
+    let mut cref = c.clone();
+    unsafe {
+        crate::sgemm(
+            &DefaultExecutor,
+            layout,
+            transa,
+            transb,
+            m,
+            n,
+            k,
+            0.5,
+            a.as_ptr(),
+            lda,
+            b.as_ptr(),
+            ldb,
+            2.0,
+            cref.as_mut_ptr(),
+            ldc,
+        );
+    }
+
+    let e = &crate::executor::RayonExecutor;
+    let packed_a = unsafe { crate::sgemm_pack_a(e, layout, transa, m, k, a.as_ptr(), lda) };
+    let packed_b = unsafe { crate::sgemm_pack_b(e, layout, transb, k, n, b.as_ptr(), ldb) };
+
+    // the packed operands are reused
+    for _ in 0..2 {
+        let mut ca = c.clone();
+        let mut cb = c.clone();
+
+        unsafe {
+            crate::sgemm_packed_a(
+                e,
+                transb,
+                n,
+                0.5,
+                &packed_a,
+                b.as_ptr(),
+                ldb,
+                2.0,
+                ca.as_mut_ptr(),
+                ldc,
+            )
+            .unwrap();
+            crate::sgemm_packed_b(
+                e,
+                transa,
+                m,
+                0.5,
+                a.as_ptr(),
+                lda,
+                &packed_b,
+                2.0,
+                cb.as_mut_ptr(),
+                ldc,
+            )
+            .unwrap();
+        }
+
+        for c in &[ca, cb] {
+            for (index, (a, b)) in c.iter().zip(cref.iter()).enumerate() {
+                assert!(
+                    (a - b).abs() <= 1e-4 * (1.0 + b.abs()),
+                    "a != b, c[{}]={}, cref[{}]={}",
+                    index,
+                    a,
+                    index,
+                    b
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_sgemm_packed() {
+    use crate::Layout::{ColMajor, RowMajor};
+
+    for &(transa, transb) in &[(false, false), (false, true), (true, false), (true, true)] {
+        check_sgemm_packed(ColMajor, transa, transb, (35, 13, 300));
+        check_sgemm_packed(RowMajor, transa, transb, (35, 13, 300));
+        check_sgemm_packed(ColMajor, transa, transb, (3, 2, 7));
+        check_sgemm_packed(RowMajor, transa, transb, (4, 0, 7));
+        check_sgemm_packed(ColMajor, transa, transb, (16, 10, 0));
+    }
+}
@@ -1 +1,196 @@
+mod cache;
+#[cfg(feature = "cblas")]
+mod cblas;
+mod complex;
+mod context;
+mod epilogue;
+#[cfg(feature = "fortran")]
+mod fortran;
+mod half;
+mod l1s;
+mod l2;
+mod l3d;
 mod l3s;
+mod quant;
+mod safe;
+mod trmm;
+mod trsm;
+
+use crate::complex::Complex;
+use crate::gemm::{Diag, Layout, Side, Uplo};
+
+// Element type of `random_matrix`, drawn from [-0.5, 0.5) and so for both
+// parts of a complex one
+trait Random {
+    fn random<R: rand::Rng>(rng: &mut R) -> Self;
+}
+
+impl Random for f32 {
+    fn random<R: rand::Rng>(rng: &mut R) -> Self {
+        rng.gen::<f32>() - 0.5
+    }
+}
+
+impl Random for f64 {
+    fn random<R: rand::Rng>(rng: &mut R) -> Self {
+        rng.gen::<f64>() - 0.5
+    }
+}
+
+impl<F: Random> Random for Complex<F> {
+    fn random<R: rand::Rng>(rng: &mut R) -> Self {
+        let re = F::random(rng);
+        Complex::new(re, F::random(rng))
+    }
+}
+
+fn random_matrix<T: Random>(len: usize) -> Vec<T> {
+    let mut rng = rand::thread_rng();
+    (0..len).map(|_| T::random(&mut rng)).collect()
+}
+
+// Element [i, j] of a matrix with leading dimension ld
+fn index(layout: Layout, ld: usize, i: usize, j: usize) -> usize {
+    match layout {
+        Layout::ColMajor => i + j * ld,
+        Layout::RowMajor => i * ld + j,
+    }
+}
+
+// Well conditioned triangular k x k matrix as a dense row-wise one
+fn triangular(k: usize, uplo: Uplo, diag: Diag) -> Vec<f64> {
+    let r = random_matrix::<f64>(k * k);
+
+    (0..k * k)
+        .map(|ij| {
+            let (i, j) = (ij / k, ij % k);
+
+            if i == j {
+                if diag == Diag::Unit {
+                    1.0
+                } else {
+                    1.0 + r[ij].abs()
+                }
+            } else if (uplo == Uplo::Upper) == (i < j) {
+                r[ij] / k as f64
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+// One case of the trsm and trmm tests: the triangular A of order k on the
+// `side` of the m x n B
+struct TriangularCase {
+    layout: Layout,
+    side: Side,
+    uplo: Uplo,
+    trans: bool,
+    diag: Diag,
+    m: usize,
+    n: usize,
+    k: usize,
+    t: Vec<f64>,
+    lda: usize,
+    ldb: usize,
+}
+
+impl TriangularCase {
+    // Every layout, side, triangle, transposition and diagonal for every
+    // m x n shape
+    fn all(shapes: &[(usize, usize)]) -> Vec<TriangularCase> {
+        let mut cases = Vec::new();
+
+        for &layout in &[Layout::ColMajor, Layout::RowMajor] {
+            for &side in &[Side::Left, Side::Right] {
+                for &uplo in &[Uplo::Upper, Uplo::Lower] {
+                    for &trans in &[false, true] {
+                        for &diag in &[Diag::NonUnit, Diag::Unit] {
+                            for &(m, n) in shapes {
+                                let k = if side == Side::Left { m } else { n };
+                                let ldb = match layout {
+                                    Layout::ColMajor => m + 2,
+                                    Layout::RowMajor => n + 2,
+                                };
+
+                                cases.push(TriangularCase {
+                                    layout,
+                                    side,
+                                    uplo,
+                                    trans,
+                                    diag,
+                                    m,
+                                    n,
+                                    k,
+                                    t: triangular(k, uplo, diag),
+                                    lda: k + 3,
+                                    ldb,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        cases
+    }
+
+    // A as stored, NaN where only the stored triangle without a unit
+    // diagonal is read
+    fn a(&self) -> Vec<f64> {
+        let k = self.k;
+        let mut a = vec![f64::NAN; self.lda * k];
+
+        for i in 0..k {
+            for j in 0..k {
+                let unread = (i == j && self.diag == Diag::Unit)
+                    || (i != j && (self.uplo == Uplo::Upper) != (i < j));
+
+                if !unread {
+                    a[index(self.layout, self.lda, i, j)] = self.t[i * k + j];
+                }
+            }
+        }
+
+        a
+    }
+
+    // Random B, which fits either side
+    fn b(&self) -> Vec<f64> {
+        random_matrix::<f64>(self.ldb * (self.m + self.n))
+    }
+
+    // Element [i, j] of op(A) * X on the left or X * op(A) on the right
+    fn product<X: Fn(usize, usize) -> f64>(&self, i: usize, j: usize, x: X) -> f64 {
+        let k = self.k;
+        let op = |i: usize, j: usize| {
+            if self.trans {
+                self.t[j * k + i]
+            } else {
+                self.t[i * k + j]
+            }
+        };
+
+        match self.side {
+            Side::Left => (0..k).map(|p| op(i, p) * x(p, j)).sum(),
+            Side::Right => (0..k).map(|p| x(i, p) * op(p, j)).sum(),
+        }
+    }
+}
+
+impl std::fmt::Debug for TriangularCase {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} {:?} {:?} {} {:?} {:?}",
+            self.layout,
+            self.side,
+            self.uplo,
+            self.trans,
+            self.diag,
+            (self.m, self.n)
+        )
+    }
+}
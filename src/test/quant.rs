@@ -0,0 +1,277 @@
+use super::index;
+use crate::dim::*;
+use crate::executor::{DefaultExecutor, RayonExecutor};
+use crate::gemm::Layout;
+use crate::kernel::generic::GenericKernel;
+use crate::quant::{requantize, Scale};
+
+const SHAPES: [(usize, usize, usize); 3] = [(19, 13, 37), (8, 6, 4), (33, 20, 1100)];
+
+fn random_bytes(len: usize) -> Vec<u8> {
+    use rand::Rng;
+
+    let mut rng = rand::thread_rng();
+    (0..len).map(|_| rng.gen()).collect()
+}
+
+// Index of op(X)[row, col] of X stored with leading dimension `ld`
+fn op_index(layout: Layout, trans: bool, ld: usize, row: usize, col: usize) -> usize {
+    if trans {
+        index(layout, ld, col, row)
+    } else {
+        index(layout, ld, row, col)
+    }
+}
+
+// Leading dimension of an op(X) of `rows` x `cols`, padded to catch strides
+fn leading(layout: Layout, trans: bool, rows: usize, cols: usize) -> usize {
+    match (layout, trans) {
+        (Layout::ColMajor, false) | (Layout::RowMajor, true) => rows + 3,
+        _ => cols + 3,
+    }
+}
+
+struct Problem {
+    layout: Layout,
+    transa: bool,
+    transb: bool,
+    m: usize,
+    n: usize,
+    k: usize,
+    a: Vec<u8>,
+    lda: usize,
+    a_zero: u8,
+    b: Vec<i8>,
+    ldb: usize,
+    b_zero: i8,
+    ldc: usize,
+}
+
+impl Problem {
+    fn new(layout: Layout, transa: bool, transb: bool, (m, n, k): (usize, usize, usize)) -> Self {
+        let lda = leading(layout, transa, m, k);
+        let ldb = leading(layout, transb, k, n);
+        let ldc = leading(layout, false, m, n);
+
+        Problem {
+            layout,
+            transa,
+            transb,
+            m,
+            n,
+            k,
+            a: random_bytes(lda * (m + k)),
+            lda,
+            a_zero: random_bytes(1)[0],
+            b: random_bytes(ldb * (k + n))
+                .into_iter()
+                .map(|x| x as i8)
+                .collect(),
+            ldb,
+            b_zero: random_bytes(1)[0] as i8,
+            ldc,
+        }
+    }
+
+    fn c_index(&self, i: usize, j: usize) -> usize {
+        index(self.layout, self.ldc, i, j)
+    }
+
+    fn reference(&self) -> Vec<i32> {
+        let mut c = vec![0; self.ldc * (self.m + self.n)];
+
+        for j in 0..self.n {
+            for i in 0..self.m {
+                let mut acc = 0;
+                for p in 0..self.k {
+                    let a = self.a[op_index(self.layout, self.transa, self.lda, i, p)] as i32;
+                    let b = self.b[op_index(self.layout, self.transb, self.ldb, p, j)] as i32;
+
+                    acc += (a - self.a_zero as i32) * (b - self.b_zero as i32);
+                }
+
+                c[self.c_index(i, j)] = acc;
+            }
+        }
+
+        c
+    }
+
+    fn check(&self, c: &[i32], cref: &[i32]) {
+        for j in 0..self.n {
+            for i in 0..self.m {
+                let idx = self.c_index(i, j);
+
+                assert_eq!(
+                    c[idx], cref[idx],
+                    "{:?} {} {} {}x{}x{}: c[{}, {}]",
+                    self.layout, self.transa, self.transb, self.m, self.n, self.k, i, j
+                );
+            }
+        }
+    }
+}
+
+fn problems() -> Vec<Problem> {
+    let mut problems = Vec::new();
+
+    for &shape in &SHAPES {
+        for &layout in &[Layout::ColMajor, Layout::RowMajor] {
+            for &(transa, transb) in &[(false, false), (false, true), (true, false), (true, true)] {
+                problems.push(Problem::new(layout, transa, transb, shape));
+            }
+        }
+    }
+
+    problems
+}
+
+#[test]
+fn test_qgemm() {
+    for p in problems() {
+        let cref = p.reference();
+        let mut c = vec![0; cref.len()];
+
+        unsafe {
+            crate::qgemm(
+                &RayonExecutor,
+                p.layout,
+                p.transa,
+                p.transb,
+                p.m,
+                p.n,
+                p.k,
+                p.a.as_ptr(),
+                p.lda,
+                p.a_zero,
+                p.b.as_ptr(),
+                p.ldb,
+                p.b_zero,
+                c.as_mut_ptr(),
+                p.ldc,
+            );
+        }
+
+        p.check(&c, &cref);
+
+        let mut c = vec![0; cref.len()];
+
+        unsafe {
+            crate::gemm::gemm_quantized::<_, GenericKernel, A8, A6>(
+                &DefaultExecutor,
+                p.layout,
+                p.transa,
+                p.transb,
+                p.m,
+                p.n,
+                p.k,
+                p.a.as_ptr(),
+                p.lda,
+                p.a_zero,
+                p.b.as_ptr(),
+                p.ldb,
+                p.b_zero,
+                c.as_mut_ptr(),
+                p.ldc,
+            );
+        }
+
+        p.check(&c, &cref);
+    }
+}
+
+#[test]
+fn test_qgemm_extremes() {
+    // every pair of products saturates i16
+    let (m, n, k) = (16, 12, 64);
+    let a = vec![255u8; m * k];
+    let b = vec![-128i8; k * n];
+    let mut c = vec![0; m * n];
+
+    unsafe {
+        crate::qgemm(
+            &RayonExecutor,
+            Layout::ColMajor,
+            false,
+            false,
+            m,
+            n,
+            k,
+            a.as_ptr(),
+            m,
+            0,
+            b.as_ptr(),
+            k,
+            0,
+            c.as_mut_ptr(),
+            m,
+        );
+    }
+
+    assert!(c.iter().all(|&x| x == 255 * -128 * k as i32));
+}
+
+#[test]
+fn test_requantize() {
+    assert_eq!(requantize(10, 0.25, 3), 6);
+    assert_eq!(requantize(-10, 0.25, 3), 0);
+    assert_eq!(requantize(-6, 0.25, 3), 1);
+    assert_eq!(requantize(1 << 30, 1.0, 0), 255);
+    assert_eq!(requantize(i32::MIN, 1.0, 255), 0);
+}
+
+#[test]
+fn test_qgemm_requantize() {
+    for p in problems() {
+        let cref = p.reference();
+
+        let channels: Vec<f32> = (0..p.n).map(|j| 1.0 / (200.0 + 37.0 * j as f32)).collect();
+
+        for &scale in &[Scale::Tensor(1.0 / 1024.0), Scale::Channel(&channels)] {
+            let mut c = vec![0u8; cref.len()];
+
+            unsafe {
+                crate::qgemm_requantize(
+                    &RayonExecutor,
+                    p.layout,
+                    p.transa,
+                    p.transb,
+                    p.m,
+                    p.n,
+                    p.k,
+                    p.a.as_ptr(),
+                    p.lda,
+                    p.a_zero,
+                    p.b.as_ptr(),
+                    p.ldb,
+                    p.b_zero,
+                    scale,
+                    128,
+                    c.as_mut_ptr(),
+                    p.ldc,
+                );
+            }
+
+            for j in 0..p.n {
+                for i in 0..p.m {
+                    let idx = p.c_index(i, j);
+
+                    assert_eq!(c[idx], requantize(cref[idx], scale.get(j), 128));
+                }
+            }
+        }
+    }
+}
+
+// Only sets the kernel the automatic choice picks anyway, so the other tests
+// keep running with the same one
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+#[test]
+fn test_qgemm_avx_needs_avx2() {
+    use crate::kernel::isa::{self, Isa};
+
+    let avx2 = isa::avx2() && isa::supported(Isa::Avx);
+
+    assert_eq!(crate::qgemm_set_isa(Isa::Avx).is_ok(), avx2);
+    assert_eq!(crate::qgemm_isa() == Isa::Avx, avx2);
+}
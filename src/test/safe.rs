@@ -0,0 +1,142 @@
+use crate::executor::DefaultExecutor;
+use crate::{GemmError, Operand};
+
+#[test]
+fn test_safe_sgemm() {
+    let (m, n, k) = (3, 2, 4);
+
+    let a: Vec<f32> = (0..m * k).map(|x| x as f32).collect();
+    let b: Vec<f32> = (0..k * n).map(|x| x as f32).collect();
+    let mut c = vec![1.0; m * n];
+
+    crate::safe::sgemm(
+        &DefaultExecutor,
+        crate::Layout::ColMajor,
+        false,
+        false,
+        m,
+        n,
+        k,
+        1.0,
+        &a,
+        m,
+        &b,
+        k,
+        1.0,
+        &mut c,
+        m,
+    )
+    .unwrap();
+
+    for j in 0..n {
+        for i in 0..m {
+            let expected: f32 = 1.0 + (0..k).map(|p| a[i + p * m] * b[p + j * k]).sum::<f32>();
+            assert_eq!(c[i + j * m], expected);
+        }
+    }
+}
+
+#[test]
+fn test_safe_sgemm_errors() {
+    let a = vec![0.0f32; 12];
+    let b = vec![0.0f32; 8];
+    let mut c = vec![0.0f32; 6];
+
+    let e = &DefaultExecutor;
+
+    assert_eq!(
+        crate::safe::sgemm(
+            e,
+            crate::Layout::ColMajor,
+            false,
+            false,
+            3,
+            2,
+            4,
+            1.0,
+            &a,
+            2,
+            &b,
+            4,
+            0.0,
+            &mut c,
+            3
+        ),
+        Err(GemmError::LeadingDimension {
+            operand: Operand::A,
+            ld: 2,
+            min: 3
+        })
+    );
+
+    assert_eq!(
+        crate::safe::sgemm(
+            e,
+            crate::Layout::ColMajor,
+            false,
+            true,
+            3,
+            2,
+            4,
+            1.0,
+            &a,
+            3,
+            &b,
+            3,
+            0.0,
+            &mut c,
+            3
+        ),
+        Err(GemmError::BufferTooSmall {
+            operand: Operand::B,
+            len: 8,
+            required: 11
+        })
+    );
+
+    assert_eq!(
+        crate::safe::sgemm(
+            e,
+            crate::Layout::RowMajor,
+            false,
+            false,
+            3,
+            2,
+            4,
+            1.0,
+            &a,
+            4,
+            &b,
+            2,
+            0.0,
+            &mut c,
+            3
+        ),
+        Err(GemmError::BufferTooSmall {
+            operand: Operand::C,
+            len: 6,
+            required: 8
+        })
+    );
+
+    assert_eq!(
+        crate::safe::dgemm(
+            e,
+            crate::Layout::ColMajor,
+            false,
+            false,
+            0,
+            0,
+            0,
+            1.0,
+            &[],
+            1,
+            &[],
+            1,
+            0.0,
+            &mut [],
+            1
+        ),
+        Ok(())
+    );
+}
@@ -0,0 +1,68 @@
+use super::{index, TriangularCase};
+use crate::dim::*;
+use crate::executor::RayonExecutor;
+use crate::kernel::generic::GenericKernel;
+
+// Larger sizes span several diagonal blocks and several MC blocks of gemm
+const SHAPES: [(usize, usize); 6] = [(0, 5), (5, 0), (1, 1), (37, 13), (150, 70), (300, 20)];
+
+macro_rules! test_trmm {
+    ($name: ident, $t: ident, $trmm: path, $eps: expr) => {
+        #[test]
+        fn $name() {
+            let e = RayonExecutor;
+
+            for case in TriangularCase::all(&SHAPES) {
+                let (layout, m, n, ldb) = (case.layout, case.m, case.n, case.ldb);
+                let a: Vec<$t> = case.a().into_iter().map(|x| x as $t).collect();
+                let b: Vec<$t> = case.b().into_iter().map(|x| x as $t).collect();
+
+                let alpha: $t = 1.5;
+                let mut c = b.clone();
+                unsafe {
+                    $trmm(
+                        &e,
+                        layout,
+                        case.side,
+                        case.uplo,
+                        case.trans,
+                        case.diag,
+                        m,
+                        n,
+                        alpha,
+                        a.as_ptr(),
+                        case.lda,
+                        c.as_mut_ptr(),
+                        ldb,
+                    );
+                }
+
+                for i in 0..m {
+                    for j in 0..n {
+                        let expected = alpha as f64
+                            * case.product(i, j, |i, j| b[index(layout, ldb, i, j)] as f64);
+                        let actual = c[index(layout, ldb, i, j)] as f64;
+
+                        assert!(
+                            (actual - expected).abs() <= $eps * (1.0 + expected.abs()),
+                            "{:?} at {:?}: {} != {}",
+                            case,
+                            (i, j),
+                            actual,
+                            expected
+                        );
+                    }
+                }
+            }
+        }
+    };
+}
+
+test_trmm!(test_strmm, f32, crate::strmm, 1e-4);
+test_trmm!(test_dtrmm, f64, crate::dtrmm, 1e-10);
+test_trmm!(
+    test_strmm_generic,
+    f32,
+    crate::trmm::trmm::<_, f32, GenericKernel, A16, A5>,
+    1e-4
+);
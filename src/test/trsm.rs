@@ -0,0 +1,67 @@
+use super::{index, TriangularCase};
+use crate::dim::*;
+use crate::executor::RayonExecutor;
+use crate::kernel::generic::GenericKernel;
+
+// Larger sizes span several diagonal blocks and several MC blocks of gemm
+const SHAPES: [(usize, usize); 6] = [(0, 5), (5, 0), (1, 1), (37, 13), (150, 70), (300, 20)];
+
+macro_rules! test_trsm {
+    ($name: ident, $t: ident, $trsm: path, $eps: expr) => {
+        #[test]
+        fn $name() {
+            let e = RayonExecutor;
+
+            for case in TriangularCase::all(&SHAPES) {
+                let (layout, m, n, ldb) = (case.layout, case.m, case.n, case.ldb);
+                let a: Vec<$t> = case.a().into_iter().map(|x| x as $t).collect();
+                let b: Vec<$t> = case.b().into_iter().map(|x| x as $t).collect();
+
+                let alpha: $t = 1.5;
+                let mut x = b.clone();
+                unsafe {
+                    $trsm(
+                        &e,
+                        layout,
+                        case.side,
+                        case.uplo,
+                        case.trans,
+                        case.diag,
+                        m,
+                        n,
+                        alpha,
+                        a.as_ptr(),
+                        case.lda,
+                        x.as_mut_ptr(),
+                        ldb,
+                    );
+                }
+
+                for i in 0..m {
+                    for j in 0..n {
+                        let actual = case.product(i, j, |i, j| x[index(layout, ldb, i, j)] as f64);
+                        let expected = (alpha * b[index(layout, ldb, i, j)]) as f64;
+
+                        assert!(
+                            (actual - expected).abs() <= $eps * (1.0 + expected.abs()),
+                            "{:?} at {:?}: {} != {}",
+                            case,
+                            (i, j),
+                            actual,
+                            expected
+                        );
+                    }
+                }
+            }
+        }
+    };
+}
+
+test_trsm!(test_strsm, f32, crate::strsm, 1e-3);
+test_trsm!(test_dtrsm, f64, crate::dtrsm, 1e-10);
+test_trsm!(
+    test_strsm_generic,
+    f32,
+    crate::trsm::trsm::<_, f32, GenericKernel, A16, A5>,
+    1e-3
+);
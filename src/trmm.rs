@@ -0,0 +1,230 @@
+use crate::aligned_alloc;
+use crate::dim::Dim;
+use crate::epilogue::NoEpilogue;
+use crate::executor::Executor;
+use crate::gemm::{gemm_blocked, packing_lens, scale_c, Diag, Layout, Side, Uplo};
+use crate::kernel::GemmKernel;
+use crate::matrix::{
+    ConstMatrix, ConstTransposedMatrix, Matrix, MatrixMut, MutMatrix, MutTransposedMatrix, Number,
+};
+
+// Order of the diagonal blocks of A, multiplied a panel of MR rows at a
+// time; the products with the blocks off the diagonal go through gemm_blocked
+const NB: usize = 64;
+
+/// B = alpha * op(A) * B (`Side::Left`) or B = alpha * B * op(A)
+/// (`Side::Right`) of a triangular A stored in `uplo` and the m x n B. A is
+/// m x m on the left and n x n on the right.
+///
+/// Only the triangle of A is packed and multiplied: a panel of a diagonal
+/// block is as deep as its rows of the triangle, zeros fill only its square
+/// on the diagonal.
+pub(crate) unsafe fn trmm<E, F, K, MR, NR>(
+    e: &E,
+    layout: Layout,
+    side: Side,
+    uplo: Uplo,
+    trans: bool,
+    diag: Diag,
+    m: usize,
+    n: usize,
+    alpha: F,
+    a: *const F,
+    lda: usize,
+    b: *mut F,
+    ldb: usize,
+) where
+    E: Executor,
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+{
+    // row-major B is column-major B^T = B^T * op(A)^T (op(A)^T * B^T), and
+    // the column-major view of A is A^T
+    let (side, uplo, m, n) = match layout {
+        Layout::ColMajor => (side, uplo, m, n),
+        Layout::RowMajor => (side.flip(), uplo.flip(), n, m),
+    };
+
+    let unit = diag == Diag::Unit;
+
+    // B * op(A) is computed as (op(A)^T * B^T)^T, the triangle of op(A) is
+    // the other one when transposed
+    let lower = (uplo == Uplo::Lower) != trans;
+
+    match (side, trans) {
+        (Side::Left, false) => trmm_left::<E, F, K, MR, NR, _, _>(
+            e,
+            lower,
+            unit,
+            m,
+            n,
+            alpha,
+            ConstMatrix::new(a, lda),
+            MutMatrix::new(b, ldb),
+        ),
+        (Side::Left, true) => trmm_left::<E, F, K, MR, NR, _, _>(
+            e,
+            lower,
+            unit,
+            m,
+            n,
+            alpha,
+            ConstTransposedMatrix::new(a, lda),
+            MutMatrix::new(b, ldb),
+        ),
+        (Side::Right, false) => trmm_left::<E, F, K, MR, NR, _, _>(
+            e,
+            !lower,
+            unit,
+            n,
+            m,
+            alpha,
+            ConstTransposedMatrix::new(a, lda),
+            MutTransposedMatrix::new(b, ldb),
+        ),
+        (Side::Right, true) => trmm_left::<E, F, K, MR, NR, _, _>(
+            e,
+            !lower,
+            unit,
+            n,
+            m,
+            alpha,
+            ConstMatrix::new(a, lda),
+            MutTransposedMatrix::new(b, ldb),
+        ),
+    }
+}
+
+// B = alpha * T * B of the m x m triangular T and the m x n B. A block of
+// rows of B is the product of the triangle of its diagonal block of T with
+// a copy of the block, one MR rows panel at a time, plus the product of the
+// rest of its rows of T with the rows of B not yet overwritten. The blocks
+// of T outside the triangle are never read. The gemms share one packing
+// workspace.
+unsafe fn trmm_left<E, F, K, MR, NR, T, B>(
+    e: &E,
+    lower: bool,
+    unit: bool,
+    m: usize,
+    n: usize,
+    alpha: F,
+    t: T,
+    b: B,
+) where
+    E: Executor,
+    F: Number,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+    T: Matrix<F>,
+    B: MatrixMut<F>,
+{
+    if m == 0 || n == 0 {
+        return;
+    }
+
+    if alpha == F::ZERO {
+        scale_c(m, n, F::ZERO, b);
+        return;
+    }
+
+    let mut panel = vec![F::ZERO; MR::DIM * NB];
+    let mut rows = vec![F::ZERO; NB * n];
+
+    // no gemm has more than NB rows or is deeper than m
+    let blocking = K::blocking();
+    let (pa_len, pb_len) = packing_lens::<F>(blocking, std::cmp::min(m, NB), n, m);
+    let workspace = aligned_alloc::Alloc::new((pa_len + pb_len) * std::mem::size_of::<F>());
+    let packed_a = workspace.ptr::<F>();
+    let packed_b = packed_a.add(pa_len);
+
+    // rows of B are overwritten after the last product reading them
+    let blocks: Vec<usize> = if lower {
+        (0..m).step_by(NB).rev().collect()
+    } else {
+        (0..m).step_by(NB).collect()
+    };
+
+    for p in blocks {
+        let p_b = std::cmp::min(m - p, NB);
+
+        let r = MutMatrix::new(rows.as_mut_ptr(), p_b);
+
+        for j in 0..n {
+            for i in 0..p_b {
+                r.set(j, i, b.get(j, p + i));
+            }
+        }
+
+        let c = b.sub(0, p);
+
+        for i0 in (0..p_b).step_by(MR::DIM) {
+            let mr_b = std::cmp::min(p_b - i0, MR::DIM);
+
+            // the columns of the panel in the triangle
+            let (k0, k1) = if lower { (0, i0 + mr_b) } else { (i0, p_b) };
+
+            let d = MutMatrix::new(panel.as_mut_ptr(), mr_b);
+
+            for k in k0..k1 {
+                for i in 0..mr_b {
+                    let row = i0 + i;
+                    let stored = if lower { row >= k } else { row <= k };
+
+                    let elem = if row == k && unit {
+                        F::ONE
+                    } else if stored {
+                        t.get(p + k, p + row)
+                    } else {
+                        F::ZERO
+                    };
+
+                    d.set(k - k0, i, elem);
+                }
+            }
+
+            gemm_blocked::<E, F, K, MR, NR, _, _, _, _>(
+                e,
+                mr_b,
+                n,
+                k1 - k0,
+                alpha,
+                d,
+                r.sub(0, k0),
+                F::ZERO,
+                c.sub(0, i0),
+                blocking,
+                packed_a,
+                packed_b,
+                NoEpilogue,
+            );
+        }
+
+        // the rest of the rows of the block in the triangle
+        let (start, len) = if lower {
+            (0, p)
+        } else {
+            (p + p_b, m - p - p_b)
+        };
+
+        if len > 0 {
+            gemm_blocked::<E, F, K, MR, NR, _, _, _, _>(
+                e,
+                p_b,
+                n,
+                len,
+                alpha,
+                t.sub(start, p),
+                b.sub(0, start),
+                F::ONE,
+                c,
+                blocking,
+                packed_a,
+                packed_b,
+                NoEpilogue,
+            );
+        }
+    }
+}
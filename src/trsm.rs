@@ -0,0 +1,229 @@
+use crate::aligned_alloc;
+use crate::dim::Dim;
+use crate::epilogue::NoEpilogue;
+use crate::executor::Executor;
+use crate::gemm::{gemm_blocked, packing_lens, scale_c, Diag, Layout, Side, Uplo};
+use crate::kernel::GemmKernel;
+use crate::matrix::{
+    ConstMatrix, ConstTransposedMatrix, Matrix, MatrixMut, MutMatrix, MutTransposedMatrix, Number,
+};
+use core::ops::{Div, Sub};
+
+// Order of the diagonal blocks of A solved by substitution, the products
+// with the blocks off the diagonal go through gemm_blocked
+const NB: usize = 64;
+
+/// Solves op(A) * X = alpha * B (`Side::Left`) or X * op(A) = alpha * B
+/// (`Side::Right`) of a triangular A stored in `uplo`, X overwrites the
+/// m x n B. A is m x m on the left and n x n on the right.
+pub(crate) unsafe fn trsm<E, F, K, MR, NR>(
+    e: &E,
+    layout: Layout,
+    side: Side,
+    uplo: Uplo,
+    trans: bool,
+    diag: Diag,
+    m: usize,
+    n: usize,
+    alpha: F,
+    a: *const F,
+    lda: usize,
+    b: *mut F,
+    ldb: usize,
+) where
+    E: Executor,
+    F: Number + Sub<Output = F> + Div<Output = F>,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+{
+    // row-major B is column-major B^T = X^T * op(A)^T (op(A)^T * X^T), and
+    // the column-major view of A is A^T
+    let (side, uplo, m, n) = match layout {
+        Layout::ColMajor => (side, uplo, m, n),
+        Layout::RowMajor => (side.flip(), uplo.flip(), n, m),
+    };
+
+    let unit = diag == Diag::Unit;
+
+    // X * op(A) = alpha * B is solved as op(A)^T * X^T = alpha * B^T, the
+    // triangle of op(A) is the other one when transposed
+    let lower = (uplo == Uplo::Lower) != trans;
+
+    match (side, trans) {
+        (Side::Left, false) => trsm_left::<E, F, K, MR, NR, _, _>(
+            e,
+            lower,
+            unit,
+            m,
+            n,
+            alpha,
+            ConstMatrix::new(a, lda),
+            MutMatrix::new(b, ldb),
+        ),
+        (Side::Left, true) => trsm_left::<E, F, K, MR, NR, _, _>(
+            e,
+            lower,
+            unit,
+            m,
+            n,
+            alpha,
+            ConstTransposedMatrix::new(a, lda),
+            MutMatrix::new(b, ldb),
+        ),
+        (Side::Right, false) => trsm_left::<E, F, K, MR, NR, _, _>(
+            e,
+            !lower,
+            unit,
+            n,
+            m,
+            alpha,
+            ConstTransposedMatrix::new(a, lda),
+            MutTransposedMatrix::new(b, ldb),
+        ),
+        (Side::Right, true) => trsm_left::<E, F, K, MR, NR, _, _>(
+            e,
+            !lower,
+            unit,
+            n,
+            m,
+            alpha,
+            ConstMatrix::new(a, lda),
+            MutTransposedMatrix::new(b, ldb),
+        ),
+    }
+}
+
+// T * X = alpha * B of the m x m triangular T and the m x n B. Every block
+// of rows of B is solved against the diagonal block of T, then subtracted
+// from the rows of B still to be solved with one gemm. The gemms share one
+// packing workspace, none of them is more than NB deep.
+unsafe fn trsm_left<E, F, K, MR, NR, T, B>(
+    e: &E,
+    lower: bool,
+    unit: bool,
+    m: usize,
+    n: usize,
+    alpha: F,
+    t: T,
+    b: B,
+) where
+    E: Executor,
+    F: Number + Sub<Output = F> + Div<Output = F>,
+    MR: Dim,
+    NR: Dim,
+    K: GemmKernel<F, MR, NR>,
+    T: Matrix<F>,
+    B: MatrixMut<F>,
+{
+    scale_c(m, n, alpha, b);
+
+    if m == 0 || n == 0 {
+        return;
+    }
+
+    let minus_one = F::ZERO - F::ONE;
+
+    let blocking = K::blocking();
+    let (pa_len, pb_len) = packing_lens::<F>(blocking, m, n, NB);
+    let workspace = aligned_alloc::Alloc::new((pa_len + pb_len) * std::mem::size_of::<F>());
+    let packed_a = workspace.ptr::<F>();
+    let packed_b = packed_a.add(pa_len);
+
+    if lower {
+        for p in (0..m).step_by(NB) {
+            let p_b = std::cmp::min(m - p, NB);
+            let below = p + p_b;
+
+            solve_block(e, true, unit, p_b, n, t.sub(p, p), b.sub(0, p));
+
+            gemm_blocked::<E, F, K, MR, NR, _, _, _, _>(
+                e,
+                m - below,
+                n,
+                p_b,
+                minus_one,
+                t.sub(p, below),
+                b.sub(0, p),
+                F::ONE,
+                b.sub(0, below),
+                blocking,
+                packed_a,
+                packed_b,
+                NoEpilogue,
+            );
+        }
+    } else {
+        // the blocks run from the bottom, the top one is the partial one
+        let mut end = m;
+
+        while end > 0 {
+            let p = end.saturating_sub(NB);
+
+            solve_block(e, false, unit, end - p, n, t.sub(p, p), b.sub(0, p));
+
+            gemm_blocked::<E, F, K, MR, NR, _, _, _, _>(
+                e,
+                p,
+                n,
+                end - p,
+                minus_one,
+                t.sub(p, 0),
+                b.sub(0, p),
+                F::ONE,
+                b,
+                blocking,
+                packed_a,
+                packed_b,
+                NoEpilogue,
+            );
+
+            end = p;
+        }
+    }
+}
+
+// Substitution of the m x m diagonal block T for the n columns of B, which
+// are split between the threads of `e`
+unsafe fn solve_block<E, F, T, B>(e: &E, lower: bool, unit: bool, m: usize, n: usize, t: T, b: B)
+where
+    E: Executor,
+    F: Number + Sub<Output = F> + Div<Output = F>,
+    T: Matrix<F>,
+    B: MatrixMut<F>,
+{
+    let step = n.div_ceil(e.num_threads());
+    let parts = n.div_ceil(step);
+
+    e.execute(0, parts, 1, move |part| {
+        let start = part * step;
+        let end = std::cmp::min(n, start + step);
+
+        for j in start..end {
+            let solve = |i: usize, others: std::ops::Range<usize>| {
+                let mut x = b.get(j, i);
+                for k in others {
+                    x = x - t.get(k, i) * b.get(j, k);
+                }
+
+                if !unit {
+                    x = x / t.get(i, i);
+                }
+
+                b.set(j, i, x);
+            };
+
+            if lower {
+                for i in 0..m {
+                    solve(i, 0..i);
+                }
+            } else {
+                for i in (0..m).rev() {
+                    solve(i, i + 1..m);
+                }
+            }
+        }
+    });
+
+    e.synchronize();
+}
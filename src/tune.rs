@@ -0,0 +1,417 @@
+//! Empirical tuning of the kernel and the blocking on the current machine.
+//!
+//! `tune_sgemm`/`tune_dgemm` time every available kernel with blockings
+//! around the cache model on a set of shapes and switch to the fastest one.
+//! `save` writes the results to the tuning file, keyed by the CPU model and
+//! the kernel, which later runs apply with `apply_stored` at startup. Gemm
+//! calls never read the file themselves.
+
+use crate::error::GemmError;
+use crate::executor::Executor;
+use crate::kernel::isa::Isa;
+use crate::kernel::{cache, Blocking};
+use crate::matrix::Number;
+use crate::Layout;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Shapes `(m, n, k)` timed when no others are given
+pub const SHAPES: &[(usize, usize, usize)] = &[
+    (512, 512, 512),
+    (1024, 1024, 256),
+    (2048, 128, 512),
+    (128, 2048, 512),
+];
+
+const REPEATS: usize = 3;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tuned {
+    pub routine: String,
+    pub isa: Isa,
+    pub blocking: Blocking,
+    pub gflops: f64,
+}
+
+struct Routine {
+    name: &'static str,
+    kernels: &'static [Isa],
+    mr: usize,
+    nr: usize,
+    default: Blocking,
+    set_isa: fn(Isa) -> Result<(), GemmError>,
+    set_blocking: fn(Blocking) -> Result<(), GemmError>,
+}
+
+/// Times the f32 kernels and switches sgemm to the fastest kernel and
+/// blocking. Returns the best blocking of every kernel, fastest first.
+///
+/// The kernel and blocking of sgemm change while tuning, so it should not
+/// run next to other sgemm calls which are timed.
+pub fn tune_sgemm<E: Executor>(e: &E, shapes: &[(usize, usize, usize)]) -> Vec<Tuned> {
+    tune::<f32, _>(&sgemm_routine(), shapes, |m, n, k, a, b, c| unsafe {
+        crate::sgemm(
+            e,
+            Layout::ColMajor,
+            false,
+            false,
+            m,
+            n,
+            k,
+            1.0,
+            a.as_ptr(),
+            m,
+            b.as_ptr(),
+            k,
+            0.0,
+            c.as_mut_ptr(),
+            m,
+        )
+    })
+}
+
+/// Same as `tune_sgemm` for the f64 kernels.
+pub fn tune_dgemm<E: Executor>(e: &E, shapes: &[(usize, usize, usize)]) -> Vec<Tuned> {
+    tune::<f64, _>(&dgemm_routine(), shapes, |m, n, k, a, b, c| unsafe {
+        crate::dgemm(
+            e,
+            Layout::ColMajor,
+            false,
+            false,
+            m,
+            n,
+            k,
+            1.0,
+            a.as_ptr(),
+            m,
+            b.as_ptr(),
+            k,
+            0.0,
+            c.as_mut_ptr(),
+            m,
+        )
+    })
+}
+
+fn sgemm_routine() -> Routine {
+    use crate::kernel::params::single::*;
+
+    Routine {
+        name: "sgemm",
+        kernels: KERNELS,
+        mr: MR,
+        nr: NR,
+        default: Blocking {
+            mc: MC,
+            kc: KC,
+            nc: NC,
+        },
+        set_isa,
+        set_blocking,
+    }
+}
+
+fn dgemm_routine() -> Routine {
+    use crate::kernel::params::double::*;
+
+    Routine {
+        name: "dgemm",
+        kernels: KERNELS,
+        mr: MR,
+        nr: NR,
+        default: Blocking {
+            mc: MC,
+            kc: KC,
+            nc: NC,
+        },
+        set_isa,
+        set_blocking,
+    }
+}
+
+fn tune<F: Number, G>(routine: &Routine, shapes: &[(usize, usize, usize)], gemm: G) -> Vec<Tuned>
+where
+    G: Fn(usize, usize, usize, &[F], &[F], &mut [F]),
+{
+    let mut buffers: Vec<_> = shapes
+        .iter()
+        .map(|&(m, n, k)| {
+            (
+                vec![F::ONE; m * k],
+                vec![F::ONE; k * n],
+                vec![F::ZERO; m * n],
+            )
+        })
+        .collect();
+
+    let model = cache::blocking::<F>(&cache::detect(), routine.mr, routine.nr, routine.default);
+    let candidates = candidates::<F>(model, routine.mr, routine.nr);
+
+    let mut tuned = Vec::new();
+
+    for &isa in routine.kernels {
+        if (routine.set_isa)(isa).is_err() {
+            continue;
+        }
+
+        let mut best: Option<Tuned> = None;
+
+        for &blocking in &candidates {
+            (routine.set_blocking)(blocking).unwrap();
+
+            let mut flops = 0.0;
+            let mut time = Duration::default();
+
+            for (&(m, n, k), (a, b, c)) in shapes.iter().zip(buffers.iter_mut()) {
+                // the first run warms up the caches and the thread pool
+                gemm(m, n, k, a, b, c);
+
+                let fastest = (0..REPEATS)
+                    .map(|_| {
+                        let start = Instant::now();
+                        gemm(m, n, k, a, b, c);
+                        start.elapsed()
+                    })
+                    .min()
+                    .unwrap_or_default();
+
+                flops += 2.0 * m as f64 * n as f64 * k as f64;
+                time += fastest;
+            }
+
+            let gflops = flops / time.as_secs_f64().max(1e-9) / 1e9;
+
+            if best.as_ref().is_none_or(|best| gflops > best.gflops) {
+                best = Some(Tuned {
+                    routine: routine.name.to_string(),
+                    isa,
+                    blocking,
+                    gflops,
+                });
+            }
+        }
+
+        tuned.extend(best);
+    }
+
+    tuned.sort_by(|x, y| y.gflops.total_cmp(&x.gflops));
+
+    if let Some(fastest) = tuned.first() {
+        (routine.set_isa)(fastest.isa).unwrap();
+        (routine.set_blocking)(fastest.blocking).unwrap();
+    }
+
+    tuned
+}
+
+// KC and MC around the cache model, NC is bound by L3 and rarely matters
+fn candidates<F>(model: Blocking, mr: usize, nr: usize) -> Vec<Blocking> {
+    let kc_align = std::cmp::max(32 / std::mem::size_of::<F>(), 1);
+    let mut candidates = Vec::new();
+
+    for &(kc_num, kc_den) in &[(1, 2), (3, 4), (1, 1), (5, 4), (3, 2)] {
+        for &(mc_num, mc_den) in &[(1, 2), (1, 1), (3, 2)] {
+            let kc = model.kc * kc_num / kc_den;
+            let mc = model.mc * mc_num / mc_den;
+
+            let blocking = Blocking {
+                mc: mc - mc % mr,
+                kc: kc - kc % kc_align,
+                nc: model.nc,
+            };
+
+            if blocking.validate::<F>(mr, nr).is_ok() && !candidates.contains(&blocking) {
+                candidates.push(blocking);
+            }
+        }
+    }
+
+    if candidates.is_empty() {
+        candidates.push(model);
+    }
+
+    candidates
+}
+
+/// `$GEMM_TUNING_FILE`, otherwise `gemm/tuning` in the user cache directory
+pub fn tuning_file() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("GEMM_TUNING_FILE") {
+        return Some(PathBuf::from(path));
+    }
+
+    let cache = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+
+    Some(cache.join("gemm").join("tuning"))
+}
+
+/// Stores the results for the current CPU in `path`, replacing the earlier
+/// results of the same kernels and keeping all the others.
+pub fn save(path: &Path, tuned: &[Tuned]) -> io::Result<()> {
+    let cpu = cpu_model();
+
+    let mut lines: Vec<String> = match fs::read_to_string(path) {
+        Ok(text) => text
+            .lines()
+            .filter(|line| match parse(line) {
+                Some((model, old)) => {
+                    model != cpu
+                        || !tuned
+                            .iter()
+                            .any(|new| new.routine == old.routine && new.isa == old.isa)
+                }
+                None => false,
+            })
+            .map(String::from)
+            .collect(),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(err),
+    };
+
+    lines.extend(tuned.iter().map(|t| {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            cpu,
+            t.routine,
+            t.isa.name(),
+            t.blocking.mc,
+            t.blocking.kc,
+            t.blocking.nc,
+            t.gflops
+        )
+    }));
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    fs::write(path, lines.join("\n") + "\n")
+}
+
+/// Results for the current CPU stored in `path`
+pub fn load(path: &Path) -> io::Result<Vec<Tuned>> {
+    let cpu = cpu_model();
+    let text = fs::read_to_string(path)?;
+
+    Ok(text
+        .lines()
+        .filter_map(parse)
+        .filter(|(model, _)| *model == cpu)
+        .map(|(_, tuned)| tuned)
+        .collect())
+}
+
+// Lines are "cpu model, routine, kernel, MC, KC, NC, GFLOPS" separated by tabs
+fn parse(line: &str) -> Option<(&str, Tuned)> {
+    let mut fields = line.split('\t');
+
+    let model = fields.next()?;
+    let routine = fields.next()?.to_string();
+    let isa = Isa::from_name(fields.next()?)?;
+    let mc = fields.next()?.parse().ok()?;
+    let kc = fields.next()?.parse().ok()?;
+    let nc = fields.next()?.parse().ok()?;
+    let gflops = fields.next()?.parse().ok()?;
+
+    Some((
+        model,
+        Tuned {
+            routine,
+            isa,
+            blocking: Blocking { mc, kc, nc },
+            gflops,
+        },
+    ))
+}
+
+/// Switches sgemm and dgemm to the fastest supported kernel of the tuning
+/// file and its blocking, and returns the results applied. A missing file
+/// applies nothing.
+pub fn apply_stored() -> io::Result<Vec<Tuned>> {
+    let stored = match tuning_file().map(|path| load(&path)) {
+        Some(Ok(stored)) => stored,
+        Some(Err(err)) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+        _ => return Ok(Vec::new()),
+    };
+
+    let mut applied = Vec::new();
+
+    for routine in &[sgemm_routine(), dgemm_routine()] {
+        let fastest = stored
+            .iter()
+            .filter(|tuned| {
+                tuned.routine == routine.name
+                    && routine.kernels.contains(&tuned.isa)
+                    && crate::kernel::isa::supported(tuned.isa)
+            })
+            .max_by(|x, y| x.gflops.total_cmp(&y.gflops));
+
+        // a blocking the kernel rejects leaves the routine unchanged
+        if let Some(tuned) = fastest {
+            if (routine.set_blocking)(tuned.blocking).is_ok() {
+                (routine.set_isa)(tuned.isa).unwrap();
+                applied.push(tuned.clone());
+            }
+        }
+    }
+
+    Ok(applied)
+}
+
+/// Brand string of the CPU, the key of the tuning file
+pub fn cpu_model() -> String {
+    let model = brand()
+        .or_else(|| {
+            let cpuinfo = fs::read_to_string("/proc/cpuinfo").ok()?;
+            cpuinfo
+                .lines()
+                .find(|line| line.starts_with("model name"))
+                .and_then(|line| line.split(':').nth(1))
+                .map(String::from)
+        })
+        .unwrap_or_default();
+
+    let model = model.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if model.is_empty() {
+        String::from("unknown")
+    } else {
+        model
+    }
+}
+
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+fn brand() -> Option<String> {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::__cpuid;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::__cpuid;
+
+    // the intrinsics are safe on newer compilers
+    #[allow(unused_unsafe)]
+    unsafe {
+        if __cpuid(0x8000_0000).eax < 0x8000_0004 {
+            return None;
+        }
+
+        let mut bytes = Vec::with_capacity(48);
+        for leaf in 0x8000_0002..=0x8000_0004 {
+            let r = __cpuid(leaf);
+            for reg in &[r.eax, r.ebx, r.ecx, r.edx] {
+                bytes.extend_from_slice(&reg.to_le_bytes());
+            }
+        }
+
+        let brand = String::from_utf8_lossy(&bytes);
+        Some(brand.trim_end_matches('\0').to_string())
+    }
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "x86")))]
+fn brand() -> Option<String> {
+    None
+}
@@ -0,0 +1,57 @@
+use crate::complex::Complex;
+use crate::dim::*;
+use crate::error::GemmError;
+use crate::executor::Executor;
+use crate::gemm::{Layout, Transpose};
+#[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+use crate::kernel::avx::AvxKernel;
+use crate::kernel::generic::GenericKernel;
+use crate::kernel::isa::Isa;
+use crate::kernel::{params, Blocking};
+
+pub unsafe fn zgemm<E: Executor>(
+    e: &E,
+    layout: Layout,
+    transa: Transpose,
+    transb: Transpose,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: Complex<f64>,
+    a: *const Complex<f64>,
+    lda: usize,
+    b: *const Complex<f64>,
+    ldb: usize,
+    beta: Complex<f64>,
+    c: *mut Complex<f64>,
+    ldc: usize,
+) {
+    match params::complex_double::isa() {
+        #[cfg(any(target_arch = "x86_64", target_arch = "x86"))]
+        Isa::Avx => {
+            crate::gemm::gemm_conj::<E, Complex<f64>, AvxKernel<Complex<f64>, GenericKernel>, A4, A4>(
+                e, layout, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc,
+            )
+        }
+
+        _ => crate::gemm::gemm_conj::<E, Complex<f64>, GenericKernel, A4, A4>(
+            e, layout, transa, transb, m, n, k, alpha, a, lda, b, ldb, beta, c, ldc,
+        ),
+    }
+}
+
+pub fn zgemm_blocking() -> Blocking {
+    params::complex_double::blocking()
+}
+
+pub fn zgemm_set_blocking(blocking: Blocking) -> Result<(), GemmError> {
+    params::complex_double::set_blocking(blocking)
+}
+
+pub fn zgemm_isa() -> Isa {
+    params::complex_double::isa()
+}
+
+pub fn zgemm_set_isa(isa: Isa) -> Result<(), GemmError> {
+    params::complex_double::set_isa(isa)
+}
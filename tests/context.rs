@@ -0,0 +1,77 @@
+// Counts the allocations of the test thread, so this runs in its own test
+// binary with its own global allocator
+
+use gemm::executor::DefaultExecutor;
+use gemm::{GemmContext, Layout};
+use std::alloc::{GlobalAlloc, Layout as AllocLayout, System};
+use std::cell::Cell;
+
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: AllocLayout) -> *mut u8 {
+        ALLOCATIONS.with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: AllocLayout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: AllocLayout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|n| n.set(n.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(|n| n.get())
+}
+
+#[test]
+fn test_sgemm_with_context_does_not_allocate() {
+    let (m, n, k) = (70, 45, 300);
+
+    let a = vec![1.0f32; m * k];
+    let b = vec![2.0f32; k * n];
+    let mut c = vec![0.0f32; m * n];
+
+    let mut ctx = GemmContext::new(gemm::sgemm_workspace_size(Layout::ColMajor, m, n, k));
+    let gemm = |ctx: &mut GemmContext<f32>, c: &mut [f32]| unsafe {
+        gemm::sgemm_with_context(
+            ctx,
+            &DefaultExecutor,
+            Layout::ColMajor,
+            false,
+            false,
+            m,
+            n,
+            k,
+            1.0,
+            a.as_ptr(),
+            m,
+            b.as_ptr(),
+            k,
+            0.0,
+            c.as_mut_ptr(),
+            m,
+        )
+        .unwrap()
+    };
+
+    // the first call may set up the kernel and blocking for the process
+    gemm(&mut ctx, &mut c);
+
+    let before = allocations();
+    gemm(&mut ctx, &mut c);
+    assert_eq!(allocations(), before);
+
+    assert!(c.iter().all(|&x| x == 600.0));
+}
@@ -0,0 +1,95 @@
+// Forcing a kernel is process-wide, so this runs in its own test binary
+// where no other gemm test can observe it
+
+use gemm::executor::DefaultExecutor;
+use gemm::kernel::generic;
+use gemm::kernel::isa::Isa;
+use gemm::Layout;
+
+#[test]
+fn test_gemv_follows_forced_isa() {
+    let (m, n) = (67, 45);
+
+    let a: Vec<f64> = (0..m * n)
+        .map(|x| ((x * 37) % 101) as f64 / 101.0 - 0.5)
+        .collect();
+    let x: Vec<f64> = (0..m)
+        .map(|x| ((x * 13) % 29) as f64 / 29.0 - 0.5)
+        .collect();
+    let y: Vec<f64> = (0..m).map(|x| ((x * 7) % 17) as f64 / 17.0 - 0.5).collect();
+
+    let (sa, sx): (Vec<f32>, Vec<f32>) = (
+        a.iter().map(|&v| v as f32).collect(),
+        x.iter().map(|&v| v as f32).collect(),
+    );
+    let sy: Vec<f32> = y.iter().map(|&v| v as f32).collect();
+
+    let (single, double) = (gemm::sgemm_isa(), gemm::dgemm_isa());
+    gemm::sgemm_set_isa(Isa::Generic).unwrap();
+    gemm::dgemm_set_isa(Isa::Generic).unwrap();
+
+    // the generic kernels round the same way whatever the CPU
+    let (mut d0, mut d1) = (y.clone(), y.clone());
+    let (mut s0, mut s1) = (sy.clone(), sy.clone());
+    unsafe {
+        gemm::dgemv(
+            &DefaultExecutor,
+            Layout::ColMajor,
+            false,
+            m,
+            n,
+            0.5,
+            a.as_ptr(),
+            m,
+            x.as_ptr(),
+            1,
+            2.0,
+            d0.as_mut_ptr(),
+            1,
+        );
+        generic::l2d::dgemv_n(
+            m,
+            n,
+            0.5,
+            a.as_ptr(),
+            m,
+            x.as_ptr(),
+            1,
+            2.0,
+            d1.as_mut_ptr(),
+        );
+
+        gemm::sgemv(
+            &DefaultExecutor,
+            Layout::ColMajor,
+            false,
+            m,
+            n,
+            0.5,
+            sa.as_ptr(),
+            m,
+            sx.as_ptr(),
+            1,
+            2.0,
+            s0.as_mut_ptr(),
+            1,
+        );
+        generic::l2s::sgemv_n(
+            m,
+            n,
+            0.5,
+            sa.as_ptr(),
+            m,
+            sx.as_ptr(),
+            1,
+            2.0,
+            s1.as_mut_ptr(),
+        );
+    }
+
+    gemm::sgemm_set_isa(single).unwrap();
+    gemm::dgemm_set_isa(double).unwrap();
+
+    assert_eq!(d0, d1);
+    assert_eq!(s0, s1);
+}
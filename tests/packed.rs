@@ -0,0 +1,71 @@
+// Changing the blocking is process-wide, so this runs in its own test binary
+// where no other gemm test can observe it
+
+use gemm::executor::DefaultExecutor;
+use gemm::{Blocking, Layout};
+
+#[test]
+fn test_sgemm_packed_after_set_blocking() {
+    let blocking = gemm::sgemm_blocking();
+    let (m, n, k) = (37, 11, 300);
+
+    let a: Vec<f32> = (0..m * k).map(|x| (x % 7) as f32 - 3.0).collect();
+    let b: Vec<f32> = (0..k * n).map(|x| (x % 5) as f32 - 2.0).collect();
+
+    // column-major, so element (i, j) of C is sum over p of a[i + p*m] * b[p + j*k]
+    let mut expected = vec![0.0f32; m * n];
+    for j in 0..n {
+        for i in 0..m {
+            expected[i + j * m] = (0..k).map(|p| a[i + p * m] * b[p + j * k]).sum();
+        }
+    }
+
+    let e = &DefaultExecutor;
+    gemm::sgemm_set_blocking(Blocking { kc: 64, ..blocking }).unwrap();
+
+    let packed_a = unsafe { gemm::sgemm_pack_a(e, Layout::ColMajor, false, m, k, a.as_ptr(), m) };
+    let packed_b = unsafe { gemm::sgemm_pack_b(e, Layout::ColMajor, false, k, n, b.as_ptr(), k) };
+
+    // the operands keep the KC they were packed with
+    gemm::sgemm_set_blocking(Blocking {
+        kc: 128,
+        ..blocking
+    })
+    .unwrap();
+
+    let mut ca = vec![0.0f32; m * n];
+    let mut cb = vec![0.0f32; m * n];
+    unsafe {
+        gemm::sgemm_packed_a(
+            e,
+            false,
+            n,
+            1.0,
+            &packed_a,
+            b.as_ptr(),
+            k,
+            0.0,
+            ca.as_mut_ptr(),
+            m,
+        )
+        .unwrap();
+        gemm::sgemm_packed_b(
+            e,
+            false,
+            m,
+            1.0,
+            a.as_ptr(),
+            m,
+            &packed_b,
+            0.0,
+            cb.as_mut_ptr(),
+            m,
+        )
+        .unwrap();
+    }
+
+    gemm::sgemm_set_blocking(blocking).unwrap();
+
+    assert_eq!(ca, expected);
+    assert_eq!(cb, expected);
+}
@@ -0,0 +1,44 @@
+// The tuner switches the process-wide sgemm kernel and blocking, so it runs
+// in its own test binary where no other gemm test can observe them
+
+use gemm::executor::DefaultExecutor;
+use gemm::tune;
+
+#[test]
+fn test_tune_sgemm() {
+    let isa = gemm::sgemm_isa();
+    let blocking = gemm::sgemm_blocking();
+
+    let tuned = tune::tune_sgemm(&DefaultExecutor, &[(48, 40, 64), (17, 33, 70)]);
+
+    assert!(!tuned.is_empty());
+    assert_eq!(gemm::sgemm_isa(), tuned[0].isa);
+    assert_eq!(gemm::sgemm_blocking(), tuned[0].blocking);
+
+    gemm::sgemm_set_isa(isa).unwrap();
+    gemm::sgemm_set_blocking(blocking).unwrap();
+
+    let path = std::env::temp_dir().join(format!("gemm-tuning-{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    tune::save(&path, &tuned).unwrap();
+    tune::save(&path, &tuned[..1]).unwrap();
+
+    let mut loaded = tune::load(&path).unwrap();
+
+    loaded.sort_by(|x, y| y.gflops.total_cmp(&x.gflops));
+    assert_eq!(loaded, tuned);
+
+    std::env::set_var("GEMM_TUNING_FILE", &path);
+    let applied = tune::apply_stored().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(applied, &tuned[..1]);
+    assert_eq!(gemm::sgemm_isa(), tuned[0].isa);
+    assert_eq!(gemm::sgemm_blocking(), tuned[0].blocking);
+
+    assert_eq!(tune::apply_stored().unwrap(), &[]);
+
+    gemm::sgemm_set_isa(isa).unwrap();
+    gemm::sgemm_set_blocking(blocking).unwrap();
+}